@@ -0,0 +1,495 @@
+//! Derive macro for [`web-thread`](https://docs.rs/web-thread)'s
+//! `MessageSend`. Use it through its re-export in `web_thread::web::message`.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Literal, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::token::Comma;
+use syn::{
+	parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Error, Field, Fields, Ident,
+	Index, Path,
+};
+
+/// Largest tuple `MessageSend` is implemented for.
+const TUPLE_LIMIT: usize = 12;
+
+/// Derives `MessageSend` for structs and enums.
+///
+/// Fields are annotated with how they should be sent:
+/// - `#[message(serialize)]`: with `SerializableWrapper`.
+/// - `#[message(transfer)]`: with `TransferableWrapper`.
+/// - `#[message(send)]`: with `SendWrapper`.
+///
+/// Fields without an annotation have to implement `MessageSend` themselves.
+///
+/// If `web-thread` is not reachable under `::web_thread`, the path to it can be
+/// set with `#[message(crate = path::to::web_thread)]` on the struct or enum.
+///
+/// Enums carry the index of their variant through `RawMessage::send`.
+#[proc_macro_derive(MessageSend, attributes(message))]
+pub fn message_send(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+
+	expand(&input)
+		.unwrap_or_else(Error::into_compile_error)
+		.into()
+}
+
+/// Generates the `MessageSend` implementation.
+fn expand(input: &DeriveInput) -> Result<TokenStream2, Error> {
+	let krate = crate_path(&input.attrs)?;
+	let message = quote! { #krate::web::message };
+
+	let (send, body) = match &input.data {
+		Data::Struct(data) => expand_struct(&data.fields, &message)?,
+		Data::Enum(data) => expand_enum(&input.ident, &data.variants, &message)?,
+		Data::Union(_) => {
+			return Err(Error::new(
+				input.ident.span(),
+				"`MessageSend` can't be derived for unions",
+			))
+		}
+	};
+
+	let ident = &input.ident;
+	let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+	Ok(quote! {
+		#[automatically_derived]
+		impl #impl_generics #message::MessageSend for #ident #type_generics #where_clause {
+			type Send = #send;
+
+			#body
+		}
+	})
+}
+
+/// Generates the `Send` type and methods for a struct.
+fn expand_struct(
+	fields: &Fields,
+	message: &TokenStream2,
+) -> Result<(TokenStream2, TokenStream2), Error> {
+	let fields = MessageFields::new(fields, message)?;
+
+	if fields.is_empty() {
+		let construct = fields.construct();
+
+		return Ok((
+			quote! { () },
+			quote! {
+				fn send<__WebThreadExtend: ::std::iter::Extend<#message::__internal::JsValue>>(
+					self,
+					_: &mut __WebThreadExtend,
+				) -> #message::RawMessage<Self::Send> {
+					#message::RawMessage {
+						serialize: ::std::option::Option::None,
+						send: ::std::option::Option::None,
+					}
+				}
+
+				fn receive(
+					_: ::std::option::Option<#message::__internal::JsValue>,
+					_: ::std::option::Option<Self::Send>,
+				) -> Self {
+					Self #construct
+				}
+			},
+		));
+	}
+
+	let tuple = fields.tuple_type();
+	let destructure = fields.construct();
+	let wrap = fields.wrap();
+	let unwrap = fields.unwrap();
+
+	Ok((
+		quote! { <#tuple as #message::MessageSend>::Send },
+		quote! {
+			fn send<__WebThreadExtend: ::std::iter::Extend<#message::__internal::JsValue>>(
+				self,
+				transfer: &mut __WebThreadExtend,
+			) -> #message::RawMessage<Self::Send> {
+				let Self #destructure = self;
+				<#tuple as #message::MessageSend>::send(#wrap, transfer)
+			}
+
+			fn receive(
+				serialized: ::std::option::Option<#message::__internal::JsValue>,
+				sent: ::std::option::Option<Self::Send>,
+			) -> Self {
+				let #unwrap = <#tuple as #message::MessageSend>::receive(serialized, sent);
+				Self #destructure
+			}
+		},
+	))
+}
+
+/// Generates the `Send` type and methods for an enum.
+fn expand_enum(
+	ident: &Ident,
+	variants: &Punctuated<syn::Variant, Comma>,
+	message: &TokenStream2,
+) -> Result<(TokenStream2, TokenStream2), Error> {
+	if variants.is_empty() {
+		return Err(Error::new(
+			ident.span(),
+			"`MessageSend` can't be derived for enums without variants",
+		));
+	}
+
+	let variants = variants
+		.iter()
+		.map(|variant| {
+			reject_attributes(&variant.attrs)?;
+			Ok((
+				&variant.ident,
+				MessageFields::new(&variant.fields, message)?,
+			))
+		})
+		.collect::<Result<Vec<_>, Error>>()?;
+
+	// Every variant with fields gets its own slot in `RawMessage::send`.
+	let slot_types: Vec<_> = variants
+		.iter()
+		.filter(|(_, fields)| !fields.is_empty())
+		.map(|(_, fields)| {
+			let tuple = fields.tuple_type();
+			quote! { ::std::option::Option<<#tuple as #message::MessageSend>::Send> }
+		})
+		.collect();
+	let slots_type = nest(&slot_types);
+	let slot_count = slot_types.len();
+
+	let mut slot = 0;
+	let (send_arms, receive_arms): (Vec<_>, Vec<_>) = variants
+		.iter()
+		.enumerate()
+		.map(|(index, (variant, fields))| {
+			if fields.is_empty() {
+				variant_arms(variant, fields, index, None, slot_count, message)
+			} else {
+				slot += 1;
+				variant_arms(variant, fields, index, Some(slot - 1), slot_count, message)
+			}
+		})
+		.unzip();
+
+	let (transfer, serialized, slots) = if slot_count == 0 {
+		(quote! { _ }, quote! { _ }, quote! { _ })
+	} else {
+		(quote! { transfer }, quote! { serialized }, quote! { slots })
+	};
+
+	Ok((
+		quote! { (::std::primitive::usize, #slots_type) },
+		quote! {
+			fn send<__WebThreadExtend: ::std::iter::Extend<#message::__internal::JsValue>>(
+				self,
+				#transfer: &mut __WebThreadExtend,
+			) -> #message::RawMessage<Self::Send> {
+				match self {
+					#(#send_arms)*
+				}
+			}
+
+			fn receive(
+				#serialized: ::std::option::Option<#message::__internal::JsValue>,
+				sent: ::std::option::Option<Self::Send>,
+			) -> Self {
+				let (index, #slots) = sent.expect("expected variant index");
+
+				match index {
+					#(#receive_arms)*
+					_ => ::std::unreachable!("unexpected variant index"),
+				}
+			}
+		},
+	))
+}
+
+/// Generates the `send()` and `receive()` match arms for an enum variant.
+/// `slot` is the position of the variant in `RawMessage::send`, if it has
+/// fields.
+fn variant_arms(
+	variant: &Ident,
+	fields: &MessageFields<'_>,
+	index: usize,
+	slot: Option<usize>,
+	slot_count: usize,
+	message: &TokenStream2,
+) -> (TokenStream2, TokenStream2) {
+	let index = Literal::usize_unsuffixed(index);
+	let construct = fields.construct();
+	let slots: Vec<_> = (0..slot_count)
+		.map(|current| {
+			if Some(current) == slot {
+				quote! { message.send }
+			} else {
+				quote! { ::std::option::Option::None }
+			}
+		})
+		.collect();
+	let slots = nest(&slots);
+
+	let Some(slot) = slot else {
+		return (
+			quote! {
+				Self::#variant #construct => #message::RawMessage {
+					serialize: ::std::option::Option::None,
+					send: ::std::option::Option::Some((#index, #slots)),
+				},
+			},
+			quote! {
+				#index => Self::#variant #construct,
+			},
+		);
+	};
+
+	let tuple = fields.tuple_type();
+	let wrap = fields.wrap();
+	let unwrap = fields.unwrap();
+	let access = access(slot_count, slot);
+
+	(
+		quote! {
+			Self::#variant #construct => {
+				let message = <#tuple as #message::MessageSend>::send(#wrap, transfer);
+
+				#message::RawMessage {
+					serialize: message.serialize,
+					send: ::std::option::Option::Some((#index, #slots)),
+				}
+			}
+		},
+		quote! {
+			#index => {
+				let #unwrap = <#tuple as #message::MessageSend>::receive(
+					serialized,
+					slots #(.#access)*,
+				);
+				Self::#variant #construct
+			}
+		},
+	)
+}
+
+/// How a field is sent.
+#[derive(Clone, Copy)]
+enum Kind {
+	/// `#[message(serialize)]`.
+	Serialize,
+	/// `#[message(transfer)]`.
+	Transfer,
+	/// `#[message(send)]`.
+	Send,
+	/// No annotation, the field implements `MessageSend` itself.
+	Message,
+}
+
+impl Kind {
+	/// Parses the `message` attribute of a [`Field`].
+	fn new(field: &Field) -> Result<Self, Error> {
+		let mut kind = None;
+
+		for attribute in &field.attrs {
+			if !attribute.path().is_ident("message") {
+				continue;
+			}
+
+			attribute.parse_nested_meta(|meta| {
+				let new = if meta.path.is_ident("serialize") {
+					Self::Serialize
+				} else if meta.path.is_ident("transfer") {
+					Self::Transfer
+				} else if meta.path.is_ident("send") {
+					Self::Send
+				} else {
+					return Err(meta.error("expected `serialize`, `transfer` or `send`"));
+				};
+
+				if kind.replace(new).is_some() {
+					Err(meta.error("found multiple `message` attributes"))
+				} else {
+					Ok(())
+				}
+			})?;
+		}
+
+		Ok(kind.unwrap_or(Self::Message))
+	}
+
+	/// Returns the wrapper type, if any.
+	fn wrapper(self, message: &TokenStream2) -> Option<TokenStream2> {
+		match self {
+			Self::Serialize => Some(quote! { #message::SerializableWrapper }),
+			Self::Transfer => Some(quote! { #message::TransferableWrapper }),
+			Self::Send => Some(quote! { #message::SendWrapper }),
+			Self::Message => None,
+		}
+	}
+}
+
+/// Fields of a struct or enum variant.
+struct MessageFields<'input> {
+	/// The original [`Fields`].
+	fields: &'input Fields,
+	/// How each field is sent.
+	kinds: Vec<Kind>,
+	/// Binding used for each field.
+	bindings: Vec<Ident>,
+	/// Path to `web_thread::web::message`.
+	message: &'input TokenStream2,
+}
+
+impl<'input> MessageFields<'input> {
+	/// Parses all field attributes.
+	fn new(fields: &'input Fields, message: &'input TokenStream2) -> Result<Self, Error> {
+		let kinds = fields.iter().map(Kind::new).collect::<Result<_, _>>()?;
+		let bindings = (0..fields.len())
+			.map(|index| format_ident!("field_{}", index))
+			.collect();
+
+		Ok(Self {
+			fields,
+			kinds,
+			bindings,
+			message,
+		})
+	}
+
+	/// Returns [`true`] if there are no fields.
+	fn is_empty(&self) -> bool {
+		self.fields.is_empty()
+	}
+
+	/// Returns the tuple type implementing `MessageSend` for these fields.
+	fn tuple_type(&self) -> TokenStream2 {
+		let types: Vec<_> = self
+			.fields
+			.iter()
+			.zip(&self.kinds)
+			.map(|(field, kind)| {
+				let ty = &field.ty;
+
+				if let Some(wrapper) = kind.wrapper(self.message) {
+					quote! { #wrapper<#ty> }
+				} else {
+					quote! { #ty }
+				}
+			})
+			.collect();
+
+		nest(&types)
+	}
+
+	/// Returns the expression wrapping all bindings into
+	/// [`Self::tuple_type()`].
+	fn wrap(&self) -> TokenStream2 {
+		let values: Vec<_> = self
+			.kinds
+			.iter()
+			.zip(&self.bindings)
+			.map(|(kind, binding)| {
+				if let Some(wrapper) = kind.wrapper(self.message) {
+					quote! { #wrapper(#binding) }
+				} else {
+					quote! { #binding }
+				}
+			})
+			.collect();
+
+		nest(&values)
+	}
+
+	/// Returns the pattern unwrapping [`Self::tuple_type()`] into all bindings.
+	fn unwrap(&self) -> TokenStream2 {
+		// Patterns look the same as expressions here.
+		self.wrap()
+	}
+
+	/// Returns the fields bound to their bindings, usable as a pattern and as
+	/// an expression.
+	fn construct(&self) -> TokenStream2 {
+		let bindings = &self.bindings;
+
+		match self.fields {
+			Fields::Named(_) => {
+				let members = self.fields.iter().map(|field| &field.ident);
+				quote! { { #(#members: #bindings),* } }
+			}
+			Fields::Unnamed(_) => quote! { (#(#bindings),*) },
+			Fields::Unit => quote! {},
+		}
+	}
+}
+
+/// Parses the `message` attributes of a struct or enum, returning the path to
+/// `web-thread`.
+fn crate_path(attributes: &[Attribute]) -> Result<TokenStream2, Error> {
+	let mut path = None;
+
+	for attribute in attributes {
+		if !attribute.path().is_ident("message") {
+			continue;
+		}
+
+		attribute.parse_nested_meta(|meta| {
+			if !meta.path.is_ident("crate") {
+				return Err(meta.error("expected `crate`"));
+			}
+
+			let new: Path = meta.value()?.parse()?;
+
+			if path.replace(new).is_some() {
+				Err(meta.error("found multiple `crate` attributes"))
+			} else {
+				Ok(())
+			}
+		})?;
+	}
+
+	let path: Path = path.unwrap_or_else(|| parse_quote! { ::web_thread });
+	Ok(quote! { #path })
+}
+
+/// Returns an error if `message` is used on an enum variant.
+fn reject_attributes(attributes: &[Attribute]) -> Result<(), Error> {
+	match attributes
+		.iter()
+		.find(|attribute| attribute.path().is_ident("message"))
+	{
+		Some(attribute) => Err(Error::new(
+			attribute.span(),
+			"`message` attributes are not supported on enum variants",
+		)),
+		None => Ok(()),
+	}
+}
+
+/// Puts `items` into a tuple, nesting them if they exceed [`TUPLE_LIMIT`].
+fn nest(items: &[TokenStream2]) -> TokenStream2 {
+	if items.len() <= TUPLE_LIMIT {
+		quote! { (#(#items,)*) }
+	} else {
+		let chunks: Vec<_> = items.chunks(TUPLE_LIMIT).map(nest).collect();
+		nest(&chunks)
+	}
+}
+
+/// Returns the field accesses to reach `index` in a tuple of `len` items
+/// created by [`nest()`].
+fn access(len: usize, index: usize) -> Vec<Index> {
+	if len <= TUPLE_LIMIT {
+		vec![Index::from(index)]
+	} else {
+		let chunks = (len + TUPLE_LIMIT - 1) / TUPLE_LIMIT;
+		let chunk = index / TUPLE_LIMIT;
+		let chunk_len = TUPLE_LIMIT.min(len - chunk * TUPLE_LIMIT);
+
+		let mut path = access(chunks, chunk);
+		path.extend(access(chunk_len, index % TUPLE_LIMIT));
+		path
+	}
+}
@@ -1,8 +1,6 @@
 //! TODO:
 //! - Re-factor builder implementation.
-//! - Add `MessageSend` macro.
 //! - Add `WorkletBuilder` (or use `Builder`).
-//! - Wrap `MessageChannel` into something safe.
 //! - Consider passing message into `Builder`.
 //! - Add README.
 //! - Consider moving some APIs into `web-thread-core/primitives`.
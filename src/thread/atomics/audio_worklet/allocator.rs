@@ -0,0 +1,109 @@
+//! Detecting allocations in
+//! [`ExtendAudioWorkletProcessor`](crate::web::audio_worklet::ExtendAudioWorkletProcessor)s.
+
+use std::any;
+use std::cell::Cell;
+
+use super::super::super::js;
+
+thread_local! {
+	/// Allocations recorded during the current call into a processor, [`None`]
+	/// if the current thread is not inside a processor.
+	static RECORD: Cell<Option<Record>> = const { Cell::new(None) };
+}
+
+/// Implementation for
+/// [`crate::web::audio_worklet::DetectingAllocator::alloc()`](std::alloc::GlobalAlloc::alloc).
+pub(in super::super::super) fn record_allocation(panic: bool) {
+	record(|record| {
+		record.allocations += 1;
+		record.panic |= panic;
+	});
+}
+
+/// Implementation for
+/// [`crate::web::audio_worklet::DetectingAllocator::dealloc()`](std::alloc::GlobalAlloc::dealloc).
+pub(in super::super::super) fn record_deallocation(panic: bool) {
+	record(|record| {
+		record.deallocations += 1;
+		record.panic |= panic;
+	});
+}
+
+/// Updates the current [`Record`] if the current thread is inside a
+/// processor.
+fn record(task: impl FnOnce(&mut Record)) {
+	// `try_with()` because allocations can happen while thread-local storage is
+	// being destroyed.
+	let _ = RECORD.try_with(|cell| {
+		if let Some(mut record) = cell.get() {
+			task(&mut record);
+			cell.set(Some(record));
+		}
+	});
+}
+
+/// Allocations recorded during a single call into a processor.
+#[derive(Clone, Copy, Default)]
+struct Record {
+	/// Amount of allocations.
+	allocations: usize,
+	/// Amount of deallocations.
+	deallocations: usize,
+	/// If the allocator requested to panic instead of logging.
+	panic: bool,
+}
+
+/// Detects allocations of a single node over its lifetime.
+#[derive(Debug)]
+pub(super) struct Detector {
+	/// Type name of the processor.
+	name: &'static str,
+	/// Total amount of allocations.
+	allocations: usize,
+	/// Total amount of deallocations.
+	deallocations: usize,
+}
+
+impl Detector {
+	/// Creates a new [`Detector`] for the processor `P`.
+	pub(super) fn new<P>() -> Self {
+		Self {
+			name: any::type_name::<P>(),
+			allocations: 0,
+			deallocations: 0,
+		}
+	}
+
+	/// Records allocations made during `task` and reports them.
+	pub(super) fn detect<R>(&mut self, task: impl FnOnce() -> R) -> R {
+		let previous = RECORD.with(|cell| cell.replace(Some(Record::default())));
+		let result = task();
+		let record = RECORD
+			.with(|cell| cell.replace(previous))
+			.expect("found no `Record`");
+
+		if record.allocations != 0 || record.deallocations != 0 {
+			self.allocations += record.allocations;
+			self.deallocations += record.deallocations;
+
+			let message = format!(
+				"processor `{}` allocated {} and deallocated {} times while processing, {} \
+				 allocations and {} deallocations in total for this node",
+				self.name,
+				record.allocations,
+				record.deallocations,
+				self.allocations,
+				self.deallocations,
+			);
+
+			if record.panic {
+				panic!("{message}");
+			} else {
+				js::console_warn(&message);
+			}
+		}
+
+		result
+	}
+}
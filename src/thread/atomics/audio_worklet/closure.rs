@@ -0,0 +1,158 @@
+//! Closure based [`ExtendAudioWorkletProcessor`].
+
+use std::any;
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::io::{self, Error};
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use web_sys::{AudioWorkletNode, AudioWorkletNodeOptions, AudioWorkletProcessor, BaseAudioContext};
+
+use super::register::AcknowledgeFuture;
+use crate::web::audio_worklet::{
+	AudioParameters, ExtendAudioWorkletProcessor, ProcessBuffers, ProcessBuffersFn,
+};
+
+/// Name [`FnProcessor`] is registered with in every audio worklet thread.
+const NAME: &str = "__web_thread_fn";
+
+/// Closure called by [`FnProcessor`].
+type ProcessFn =
+	Box<dyn FnMut(&[&[&[f32]]], &mut [&mut [&mut [f32]]], &AudioParameters<'_>) -> bool + Send>;
+
+/// [`ExtendAudioWorkletProcessor`] running the closure passed to
+/// [`crate::web::audio_worklet::BaseAudioContextExt::audio_worklet_node_from_fn()`].
+struct FnProcessor(Option<ProcessFn>);
+
+impl ExtendAudioWorkletProcessor for FnProcessor {
+	type Data = ProcessFn;
+
+	const PROCESS_BUFFERS: Option<ProcessBuffersFn<Self>> = Some(ProcessBuffersFn::new());
+
+	fn new(_: AudioWorkletProcessor, data: Option<Self::Data>, _: AudioWorkletNodeOptions) -> Self {
+		Self(data)
+	}
+}
+
+impl ProcessBuffers for FnProcessor {
+	fn process_buffers(
+		&mut self,
+		inputs: &[&[&[f32]]],
+		outputs: &mut [&mut [&mut [f32]]],
+		parameters: &AudioParameters<'_>,
+	) -> bool {
+		self.0
+			.as_mut()
+			.is_some_and(|process| process(inputs, outputs, parameters))
+	}
+}
+
+/// Registers [`FnProcessor`]. Has to be called once in every audio worklet
+/// thread.
+pub(super) fn register() {
+	super::register_processor::<FnProcessor>(NAME)
+		.expect("registering `FnProcessor` is not expected to fail");
+}
+
+/// Implementation for
+/// [`crate::web::audio_worklet::BaseAudioContextExt::audio_worklet_node_from_fn()`].
+pub(in super::super::super) fn audio_worklet_node_from_fn<F>(
+	context: &BaseAudioContext,
+	options: Option<&AudioWorkletNodeOptions>,
+	process: F,
+) -> AudioWorkletNodeFromFnFuture
+where
+	F: 'static + FnMut(&[&[&[f32]]], &mut [&mut [&mut [f32]]], &AudioParameters<'_>) -> bool + Send,
+{
+	// `FnProcessor` is registered when the audio worklet thread starts, so it is
+	// available as soon as the audio worklet has run any task.
+	match super::register::dispatch(context, |_| ()) {
+		Ok(acknowledge) => AudioWorkletNodeFromFnFuture(Some(State::Acknowledge {
+			acknowledge,
+			context: context.clone(),
+			options: options.cloned(),
+			process: Box::new(process),
+		})),
+		Err(error) => AudioWorkletNodeFromFnFuture::error(error),
+	}
+}
+
+/// Implementation for
+/// [`crate::web::audio_worklet::AudioWorkletNodeFromFnFuture`].
+#[derive(Debug)]
+pub(in super::super::super) struct AudioWorkletNodeFromFnFuture(Option<State>);
+
+/// State of [`AudioWorkletNodeFromFnFuture`].
+enum State {
+	/// Early error.
+	Error(Error),
+	/// Waiting for the audio worklet to be ready to construct [`FnProcessor`].
+	Acknowledge {
+		/// Waiting for the audio worklet to acknowledge that it has run a task.
+		acknowledge: AcknowledgeFuture,
+		/// The [`BaseAudioContext`] to create the [`AudioWorkletNode`] on.
+		context: BaseAudioContext,
+		/// Options to create the [`AudioWorkletNode`] with.
+		options: Option<AudioWorkletNodeOptions>,
+		/// Closure to pass to [`FnProcessor`].
+		process: ProcessFn,
+	},
+}
+
+impl Debug for State {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Error(error) => formatter.debug_tuple("Error").field(error).finish(),
+			Self::Acknowledge {
+				acknowledge,
+				context,
+				options,
+				process,
+			} => formatter
+				.debug_struct("Acknowledge")
+				.field("acknowledge", acknowledge)
+				.field("context", context)
+				.field("options", options)
+				.field("process", &any::type_name_of_val(process))
+				.finish(),
+		}
+	}
+}
+
+impl Future for AudioWorkletNodeFromFnFuture {
+	type Output = io::Result<AudioWorkletNode>;
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		let state = self.0.as_mut().expect("polled after completion");
+
+		if let State::Acknowledge { acknowledge, .. } = state {
+			let success = ready!(Pin::new(acknowledge).poll(cx));
+
+			if !success {
+				self.0.take();
+				return Poll::Ready(Err(Error::other("audio worklet panicked")));
+			}
+		}
+
+		match self.0.take().expect("found no state") {
+			State::Error(error) => Poll::Ready(Err(error)),
+			State::Acknowledge {
+				context,
+				options,
+				process,
+				..
+			} => Poll::Ready(
+				super::audio_worklet_node::<FnProcessor>(&context, NAME, process, options.as_ref())
+					.map_err(|error| error.error),
+			),
+		}
+	}
+}
+
+impl AudioWorkletNodeFromFnFuture {
+	/// Create a [`AudioWorkletNodeFromFnFuture`] that returns `error`.
+	pub(in super::super::super) const fn error(error: Error) -> Self {
+		Self(Some(State::Error(error)))
+	}
+}
@@ -1,10 +1,16 @@
 //! Bindings to the JS API.
 
 use std::ptr::NonNull;
+use std::sync::atomic::AtomicBool;
 
 use js_sys::Object;
 use wasm_bindgen::prelude::wasm_bindgen;
+#[cfg(feature = "message")]
+use wasm_bindgen::JsValue;
+use web_sys::MessagePort;
 
+#[cfg(feature = "message")]
+use super::node::ProcessorMessage;
 use super::Data;
 
 #[wasm_bindgen]
@@ -20,6 +26,31 @@ extern "C" {
 	#[wasm_bindgen(method, setter, js_name = __web_thread_registered)]
 	pub(super) fn set_registered(this: &BaseAudioContextExt, value: bool);
 
+	/// Returns our custom `port` property.
+	#[wasm_bindgen(method, getter, js_name = __web_thread_port)]
+	pub(super) fn port(this: &BaseAudioContextExt) -> Option<MessagePort>;
+
+	/// Sets our custom `port` property.
+	#[wasm_bindgen(method, setter, js_name = __web_thread_port)]
+	pub(super) fn set_port(this: &BaseAudioContextExt, value: &MessagePort);
+
+	/// Returns our custom `released` property.
+	#[wasm_bindgen(method, getter, js_name = __web_thread_released)]
+	pub(super) fn released(this: &BaseAudioContextExt) -> Option<NonNull<AtomicBool>>;
+
+	/// Sets our custom `released` property.
+	#[wasm_bindgen(method, setter, js_name = __web_thread_released)]
+	pub(super) fn set_released(this: &BaseAudioContextExt, value: NonNull<AtomicBool>);
+
+	/// Message sent over [`AudioWorkletNode.port`](https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletNode/port)
+	/// to drop the processor.
+	#[wasm_bindgen(extends = Object)]
+	pub(super) type DisposeMessage;
+
+	/// Sets our custom `dispose` property.
+	#[wasm_bindgen(method, setter, js_name = __web_thread_dispose)]
+	pub(super) fn set_dispose(this: &DisposeMessage, value: bool);
+
 	/// Type for [`AudioWorkletNodeOptions.processorOptions`](https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletNode/AudioWorkletNode#processoroptions).
 	#[wasm_bindgen(extends = Object)]
 	#[derive(Default)]
@@ -32,4 +63,52 @@ extern "C" {
 	/// Sets our custom `data` property.
 	#[wasm_bindgen(method, setter, js_name = __web_thread_data)]
 	pub(super) fn set_data(this: &ProcessorOptions, value: NonNull<Data>);
+
+	/// Type for [`AudioParamDescriptor`](https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletProcessor/parameterDescriptors).
+	#[wasm_bindgen(extends = Object)]
+	pub(super) type AudioParamDescriptor;
+
+	/// Sets the `name` property.
+	#[wasm_bindgen(method, setter)]
+	pub(super) fn set_name(this: &AudioParamDescriptor, value: &str);
+
+	/// Sets the `defaultValue` property.
+	#[wasm_bindgen(method, setter, js_name = defaultValue)]
+	pub(super) fn set_default_value(this: &AudioParamDescriptor, value: f32);
+
+	/// Sets the `minValue` property.
+	#[wasm_bindgen(method, setter, js_name = minValue)]
+	pub(super) fn set_min_value(this: &AudioParamDescriptor, value: f32);
+
+	/// Sets the `maxValue` property.
+	#[wasm_bindgen(method, setter, js_name = maxValue)]
+	pub(super) fn set_max_value(this: &AudioParamDescriptor, value: f32);
+
+	/// Sets the `automationRate` property.
+	#[wasm_bindgen(method, setter, js_name = automationRate)]
+	pub(super) fn set_automation_rate(this: &AudioParamDescriptor, value: &str);
+}
+
+#[cfg(feature = "message")]
+#[wasm_bindgen]
+extern "C" {
+	/// Message sent over [`AudioWorkletNode.port`](https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletNode/port).
+	#[wasm_bindgen(extends = Object)]
+	pub(super) type PortMessage;
+
+	/// Sets our custom `message` property.
+	#[wasm_bindgen(method, setter, js_name = __web_thread_message)]
+	pub(super) fn set_message(this: &PortMessage, value: NonNull<ProcessorMessage>);
+
+	/// Sets our custom `serialized` property.
+	#[wasm_bindgen(method, setter, js_name = __web_thread_serialized)]
+	pub(super) fn set_serialized(this: &PortMessage, value: &JsValue);
+
+	/// Returns our custom `serialized` property.
+	#[wasm_bindgen(method, getter, js_name = __web_thread_serialized)]
+	pub(super) fn serialized(this: &ProcessorOptions) -> JsValue;
+
+	/// Sets our custom `serialized` property.
+	#[wasm_bindgen(method, setter, js_name = __web_thread_serialized)]
+	pub(super) fn set_serialized(this: &ProcessorOptions, value: &JsValue);
 }
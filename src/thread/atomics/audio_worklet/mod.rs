@@ -1,28 +1,50 @@
 //! Audio worklet extension implementations.
 
+#[cfg(all(feature = "detect-allocations", debug_assertions))]
+mod allocator;
+mod closure;
 mod js;
 #[cfg(feature = "message")]
 pub(super) mod main;
+mod node;
 mod processor;
 pub(super) mod register;
+mod render;
 
 use std::any::{Any, TypeId};
+use std::io::Error;
+#[cfg(feature = "message")]
+use std::io::ErrorKind;
 use std::ptr::NonNull;
 
 use js_sys::{JsString, Object, Reflect};
 use wasm_bindgen::prelude::wasm_bindgen;
 use wasm_bindgen::JsCast;
+#[cfg(feature = "message")]
+use wasm_bindgen::JsValue;
 use web_sys::{AudioWorkletNode, AudioWorkletNodeOptions, BaseAudioContext};
 
+#[cfg(all(feature = "detect-allocations", debug_assertions))]
+pub(in super::super) use self::allocator::{record_allocation, record_deallocation};
+pub(in super::super) use self::closure::{
+	audio_worklet_node_from_fn, AudioWorkletNodeFromFnFuture,
+};
 use self::js::{BaseAudioContextExt, ProcessorOptions};
+#[cfg(feature = "message")]
+pub(in super::super) use self::node::post_message;
+pub(in super::super) use self::node::{dispose, processor_error, ProcessorErrorFuture};
 pub(in super::super) use self::processor::register_processor;
 #[cfg(feature = "message")]
 pub(in super::super) use self::register::message::register_thread_with_message;
 pub(in super::super) use self::register::{
-	register_thread, AudioWorkletHandle, RegisterThreadFuture,
+	register_processor_from_main, register_thread, AudioWorkletHandle, RegisterProcessorFuture,
+	RegisterThreadFuture,
 };
+pub(in super::super) use self::render::render_offline;
 pub(in super::super) use super::is_main_thread;
 use crate::web::audio_worklet::{AudioWorkletNodeError, ExtendAudioWorkletProcessor};
+#[cfg(feature = "message")]
+use crate::web::message::{ArrayBuilder, MessageSend, RawMessage};
 
 #[wasm_bindgen]
 #[rustfmt::skip]
@@ -38,6 +60,16 @@ extern "C" {
 	static PROCESSOR_OPTIONS_PROPERTY_NAME: JsString = "processorOptions";
 }
 
+#[cfg(feature = "message")]
+#[wasm_bindgen]
+#[rustfmt::skip]
+extern "C" {
+	/// Name of our custom property on [`AudioWorkletNodeOptions`] holding the
+	/// serialized part of [`ExtendAudioWorkletProcessor::Data`].
+	#[wasm_bindgen(thread_local, static_string)]
+	static SERIALIZED_PROPERTY_NAME: JsString = "__web_thread_serialized";
+}
+
 /// Returns [`true`] if this context has a registered thread.
 pub(in super::super) fn is_registered(context: &BaseAudioContext) -> bool {
 	matches!(
@@ -53,7 +85,90 @@ pub(in super::super) fn audio_worklet_node<P: 'static + ExtendAudioWorkletProces
 	name: &str,
 	data: P::Data,
 	options: Option<&AudioWorkletNodeOptions>,
-) -> Result<AudioWorkletNode, AudioWorkletNodeError<P>> {
+) -> Result<AudioWorkletNode, AudioWorkletNodeError<P>>
+where
+	P::Data: Send,
+{
+	create_node::<P>(
+		context,
+		name,
+		Box::new(data),
+		#[cfg(feature = "message")]
+		None,
+		options,
+	)
+	.map_err(|(data, error)| AudioWorkletNodeError {
+		data: *data.downcast().expect("wrong type encoded"),
+		error,
+	})
+}
+
+/// Implementation for
+/// [`crate::web::audio_worklet::BaseAudioContextExt::audio_worklet_node_with_message()`].
+#[cfg(feature = "message")]
+pub(in super::super) fn audio_worklet_node_with_message<P: 'static + ExtendAudioWorkletProcessor>(
+	context: &BaseAudioContext,
+	name: &str,
+	data: P::Data,
+	options: Option<&AudioWorkletNodeOptions>,
+) -> Result<AudioWorkletNode, AudioWorkletNodeError<P>>
+where
+	P::Data: MessageSend,
+{
+	let mut transfer_builder = ArrayBuilder::new();
+	let RawMessage { serialize, send } = data.send(&mut transfer_builder);
+
+	if transfer_builder.finish().is_some() {
+		return Err(AudioWorkletNodeError {
+			data: P::Data::receive(serialize, send),
+			error: Error::new(
+				ErrorKind::Unsupported,
+				"transferable objects can not be sent through `processorOptions`",
+			),
+		});
+	}
+
+	create_node::<P>(
+		context,
+		name,
+		Box::new(send),
+		Some((serialize.clone(), receive::<P::Data>)),
+		options,
+	)
+	.map_err(|(send, error)| AudioWorkletNodeError {
+		data: P::Data::receive(serialize, *send.downcast().expect("wrong type encoded")),
+		error,
+	})
+}
+
+/// Reconstructs a [`MessageSend`] from its serialized value and its
+/// type-erased [`RawMessage::send`].
+#[cfg(feature = "message")]
+fn receive<M: 'static + MessageSend>(
+	serialized: Option<JsValue>,
+	send: Box<dyn Any>,
+) -> Box<dyn Any> {
+	Box::new(M::receive(
+		serialized,
+		*send
+			.downcast::<Option<M::Send>>()
+			.expect("wrong type encoded"),
+	))
+}
+
+/// Instantiates an [`AudioWorkletNode`] passing `value` and optionally
+/// `serialized` to the processor through
+/// [`AudioWorkletNodeOptions.processorOptions`]. Returns `value` back on
+/// error.
+///
+/// [`AudioWorkletNodeOptions.processorOptions`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletNode/AudioWorkletNode#processoroptions
+fn create_node<P: 'static>(
+	context: &BaseAudioContext,
+	name: &str,
+	value: Box<dyn Any>,
+	#[cfg(feature = "message")] serialized: Option<(Option<JsValue>, Receive)>,
+	options: Option<&AudioWorkletNodeOptions>,
+) -> Result<AudioWorkletNode, (Box<dyn Any>, Error)> {
 	// If `processor_options` is set already by the caller, don't overwrite it!
 	let options: &AudioWorkletNodeOptions = match options {
 		Some(options) => options.unchecked_ref(),
@@ -61,14 +176,25 @@ pub(in super::super) fn audio_worklet_node<P: 'static + ExtendAudioWorkletProces
 	};
 	let processor_options = options.get_processor_options();
 	let has_processor_options = processor_options.is_some();
+	let processor_options: ProcessorOptions =
+		processor_options.unwrap_or_default().unchecked_into();
+
+	#[cfg(feature = "message")]
+	let receive = serialized.map(|(serialized, receive)| {
+		if let Some(serialized) = serialized {
+			processor_options.set_serialized(&serialized);
+		}
+
+		receive
+	});
 
 	let data = Box::new(Data {
 		type_id: TypeId::of::<P>(),
-		value: Box::new(data),
+		value,
+		#[cfg(feature = "message")]
+		receive,
 		empty: !has_processor_options,
 	});
-	let processor_options: ProcessorOptions =
-		processor_options.unwrap_or_default().unchecked_into();
 	let data: NonNull<Data> = NonNull::from(Box::leak(data));
 	processor_options.set_data(data);
 
@@ -82,26 +208,28 @@ pub(in super::super) fn audio_worklet_node<P: 'static + ExtendAudioWorkletProces
 		DATA_PROPERTY_NAME
 			.with(|name| Reflect::delete_property(&processor_options, name))
 			.expect("expected `processor_options` to be an `Object`");
+		#[cfg(feature = "message")]
+		SERIALIZED_PROPERTY_NAME
+			.with(|name| Reflect::delete_property(&processor_options, name))
+			.expect("expected `processor_options` to be an `Object`");
 	} else {
 		PROCESSOR_OPTIONS_PROPERTY_NAME
 			.with(|name| Reflect::delete_property(options, name))
 			.expect("expected `AudioWorkletNodeOptions` to be an `Object`");
 	}
 
-	match result {
-		Ok(node) => Ok(node),
-		Err(error) => Err(AudioWorkletNodeError {
-			// SAFETY: We just made this pointer above and `new AudioWorkletNode` has to guarantee
-			// that on error transmission failed to avoid double-free.
-			data: *unsafe { Box::from_raw(data.as_ptr()) }
-				.value
-				.downcast()
-				.expect("wrong type encoded"),
-			error: super::error_from_exception(error),
-		}),
-	}
+	result.map_err(|error| {
+		// SAFETY: We just made this pointer above and `new AudioWorkletNode` has to
+		// guarantee that on error transmission failed to avoid double-free.
+		let data = *unsafe { Box::from_raw(data.as_ptr()) };
+		(data.value, super::error_from_exception(error))
+	})
 }
 
+/// Type-erased [`MessageSend::receive()`]. See [`Data::receive`].
+#[cfg(feature = "message")]
+type Receive = fn(Option<JsValue>, Box<dyn Any>) -> Box<dyn Any>;
+
 /// Data stored in [`AudioWorkletNodeOptions.processorOptions`] to transport
 /// [`ExtendAudioWorkletProcessor::Data`].
 ///
@@ -109,8 +237,14 @@ pub(in super::super) fn audio_worklet_node<P: 'static + ExtendAudioWorkletProces
 struct Data {
 	/// [`TypeId`] to compare to the type when arriving at the constructor.
 	type_id: TypeId,
-	/// [`ExtendAudioWorkletProcessor::Data`].
+	/// [`ExtendAudioWorkletProcessor::Data`] or, if sent as a message, its
+	/// type-erased `RawMessage::send`.
 	value: Box<dyn Any>,
+	/// Reconstructs [`ExtendAudioWorkletProcessor::Data`] from [`Self::value`]
+	/// and its serialized value if it was sent with
+	/// [`audio_worklet_node_with_message()`].
+	#[cfg(feature = "message")]
+	receive: Option<Receive>,
 	/// If [`AudioWorkletNodeOptions.processorOptions`] was empty.
 	///
 	/// [`AudioWorkletNodeOptions.processorOptions`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletNode/AudioWorkletNode#processoroptions
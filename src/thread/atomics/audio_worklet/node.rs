@@ -0,0 +1,212 @@
+//! [`RustAudioWorkletNode`](crate::web::audio_worklet::RustAudioWorkletNode)
+//! related implementation.
+
+#[cfg(feature = "message")]
+use std::any::{self, Any};
+use std::future::Future;
+#[cfg(feature = "message")]
+use std::marker::PhantomData;
+use std::pin::Pin;
+#[cfg(feature = "message")]
+use std::ptr::NonNull;
+use std::task::{ready, Context, Poll};
+
+use js_sys::Object;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+#[cfg(feature = "message")]
+use wasm_bindgen::JsValue;
+use web_sys::AudioWorkletNode;
+
+#[cfg(feature = "message")]
+use super::super::super::js;
+use super::super::oneshot::{self, Receiver};
+use super::js::DisposeMessage;
+#[cfg(feature = "message")]
+use super::js::PortMessage;
+#[cfg(feature = "message")]
+use crate::web::audio_worklet::ReceiveMessage;
+#[cfg(feature = "message")]
+use crate::web::message::{ArrayBuilder, MessageError, MessageSend, RawMessage};
+
+/// Implementation for
+/// [`crate::web::audio_worklet::RustAudioWorkletNode::processor_error()`].
+pub(in super::super::super) fn processor_error(node: &AudioWorkletNode) -> ProcessorErrorFuture {
+	let (sender, receiver) = oneshot::channel();
+	let mut sender = Some(sender);
+	let listener = Closure::<dyn FnMut()>::new(move || {
+		if let Some(sender) = sender.take() {
+			sender.send(());
+		}
+	});
+	node.add_event_listener_with_callback("processorerror", listener.as_ref().unchecked_ref())
+		.expect("`EventTarget.addEventListener()` is not expected to fail");
+
+	ProcessorErrorFuture {
+		node: node.clone(),
+		listener,
+		receiver: Some(receiver),
+	}
+}
+
+/// Implementation for [`crate::web::audio_worklet::ProcessorErrorFuture`].
+#[derive(Debug)]
+pub(in super::super::super) struct ProcessorErrorFuture {
+	/// The [`AudioWorkletNode`] the listener is registered on.
+	node: AudioWorkletNode,
+	/// Listener for the [`processorerror`] event.
+	///
+	/// [`processorerror`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletNode/processorerror_event
+	listener: Closure<dyn FnMut()>,
+	/// [`Receiver`] notified by [`Self::listener`].
+	receiver: Option<Receiver<()>>,
+}
+
+impl Drop for ProcessorErrorFuture {
+	fn drop(&mut self) {
+		self.node
+			.remove_event_listener_with_callback(
+				"processorerror",
+				self.listener.as_ref().unchecked_ref(),
+			)
+			.expect("`EventTarget.removeEventListener()` is not expected to fail");
+	}
+}
+
+impl Future for ProcessorErrorFuture {
+	type Output = ();
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		let receiver = self.receiver.as_mut().expect("polled after completion");
+		let Some(()) = ready!(Pin::new(receiver).poll(cx)) else {
+			unreachable!("`Sender` dropped somehow")
+		};
+		self.receiver.take();
+
+		Poll::Ready(())
+	}
+}
+
+/// Implementation for
+/// [`crate::web::audio_worklet::RustAudioWorkletNode::dispose()`].
+pub(in super::super::super) fn dispose(node: &AudioWorkletNode) {
+	let message: DisposeMessage = Object::new().unchecked_into();
+	message.set_dispose(true);
+
+	node.port()
+		.expect("`AudioWorkletNode.port` is not expected to fail")
+		.post_message(&message)
+		.expect("`MessagePort.postMessage()` is not expected to fail without a `transfer` object");
+}
+
+/// Implementation for
+/// [`crate::web::audio_worklet::RustAudioWorkletNode::post()`].
+#[cfg(feature = "message")]
+pub(in super::super::super) fn post_message<P: 'static + ReceiveMessage>(
+	node: &AudioWorkletNode,
+	message: P::Message,
+) -> Result<(), MessageError<P::Message>> {
+	let port = match node.port() {
+		Ok(port) => port,
+		Err(error) => {
+			return Err(super::super::message_error_from_exception(
+				error, None, message,
+			))
+		}
+	};
+
+	let mut transfer_builder = ArrayBuilder::new();
+	let RawMessage { serialize, send } = message.send(&mut transfer_builder);
+	let transfer = transfer_builder.finish();
+
+	let message = Box::new(ProcessorMessage(Box::new(Envelope::<P> {
+		send,
+		_processor: PhantomData,
+	})));
+	let message: NonNull<ProcessorMessage> = NonNull::from(Box::leak(message));
+
+	let port_message: PortMessage = Object::new().unchecked_into();
+	port_message.set_message(message);
+
+	if let Some(serialize) = &serialize {
+		port_message.set_serialized(serialize);
+	}
+
+	let result = if let Some(transfer) = &transfer {
+		port.post_message_with_transferable(&port_message, transfer)
+	} else {
+		port.post_message(&port_message)
+	};
+
+	result.map_err(|error| {
+		// SAFETY: We just made this pointer above and `postMessage()` has to guarantee
+		// that on error transmission failed to avoid double-free.
+		let message = unsafe { Box::from_raw(message.as_ptr()) };
+		let message = *message
+			.receive(serialize)
+			.downcast()
+			.expect("wrong type encoded");
+		super::super::message_error_from_exception(error, transfer.as_ref(), message)
+	})
+}
+
+/// Message sent to an
+/// [`ExtendAudioWorkletProcessor`](crate::web::audio_worklet::ExtendAudioWorkletProcessor).
+#[cfg(feature = "message")]
+pub(super) struct ProcessorMessage(Box<dyn DeliverMessage>);
+
+#[cfg(feature = "message")]
+impl ProcessorMessage {
+	/// Deserializes the message with the given `serialized` value.
+	fn receive(self, serialized: Option<JsValue>) -> Box<dyn Any> {
+		self.0.receive(serialized)
+	}
+
+	/// Deserializes the message with the given `serialized` value and passes
+	/// it to [`ReceiveMessage::on_message()`] of the given `processor`.
+	pub(super) fn deliver(self, processor: &mut dyn Any, serialized: Option<JsValue>) {
+		self.0.deliver(processor, serialized);
+	}
+}
+
+/// Type-erased [`MessageSend::receive()`] and
+/// [`ReceiveMessage::on_message()`].
+#[cfg(feature = "message")]
+trait DeliverMessage: Send {
+	/// Calls the underlying [`MessageSend::receive()`].
+	fn receive(self: Box<Self>, serialized: Option<JsValue>) -> Box<dyn Any>;
+
+	/// Calls the underlying [`ReceiveMessage::on_message()`] if `processor` is
+	/// the right type.
+	fn deliver(self: Box<Self>, processor: &mut dyn Any, serialized: Option<JsValue>);
+}
+
+/// Holds the [`MessageSend::Send`] part of a message.
+#[cfg(feature = "message")]
+struct Envelope<P: ReceiveMessage> {
+	/// [`RawMessage::send`].
+	send: Option<<P::Message as MessageSend>::Send>,
+	/// The receiving processor.
+	_processor: PhantomData<fn() -> P>,
+}
+
+#[cfg(feature = "message")]
+impl<P: 'static + ReceiveMessage> DeliverMessage for Envelope<P> {
+	fn receive(self: Box<Self>, serialized: Option<JsValue>) -> Box<dyn Any> {
+		Box::new(P::Message::receive(serialized, self.send))
+	}
+
+	fn deliver(self: Box<Self>, processor: &mut dyn Any, serialized: Option<JsValue>) {
+		let message = P::Message::receive(serialized, self.send);
+
+		if let Some(processor) = processor.downcast_mut::<P>() {
+			processor.on_message(message);
+		} else {
+			js::console_warn(&format!(
+				"dropped message from a `RustAudioWorkletNode<{}>` not matching the registered \
+				 processor",
+				any::type_name::<P>()
+			));
+		}
+	}
+}
@@ -2,18 +2,31 @@
 //!
 //! [`AudioWorkletProcessor`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletProcessor
 
+#[cfg(feature = "message")]
+use std::any::Any;
 use std::any::TypeId;
 use std::io::Error;
 use std::marker::PhantomData;
+use std::ptr;
+#[cfg(feature = "message")]
+use std::ptr::NonNull;
 
 use js_sys::{Array, Iterator, JsString, Object, Reflect};
 use wasm_bindgen::prelude::wasm_bindgen;
 use wasm_bindgen::JsCast;
+#[cfg(feature = "message")]
+use wasm_bindgen::JsValue;
 use web_sys::{AudioWorkletNodeOptions, DomException};
 
-use super::js::ProcessorOptions;
+#[cfg(all(feature = "detect-allocations", debug_assertions))]
+use super::allocator::Detector;
+use super::js::{AudioParamDescriptor, ProcessorOptions};
+#[cfg(feature = "message")]
+use super::node::ProcessorMessage;
+#[cfg(feature = "message")]
+use super::SERIALIZED_PROPERTY_NAME;
 use super::{Data, DATA_PROPERTY_NAME, PROCESSOR_OPTIONS_PROPERTY_NAME};
-use crate::web::audio_worklet::ExtendAudioWorkletProcessor;
+use crate::web::audio_worklet::{AudioParameters, ExtendAudioWorkletProcessor};
 
 /// Implementation for
 /// [`crate::web::audio_worklet::AudioWorkletGlobalScopeExt::register_processor_ext()`].
@@ -94,12 +107,16 @@ impl<P: 'static + ExtendAudioWorkletProcessor> ProcessorConstructor
 				let data: Data = *unsafe { Box::<Data>::from_raw(data.as_ptr()) };
 
 				if data.type_id == TypeId::of::<P>() {
-					processor_data = Some(
-						*data
-							.value
-							.downcast::<P::Data>()
-							.expect("wrong type encoded"),
-					);
+					let value = data.value;
+					#[cfg(feature = "message")]
+					let value = if let Some(receive) = data.receive {
+						let serialized = processor_options.serialized();
+						receive((!serialized.is_undefined()).then_some(serialized), value)
+					} else {
+						value
+					};
+					processor_data =
+						Some(*value.downcast::<P::Data>().expect("wrong type encoded"));
 
 					if data.empty {
 						PROCESSOR_OPTIONS_PROPERTY_NAME
@@ -109,43 +126,355 @@ impl<P: 'static + ExtendAudioWorkletProcessor> ProcessorConstructor
 						DATA_PROPERTY_NAME
 							.with(|name| Reflect::delete_property(&processor_options, name))
 							.expect("expected `processor_options` to be an `Object`");
+						#[cfg(feature = "message")]
+						SERIALIZED_PROPERTY_NAME
+							.with(|name| Reflect::delete_property(&processor_options, name))
+							.expect("expected `processor_options` to be an `Object`");
 					}
 				}
 			}
 		}
 
-		__WebThreadProcessor(Box::new(P::new(this, processor_data, options)))
+		__WebThreadProcessor {
+			processor: Box::new(P::new(this, processor_data, options)),
+			buffers: Buffers::default(),
+			stopped: false,
+			#[cfg(all(feature = "detect-allocations", debug_assertions))]
+			detector: Detector::new::<P>(),
+		}
 	}
 
 	fn parameter_descriptors(&self) -> Iterator {
-		P::parameter_descriptors()
+		if P::PARAMETERS.is_empty() {
+			return P::parameter_descriptors();
+		}
+
+		P::PARAMETERS
+			.iter()
+			.map(|parameter| {
+				let descriptor: AudioParamDescriptor = Object::new().unchecked_into();
+				descriptor.set_name(parameter.name());
+				descriptor.set_default_value(parameter.default_value());
+				descriptor.set_min_value(parameter.min_value());
+				descriptor.set_max_value(parameter.max_value());
+				descriptor.set_automation_rate(parameter.automation_rate().as_str());
+				descriptor
+			})
+			.collect::<Array>()
+			.values()
 	}
 }
 
 /// Holds the supplied [`ExtendAudioWorkletProcessor`] while type-erasing
 /// it.
 #[wasm_bindgen(skip_typescript)]
-struct __WebThreadProcessor(Box<dyn Processor>);
+struct __WebThreadProcessor {
+	/// The type-erased [`ExtendAudioWorkletProcessor`].
+	processor: Box<dyn Processor>,
+	/// Buffers for
+	/// [`crate::web::audio_worklet::ProcessBuffers::process_buffers()`].
+	buffers: Buffers,
+	/// If the last call to [`Processor::process()`] or
+	/// [`Processor::process_buffers()`] returned [`false`].
+	stopped: bool,
+	/// Detects allocations made by this processor while processing.
+	#[cfg(all(feature = "detect-allocations", debug_assertions))]
+	detector: Detector,
+}
 
 /// Object-safe version of [`ExtendAudioWorkletProcessor`].
 trait Processor {
+	/// Returns [`true`] if [`ExtendAudioWorkletProcessor::PROCESS_BUFFERS`] is
+	/// set.
+	fn has_buffers(&self) -> bool;
+
 	/// Calls the underlying [`ExtendAudioWorkletProcessor::process`].
 	fn process(&mut self, inputs: Array, outputs: Array, parameters: Object) -> bool;
+
+	/// Calls the underlying
+	/// [`crate::web::audio_worklet::ProcessBuffers::process_buffers`].
+	fn process_buffers(
+		&mut self,
+		inputs: &[&[&[f32]]],
+		outputs: &mut [&mut [&mut [f32]]],
+		parameters: &AudioParameters<'_>,
+	) -> bool;
+
+	/// Calls the underlying [`ExtendAudioWorkletProcessor::on_stop`].
+	fn on_stop(&mut self);
+
+	/// Returns the underlying [`ExtendAudioWorkletProcessor`] to deliver
+	/// messages to.
+	#[cfg(feature = "message")]
+	fn as_any(&mut self) -> &mut dyn Any;
 }
 
-impl<P: ExtendAudioWorkletProcessor> Processor for P {
+impl<P: 'static + ExtendAudioWorkletProcessor> Processor for P {
+	fn has_buffers(&self) -> bool {
+		P::PROCESS_BUFFERS.is_some()
+	}
+
 	fn process(&mut self, inputs: Array, outputs: Array, parameters: Object) -> bool {
 		ExtendAudioWorkletProcessor::process(self, inputs, outputs, parameters)
 	}
+
+	fn process_buffers(
+		&mut self,
+		inputs: &[&[&[f32]]],
+		outputs: &mut [&mut [&mut [f32]]],
+		parameters: &AudioParameters<'_>,
+	) -> bool {
+		P::PROCESS_BUFFERS.is_some_and(|process| process.0(self, inputs, outputs, parameters))
+	}
+
+	fn on_stop(&mut self) {
+		ExtendAudioWorkletProcessor::on_stop(self);
+	}
+
+	#[cfg(feature = "message")]
+	fn as_any(&mut self) -> &mut dyn Any {
+		self
+	}
 }
 
 #[wasm_bindgen]
 impl __WebThreadProcessor {
-	/// Calls the underlying [`ExtendAudioWorkletProcessor::new`].
+	/// Calls the underlying [`ExtendAudioWorkletProcessor::process`].
 	#[wasm_bindgen]
 	#[allow(unreachable_pub)]
 	pub fn process(&mut self, inputs: Array, outputs: Array, parameters: Object) -> bool {
-		self.0.process(inputs, outputs, parameters)
+		#[cfg(all(feature = "detect-allocations", debug_assertions))]
+		let result = self
+			.detector
+			.detect(|| self.processor.process(inputs, outputs, parameters));
+		#[cfg(not(all(feature = "detect-allocations", debug_assertions)))]
+		let result = self.processor.process(inputs, outputs, parameters);
+		self.stop(result)
+	}
+
+	/// Returns [`true`] if
+	/// [`crate::web::audio_worklet::ProcessBuffers::process_buffers`] should be
+	/// called instead of [`ExtendAudioWorkletProcessor::process`].
+	#[wasm_bindgen(js_name = hasBuffers)]
+	#[allow(unreachable_pub)]
+	pub fn has_buffers(&self) -> bool {
+		self.processor.has_buffers()
+	}
+
+	/// Resizes the buffers to the given `layout` and parameter `names`.
+	/// Returns a pointer to the samples the shim has to copy from and to.
+	///
+	/// The `layout` is encoded as `[input count, ...input channel counts,
+	/// output count, ...output channel counts, frames]`.
+	///
+	/// This allocates, but is only called by the shim when the layout
+	/// changed.
+	#[wasm_bindgen]
+	#[allow(unreachable_pub)]
+	pub fn layout(&mut self, layout: &[u32], names: Vec<String>) -> *mut f32 {
+		self.buffers.layout(layout, names)
+	}
+
+	/// Calls the underlying
+	/// [`crate::web::audio_worklet::ProcessBuffers::process_buffers`] with the
+	/// samples copied into the buffers by the shim.
+	#[wasm_bindgen(js_name = processBuffers)]
+	#[allow(unreachable_pub)]
+	pub fn process_buffers(&mut self) -> bool {
+		#[cfg(all(feature = "detect-allocations", debug_assertions))]
+		let result = self
+			.detector
+			.detect(|| self.buffers.process(self.processor.as_mut()));
+		#[cfg(not(all(feature = "detect-allocations", debug_assertions)))]
+		let result = self.buffers.process(self.processor.as_mut());
+		self.stop(result)
+	}
+}
+
+#[cfg(feature = "message")]
+#[wasm_bindgen]
+impl __WebThreadProcessor {
+	/// Receives a message sent by
+	/// [`RustAudioWorkletNode::post()`](crate::web::audio_worklet::RustAudioWorkletNode::post).
+	///
+	/// # Safety
+	///
+	/// `message` has to be a valid pointer to [`ProcessorMessage`].
+	#[wasm_bindgen]
+	#[allow(private_interfaces, unreachable_pub)]
+	pub unsafe fn message(&mut self, message: NonNull<ProcessorMessage>, serialized: JsValue) {
+		// SAFETY: Has to be a valid pointer to a `ProcessorMessage`. We only call
+		// `message()` from `worklet_with_message.js`. The data sent to it comes only
+		// from `post_message()`.
+		let message: ProcessorMessage = *unsafe { Box::from_raw(message.as_ptr()) };
+		let serialized = (!serialized.is_undefined()).then_some(serialized);
+		message.deliver(self.processor.as_any(), serialized);
+	}
+}
+
+impl __WebThreadProcessor {
+	/// Calls [`Processor::on_stop()`] if `result` is [`false`] and it wasn't
+	/// already called.
+	fn stop(&mut self, result: bool) -> bool {
+		if result {
+			self.stopped = false;
+		} else if !self.stopped {
+			self.stopped = true;
+			self.processor.on_stop();
+		}
+
+		result
+	}
+}
+
+/// Buffers for [`crate::web::audio_worklet::ProcessBuffers::process_buffers()`]
+/// living in Wasm memory, reused across calls.
+///
+/// The samples are laid out as all input channels, all output channels, all
+/// parameters, each taking up `frames` samples, followed by the amount of
+/// values each parameter holds.
+#[derive(Debug, Default)]
+struct Buffers {
+	/// The samples copied from and to the shim.
+	samples: Vec<f32>,
+	/// Amount of frames per channel.
+	frames: usize,
+	/// Amount of channels of each input.
+	inputs: Vec<usize>,
+	/// Amount of channels of each output.
+	outputs: Vec<usize>,
+	/// Names of the parameters.
+	names: Vec<String>,
+	/// Scratch space for input channels.
+	input_channels: Vec<*const [f32]>,
+	/// Scratch space for inputs.
+	input_groups: Vec<*const [*const [f32]]>,
+	/// Scratch space for output channels.
+	output_channels: Vec<*mut [f32]>,
+	/// Scratch space for outputs.
+	output_groups: Vec<*mut [*mut [f32]]>,
+	/// Scratch space for parameters.
+	parameters: Vec<*const [f32]>,
+}
+
+impl Buffers {
+	/// Resizes the buffers. See [`__WebThreadProcessor::layout()`].
+	fn layout(&mut self, layout: &[u32], names: Vec<String>) -> *mut f32 {
+		let mut layout = layout
+			.iter()
+			.map(|value| usize::try_from(*value).expect("found 64-bit Wasm"));
+		let mut counts = |buffer: &mut Vec<usize>| {
+			let len = layout.next().expect("found invalid layout");
+			buffer.clear();
+			buffer.extend(layout.by_ref().take(len));
+			assert_eq!(buffer.len(), len, "found invalid layout");
+		};
+
+		counts(&mut self.inputs);
+		counts(&mut self.outputs);
+		self.frames = layout.next().expect("found invalid layout");
+		self.names = names;
+
+		let input_channels = self.inputs.iter().sum();
+		let output_channels = self.outputs.iter().sum();
+		let samples =
+			(input_channels + output_channels + self.names.len()) * self.frames + self.names.len();
+
+		self.samples.clear();
+		self.samples.resize(samples, 0.);
+		Self::reserve(&mut self.input_channels, input_channels);
+		Self::reserve(&mut self.input_groups, self.inputs.len());
+		Self::reserve(&mut self.output_channels, output_channels);
+		Self::reserve(&mut self.output_groups, self.outputs.len());
+		Self::reserve(&mut self.parameters, self.names.len());
+
+		self.samples.as_mut_ptr()
+	}
+
+	/// Clears the `buffer` and makes sure it has capacity for `len` elements
+	/// without re-allocating.
+	fn reserve<T>(buffer: &mut Vec<T>, len: usize) {
+		buffer.clear();
+		buffer.reserve_exact(len);
+	}
+
+	/// Calls [`Processor::process_buffers()`] with the current samples.
+	fn process(&mut self, processor: &mut dyn Processor) -> bool {
+		let Self {
+			samples,
+			frames,
+			inputs,
+			outputs,
+			names,
+			input_channels,
+			input_groups,
+			output_channels,
+			output_groups,
+			parameters,
+		} = self;
+
+		if *frames == 0 {
+			return processor.process_buffers(&[], &mut [], &AudioParameters::new(&[], &[]));
+		}
+
+		let input_len = inputs.iter().sum::<usize>() * *frames;
+		let output_len = outputs.iter().sum::<usize>() * *frames;
+		let (input_samples, rest) = samples.split_at_mut(input_len);
+		let (output_samples, rest) = rest.split_at_mut(output_len);
+		let (parameter_samples, lengths) = rest.split_at(names.len() * *frames);
+		output_samples.fill(0.);
+
+		input_channels.clear();
+		input_channels.extend(input_samples.chunks_exact(*frames).map(ptr::from_ref));
+		input_groups.clear();
+		let mut rest = input_channels.as_slice();
+
+		for count in inputs.iter() {
+			let (group, next) = rest.split_at(*count);
+			input_groups.push(ptr::from_ref(group));
+			rest = next;
+		}
+
+		output_channels.clear();
+		output_channels.extend(output_samples.chunks_exact_mut(*frames).map(ptr::from_mut));
+		output_groups.clear();
+		let mut rest = output_channels.as_mut_slice();
+
+		for count in outputs.iter() {
+			let (group, next) = rest.split_at_mut(*count);
+			output_groups.push(ptr::from_mut(group));
+			rest = next;
+		}
+
+		parameters.clear();
+		parameters.extend(parameter_samples.chunks_exact(*frames).zip(lengths).map(
+			|(values, len)| {
+				// The shim only writes `1` or `frames` as length.
+				#[allow(
+					clippy::as_conversions,
+					clippy::cast_possible_truncation,
+					clippy::cast_sign_loss
+				)]
+				let len = *len as usize;
+				ptr::from_ref(values.get(..len).unwrap_or(values))
+			},
+		));
+
+		// SAFETY: `*const [T]` and `&[T]` have the same layout. All pointers were
+		// just derived from disjoint parts of `samples` and the scratch spaces,
+		// which are not touched until the references are dropped at the end of
+		// this function.
+		#[allow(clippy::as_conversions)]
+		let (inputs, outputs, parameters) = unsafe {
+			(
+				&*(ptr::from_ref(input_groups.as_slice()) as *const [&[&[f32]]]),
+				&mut *(ptr::from_mut(output_groups.as_mut_slice())
+					as *mut [&mut [&mut [f32]]]),
+				&*(ptr::from_ref(parameters.as_slice()) as *const [&[f32]]),
+			)
+		};
+
+		processor.process_buffers(inputs, outputs, &AudioParameters::new(names, parameters))
 	}
 }
 
@@ -0,0 +1,81 @@
+//! Waiting for the audio worklet to acknowledge that it has run a task.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::task::{ready, Context, Poll};
+
+use js_sys::Array;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{MessageEvent, MessagePort};
+
+use super::super::super::oneshot::{self, Receiver};
+
+/// Counter for generating unique acknowledgement IDs.
+static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+
+/// Returns a new unique acknowledgement ID.
+pub(super) fn next_id() -> u32 {
+	NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Waits for the audio worklet to post `[id, success]` to `port`.
+pub(super) fn acknowledge(port: &MessagePort, id: u32) -> AcknowledgeFuture {
+	let (sender, receiver) = oneshot::channel();
+	let mut sender = Some(sender);
+	let listener = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+		let Ok(data) = event.data().dyn_into::<Array>() else {
+			return;
+		};
+
+		if data.get(0).as_f64() == Some(id.into()) {
+			if let Some(sender) = sender.take() {
+				sender.send(data.get(1).is_truthy());
+			}
+		}
+	});
+	port.add_event_listener_with_callback("message", listener.as_ref().unchecked_ref())
+		.expect("`EventTarget.addEventListener()` is not expected to fail");
+	port.start();
+
+	AcknowledgeFuture {
+		port: port.clone(),
+		listener,
+		receiver: Some(receiver),
+	}
+}
+
+/// Resolves to `true` if the audio worklet ran the task successfully or to
+/// `false` if it panicked.
+#[derive(Debug)]
+pub(in super::super) struct AcknowledgeFuture {
+	/// The [`MessagePort`] the listener is registered on.
+	port: MessagePort,
+	/// Listener for the acknowledgement.
+	listener: Closure<dyn FnMut(MessageEvent)>,
+	/// [`Receiver`] notified by [`Self::listener`].
+	receiver: Option<Receiver<bool>>,
+}
+
+impl Drop for AcknowledgeFuture {
+	fn drop(&mut self) {
+		self.port
+			.remove_event_listener_with_callback("message", self.listener.as_ref().unchecked_ref())
+			.expect("`EventTarget.removeEventListener()` is not expected to fail");
+	}
+}
+
+impl Future for AcknowledgeFuture {
+	type Output = bool;
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		let receiver = self.receiver.as_mut().expect("polled after completion");
+		let Some(success) = ready!(Pin::new(receiver).poll(cx)) else {
+			unreachable!("`Sender` dropped somehow")
+		};
+		self.receiver.take();
+
+		Poll::Ready(success)
+	}
+}
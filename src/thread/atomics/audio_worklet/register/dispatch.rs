@@ -0,0 +1,140 @@
+//! Dispatching tasks from the main thread to an already registered audio
+//! worklet thread.
+
+use std::future::Future;
+use std::io::{self, Error};
+use std::pin::Pin;
+use std::ptr::NonNull;
+use std::sync::atomic::Ordering;
+use std::task::{ready, Context, Poll};
+
+use js_sys::Array;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::BaseAudioContext;
+
+use super::super::super::oneshot::{self, Receiver};
+use super::super::js::BaseAudioContextExt;
+use super::acknowledge::{self, AcknowledgeFuture};
+use super::Task;
+use crate::web::audio_worklet::ExtendAudioWorkletProcessor;
+
+/// Implementation for
+/// [`crate::web::audio_worklet::BaseAudioContextExt::register_processor()`].
+pub(in super::super::super::super) fn register_processor_from_main<
+	P: 'static + ExtendAudioWorkletProcessor,
+>(
+	context: &BaseAudioContext,
+	name: &str,
+) -> RegisterProcessorFuture {
+	let (sender, receiver) = oneshot::channel();
+	let name = name.to_owned();
+	let task = move |_| {
+		sender.send(super::super::register_processor::<P>(&name));
+	};
+
+	match dispatch(context, task) {
+		Ok(acknowledge) => RegisterProcessorFuture(Some(State::Receiver {
+			acknowledge: Some(acknowledge),
+			receiver,
+		})),
+		Err(error) => RegisterProcessorFuture::error(error),
+	}
+}
+
+/// Runs `task` on the audio worklet thread registered on `context`. The
+/// returned [`AcknowledgeFuture`] resolves after `task` has run.
+pub(in super::super) fn dispatch(
+	context: &BaseAudioContext,
+	task: impl 'static + FnOnce(JsValue) + Send,
+) -> io::Result<AcknowledgeFuture> {
+	let context = context.unchecked_ref::<BaseAudioContextExt>();
+	let (Some(port), Some(released)) = (context.port(), context.released()) else {
+		return Err(Error::other(
+			"`RegisterThreadFuture` has to be awaited on this context first",
+		));
+	};
+
+	// SAFETY: `released` is leaked by `RegisterThreadFuture` and never deallocated.
+	if unsafe { released.as_ref() }.load(Ordering::Relaxed) {
+		return Err(Error::other(
+			"the audio worklet thread of this context was released",
+		));
+	}
+
+	let task: Task = Box::new(task);
+	let task: NonNull<Task> = NonNull::from(Box::leak(Box::new(task)));
+
+	let id = acknowledge::next_id();
+	let acknowledge = acknowledge::acknowledge(&port, id);
+
+	match port.post_message(&Array::of2(&task.into(), &id.into())) {
+		Ok(()) => Ok(acknowledge),
+		Err(error) => {
+			// SAFETY: We just made this pointer above and `MessagePort.postMessage()` has
+			// to guarantee that on error transmission failed to avoid double-free.
+			let task: Task = *unsafe { Box::from_raw(task.as_ptr()) };
+			drop(task);
+			Err(super::super::super::error_from_exception(error))
+		}
+	}
+}
+
+/// Implementation for [`crate::web::audio_worklet::RegisterProcessorFuture`].
+#[derive(Debug)]
+pub(in super::super::super::super) struct RegisterProcessorFuture(Option<State>);
+
+/// State of [`RegisterProcessorFuture`].
+#[derive(Debug)]
+enum State {
+	/// Early error.
+	Error(Error),
+	/// Waiting for the audio worklet to register the processor.
+	Receiver {
+		/// Waiting for the audio worklet to acknowledge that it has run the
+		/// task, after which the registered processor is available to new
+		/// [`AudioWorkletNode`](web_sys::AudioWorkletNode)s.
+		acknowledge: Option<AcknowledgeFuture>,
+		/// [`Receiver`] for the result of the registration.
+		receiver: Receiver<io::Result<()>>,
+	},
+}
+
+impl Future for RegisterProcessorFuture {
+	type Output = io::Result<()>;
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		match self.0.as_mut().expect("polled after completion") {
+			State::Error(_) => {
+				let Some(State::Error(error)) = self.0.take() else {
+					unreachable!("found wrong state")
+				};
+				Poll::Ready(Err(error))
+			}
+			State::Receiver {
+				acknowledge,
+				receiver,
+			} => {
+				if let Some(future) = acknowledge {
+					let success = ready!(Pin::new(future).poll(cx));
+					acknowledge.take();
+
+					if !success {
+						self.0.take();
+						return Poll::Ready(Err(Error::other("audio worklet panicked")));
+					}
+				}
+
+				let result = ready!(Pin::new(receiver).poll(cx));
+				self.0.take();
+				Poll::Ready(result.unwrap_or_else(|| Err(Error::other("audio worklet panicked"))))
+			}
+		}
+	}
+}
+
+impl RegisterProcessorFuture {
+	/// Create a [`RegisterProcessorFuture`] that returns `error`.
+	pub(in super::super::super::super) const fn error(error: Error) -> Self {
+		Self(Some(State::Error(error)))
+	}
+}
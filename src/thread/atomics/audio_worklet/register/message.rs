@@ -103,5 +103,6 @@ pub unsafe fn __web_thread_worklet_register(data: NonNull<Data>) {
 	let data: Data = *unsafe { Box::from_raw(data.as_ptr()) };
 
 	Thread::register(data.thread);
+	super::super::closure::register();
 	data.memory_sender.send(ThreadMemory::new(data.stack_size));
 }
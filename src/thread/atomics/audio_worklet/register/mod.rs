@@ -1,5 +1,7 @@
 //! Registering an audio worklet thread on a [`BaseAudioContext`].
 
+mod acknowledge;
+mod dispatch;
 #[cfg(feature = "message")]
 pub(in super::super) mod message;
 
@@ -9,7 +11,7 @@ use std::future::Future;
 use std::io::{Error, ErrorKind};
 use std::pin::Pin;
 use std::ptr::NonNull;
-use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
 use std::task::{Context, Poll};
 use std::{any, io};
 
@@ -24,12 +26,16 @@ use web_sys::{
 use {
 	self::message::{Data, MessageState},
 	super::super::channel,
-	super::super::spawn::message::SPAWN_SENDER,
-	super::super::spawn::SpawnData,
+	super::super::spawn::message::{MessageCommand, SPAWN_SENDER},
 	super::main::WORKLETS,
 	web_sys::MessageChannel,
 };
 
+pub(super) use self::acknowledge::AcknowledgeFuture;
+pub(super) use self::dispatch::dispatch;
+pub(in super::super::super) use self::dispatch::{
+	register_processor_from_main, RegisterProcessorFuture,
+};
 use super::super::js::{Meta, META};
 use super::super::memory::ThreadMemory;
 use super::super::url::ScriptUrl;
@@ -94,7 +100,13 @@ fn register_thread_internal(
 			#[cfg(feature = "message")]
 			let template = include_str!("../../script/worklet_with_message.min.js");
 
-			ScriptUrl::new(&template.replacen("@shim.js", &META.with(Meta::url), 1))
+			let common = wasm_bindgen::link_to!(module = "/src/thread/atomics/script/worklet_common.min.js");
+
+			ScriptUrl::new(
+				&template
+					.replacen("@shim.js", &META.with(Meta::url), 1)
+					.replacen("@worklet_common.js", &common, 1),
+			)
 		};
 	}
 
@@ -134,6 +146,7 @@ fn register_thread_internal(
 						#[cfg(not(feature = "message"))]
 						{
 							Thread::register(thread);
+							super::closure::register();
 							memory_sender.send(ThreadMemory::new(stack_size));
 						}
 						#[cfg(feature = "message")]
@@ -191,9 +204,9 @@ enum State {
 		memory_sender: oneshot::Sender<ThreadMemory>,
 		/// [`Receiver`](oneshot::Receiver) for [`ThreadMemory`].
 		memory_receiver: oneshot::Receiver<ThreadMemory>,
-		/// [`Receiver`](channel::Receiver) for [`SpawnData`].
+		/// [`Receiver`](channel::Receiver) for [`MessageCommand`]s.
 		#[cfg(feature = "message")]
-		spawn_receiver: channel::Receiver<SpawnData>,
+		spawn_receiver: channel::Receiver<MessageCommand>,
 		/// Message to be sent.
 		#[cfg(feature = "message")]
 		message: Option<MessageState>,
@@ -215,9 +228,9 @@ enum State {
 		memory_sender: oneshot::Sender<ThreadMemory>,
 		/// [`Receiver`](oneshot::Receiver) for [`ThreadMemory`].
 		memory_receiver: oneshot::Receiver<ThreadMemory>,
-		/// [`Receiver`](channel::Receiver) for [`SpawnData`].
+		/// [`Receiver`](channel::Receiver) for [`MessageCommand`]s.
 		#[cfg(feature = "message")]
-		spawn_receiver: channel::Receiver<SpawnData>,
+		spawn_receiver: channel::Receiver<MessageCommand>,
 		/// Message to be sent.
 		#[cfg(feature = "message")]
 		message: Option<MessageState>,
@@ -239,9 +252,9 @@ enum State {
 		memory_sender: oneshot::Sender<ThreadMemory>,
 		/// [`Receiver`](oneshot::Receiver) for [`ThreadMemory`].
 		memory_receiver: oneshot::Receiver<ThreadMemory>,
-		/// [`Receiver`](channel::Receiver) for [`SpawnData`].
+		/// [`Receiver`](channel::Receiver) for [`MessageCommand`]s.
 		#[cfg(feature = "message")]
-		spawn_receiver: channel::Receiver<SpawnData>,
+		spawn_receiver: channel::Receiver<MessageCommand>,
 		/// Message to be sent.
 		#[cfg(feature = "message")]
 		message: Option<MessageState>,
@@ -257,12 +270,14 @@ enum State {
 		/// Caller-supplied task.
 		#[cfg(feature = "message")]
 		task: Task,
-		/// [`AudioWorkletNode`] used to initialize the Wasm module.
+		/// [`MessagePort`] of the [`AudioWorkletNode`] used to initialize the
+		/// Wasm module.
+		port: MessagePort,
+		/// Marks the [`BaseAudioContext`] as released.
+		released: &'static AtomicBool,
+		/// [`Receiver`](channel::Receiver) for [`MessageCommand`]s.
 		#[cfg(feature = "message")]
-		node: AudioWorkletNode,
-		/// [`Receiver`](channel::Receiver) for [`SpawnData`].
-		#[cfg(feature = "message")]
-		spawn_receiver: channel::Receiver<SpawnData>,
+		spawn_receiver: channel::Receiver<MessageCommand>,
 		/// Message to be sent.
 		#[cfg(feature = "message")]
 		message: Option<MessageState>,
@@ -358,8 +373,8 @@ impl Debug for State {
 				memory_receiver,
 				#[cfg(feature = "message")]
 				task,
-				#[cfg(feature = "message")]
-				node,
+				port,
+				released,
 				#[cfg(feature = "message")]
 				spawn_receiver,
 				#[cfg(feature = "message")]
@@ -369,11 +384,12 @@ impl Debug for State {
 				debug_struct
 					.field("context", context)
 					.field("thread", thread)
-					.field("memory_receiver", memory_receiver);
+					.field("memory_receiver", memory_receiver)
+					.field("port", port)
+					.field("released", released);
 				#[cfg(feature = "message")]
 				debug_struct
 					.field("task", &any::type_name_of_val(task))
-					.field("node", node)
 					.field("spawn_receiver", spawn_receiver)
 					.field("message", message);
 				debug_struct.finish()
@@ -550,14 +566,21 @@ impl Future for RegisterThreadFuture {
 						memory_sender,
 					})));
 
-					let options = AudioWorkletNodeOptions::new();
-					options.set_processor_options(Some(&MODULE.with(|module| {
+					// Shared with the audio worklet, so it doesn't call into the Wasm module
+					// after it was released. It is leaked because the audio worklet might hold
+					// on to it forever.
+					let released: &'static AtomicBool = Box::leak(Box::new(AtomicBool::new(false)));
+
+					let processor_options = MODULE.with(|module| {
 						MEMORY.with(|memory| {
 							WORKLET_LOCK_INDEX.with(|index| {
 								Array::of5(module, memory, &stack_size.into(), index, &data.into())
 							})
 						})
-					})));
+					});
+					processor_options.push(&NonNull::from(released).into());
+					let options = AudioWorkletNodeOptions::new();
+					options.set_processor_options(Some(&processor_options));
 
 					match AudioWorkletNode::new_with_options(
 						&context,
@@ -565,16 +588,17 @@ impl Future for RegisterThreadFuture {
 						&options,
 					) {
 						Ok(node) => {
-							#[cfg(not(feature = "message"))]
-							drop(node);
+							let port = node
+								.port()
+								.expect("`AudioWorkletNode.port` is not expected to fail");
 							self.0 = Some(State::Memory {
 								context,
 								thread,
 								memory_receiver,
 								#[cfg(feature = "message")]
 								task,
-								#[cfg(feature = "message")]
-								node,
+								port,
+								released,
 								#[cfg(feature = "message")]
 								spawn_receiver,
 								#[cfg(feature = "message")]
@@ -609,11 +633,12 @@ impl Future for RegisterThreadFuture {
 				} => match Pin::new(memory_receiver).poll(cx) {
 					Poll::Ready(Some(memory)) => {
 						let State::Memory {
+							context,
 							thread,
 							#[cfg(feature = "message")]
 							task,
-							#[cfg(feature = "message")]
-							node,
+							port: node_port,
+							released,
 							#[cfg(feature = "message")]
 							spawn_receiver,
 							#[cfg(feature = "message")]
@@ -626,10 +651,6 @@ impl Future for RegisterThreadFuture {
 
 						#[cfg(feature = "message")]
 						{
-							let node_port = node
-								.port()
-								.expect("`AudioWorkletNode.port` is not expected to fail");
-
 							let channel = MessageChannel::new()
 								.expect("`new MessageChannel` is not expected to fail");
 							let port = channel.port1();
@@ -700,7 +721,15 @@ impl Future for RegisterThreadFuture {
 							}
 						}
 
-						return Poll::Ready(Ok(AudioWorkletHandle { thread, memory }));
+						let context = context.unchecked_ref::<BaseAudioContextExt>();
+						context.set_port(&node_port);
+						context.set_released(NonNull::from(released));
+
+						return Poll::Ready(Ok(AudioWorkletHandle {
+							thread,
+							memory,
+							released,
+						}));
 					}
 					Poll::Pending => {
 						self.0 = Some(state);
@@ -727,6 +756,9 @@ pub(in super::super::super) struct AudioWorkletHandle {
 	thread: Thread,
 	/// Memory handle of the corresponding audio worklet thread.
 	memory: ThreadMemory,
+	/// Marks the [`BaseAudioContext`] as released, preventing further tasks
+	/// from being dispatched to the audio worklet thread.
+	released: &'static AtomicBool,
 }
 
 impl AudioWorkletHandle {
@@ -743,6 +775,10 @@ impl AudioWorkletHandle {
 	///
 	/// See [`ThreadMemory::release()`].
 	pub(crate) unsafe fn release(self) -> Result<(), Self> {
+		// Mark as released before releasing the memory, so the audio worklet stops
+		// calling into the Wasm module, e.g. to drop garbage collected processors.
+		self.released.store(true, Ordering::Relaxed);
+
 		// SAFETY: See `ThreadMemory::release()`. Other safety guarantees have to be
 		// uphold by the caller.
 		let result = unsafe { self.memory.release() };
@@ -758,10 +794,15 @@ impl AudioWorkletHandle {
 
 				Ok(())
 			}
-			Err(memory) => Err(Self {
-				thread: self.thread,
-				memory,
-			}),
+			Err(memory) => {
+				self.released.store(false, Ordering::Relaxed);
+
+				Err(Self {
+					thread: self.thread,
+					memory,
+					released: self.released,
+				})
+			}
 		}
 	}
 }
@@ -790,3 +831,18 @@ pub unsafe fn __web_thread_worklet_entry(
 	let task: Task = *unsafe { Box::from_raw(task.as_ptr()) };
 	task(message);
 }
+
+/// Entry function for tasks dispatched to an already running worklet.
+///
+/// # Safety
+///
+/// `task` has to be a valid pointer to [`Task`].
+#[wasm_bindgen(skip_typescript)]
+#[allow(unreachable_pub)]
+pub unsafe fn __web_thread_worklet_run(task: NonNull<Task>) {
+	// SAFETY: Has to be a valid pointer to a `Task`. We only call
+	// `__web_thread_worklet_run` from `worklet.js`. The data sent to it comes
+	// only from `register_processor_from_main()`.
+	let task: Task = *unsafe { Box::from_raw(task.as_ptr()) };
+	task(JsValue::UNDEFINED);
+}
@@ -0,0 +1,61 @@
+//! Rendering an [`ExtendAudioWorkletProcessor`] with an
+//! [`OfflineAudioContext`].
+
+use std::io;
+
+use js_sys::Array;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{AudioBuffer, AudioWorkletNodeOptions, OfflineAudioContext};
+
+use crate::web::audio_worklet::ExtendAudioWorkletProcessor;
+
+/// Name the [`ExtendAudioWorkletProcessor`] is registered with.
+const NAME: &str = "__web_thread_render_offline";
+
+/// Implementation for [`crate::web::audio_worklet::render_offline()`].
+pub(in super::super::super) async fn render_offline<P: 'static + ExtendAudioWorkletProcessor>(
+	sample_rate: f32,
+	frames: u32,
+	channels: u32,
+	data: P::Data,
+) -> io::Result<Vec<Vec<f32>>>
+where
+	P::Data: Send,
+{
+	let context = OfflineAudioContext::new_with_number_of_channels_and_length_and_sample_rate(
+		channels,
+		frames,
+		sample_rate,
+	)
+	.map_err(super::super::error_from_exception)?;
+
+	super::register_thread(context.clone().into(), None, || ()).await?;
+	// Only resolves after the audio worklet registered the processor, so it is
+	// available right away.
+	super::register_processor_from_main::<P>(&context, NAME).await?;
+
+	let options = AudioWorkletNodeOptions::new();
+	options.set_number_of_inputs(0);
+	options.set_output_channel_count(&Array::of1(&channels.into()));
+	let node = super::audio_worklet_node::<P>(&context, NAME, data, Some(&options))
+		.map_err(|error| error.error)?;
+	node.connect_with_audio_node(&context.destination())
+		.map_err(super::super::error_from_exception)?;
+
+	let promise = context
+		.start_rendering()
+		.map_err(super::super::error_from_exception)?;
+	let buffer: AudioBuffer = JsFuture::from(promise)
+		.await
+		.map_err(super::super::error_from_exception)?
+		.unchecked_into();
+
+	(0..channels)
+		.map(|channel| {
+			buffer
+				.get_channel_data(channel)
+				.map_err(super::super::error_from_exception)
+		})
+		.collect()
+}
@@ -1,17 +1,18 @@
 //! Main thread initialization and command handling.
 
-use std::cell::RefCell;
+use std::cell::{OnceCell, RefCell};
 use std::collections::HashMap;
 use std::pin::Pin;
+use std::rc::Rc;
 use std::sync::atomic::AtomicI32;
 use std::sync::OnceLock;
 
 use web_sys::Worker;
 #[cfg(feature = "message")]
-use {wasm_bindgen::closure::Closure, web_sys::MessageEvent};
+use {super::spawn::message::InboxMessage, wasm_bindgen::closure::Closure, web_sys::MessageEvent};
 
 use super::super::ThreadId;
-use super::channel::{self, Sender};
+use super::channel::{self, Receiver, Sender};
 use super::memory::ThreadMemory;
 use super::spawn::{self, SpawnData};
 use super::wait_async::WaitAsync;
@@ -27,6 +28,8 @@ static COMMAND_SENDER: OnceLock<Sender<Command>> = OnceLock::new();
 thread_local! {
 	/// Containing all spawned workers.
 	pub(super) static WORKERS: RefCell<HashMap<ThreadId, State>> = RefCell::new(HashMap::new());
+	/// [`Command`] [`Receiver`] on the main thread.
+	static COMMAND_RECEIVER: OnceCell<Rc<Receiver<Command>>> = const { OnceCell::new() };
 }
 
 /// State for each [`Worker`].
@@ -36,6 +39,9 @@ pub(super) struct State {
 	/// Callback handling messages.
 	#[cfg(feature = "message")]
 	pub(super) _message_handler: Closure<dyn Fn(MessageEvent)>,
+	/// [`Sender`] for messages posted to the thread.
+	#[cfg(feature = "message")]
+	pub(super) inbox_sender: Sender<InboxMessage>,
 }
 
 /// Command sent to the main thread.
@@ -80,47 +86,15 @@ pub(super) fn init_main_thread() {
 		super::has_spawn_support();
 
 		let (sender, receiver) = channel::channel::<Command>();
+		let receiver = Rc::new(receiver);
+		COMMAND_RECEIVER.with(|cell| {
+			cell.set(Rc::clone(&receiver))
+				.expect("`COMMAND_RECEIVER` initialized twice");
+		});
 
 		wasm_bindgen_futures::spawn_local(async move {
 			while let Ok(command) = receiver.next().await {
-				match command {
-					Command::Spawn(SpawnData {
-						id,
-						name,
-						stack_size,
-						#[cfg(feature = "message")]
-						spawn_receiver,
-						task,
-					}) => {
-						spawn::spawn_internal(
-							id,
-							name.as_deref(),
-							stack_size,
-							#[cfg(feature = "message")]
-							spawn_receiver,
-							Box::new(task),
-						);
-					}
-					Command::Terminate { id, value, memory } => {
-						wasm_bindgen_futures::spawn_local(async move {
-							WaitAsync::wait(&value, 0).await;
-
-							// SAFETY: We wait until the execution block has exited and block the
-							// thread afterwards.
-							unsafe { memory.release() }.expect("attempted to clean up main thread");
-
-							let state = WORKERS.with(|workers| {
-								workers
-									.borrow_mut()
-									.remove(&id)
-									.expect("`Worker` to be terminated not found")
-							});
-							state.this.terminate();
-							#[cfg(feature = "message")]
-							state.this.set_onmessage(None);
-						});
-					}
-				}
+				handle_command(command);
 			}
 		});
 
@@ -130,3 +104,65 @@ pub(super) fn init_main_thread() {
 		sender
 	});
 }
+
+/// Executes all pending [`Command`]s immediately. Used to make sure that
+/// threads spawned from other threads are known before accessing [`WORKERS`].
+#[cfg(feature = "message")]
+pub(super) fn handle_commands() {
+	let receiver = COMMAND_RECEIVER.with(|cell| {
+		Rc::clone(
+			cell.get()
+				.expect("handling `Command`s before `COMMAND_RECEIVER` is initialized"),
+		)
+	});
+
+	while let Ok(command) = receiver.try_recv() {
+		handle_command(command);
+	}
+}
+
+/// Executes a [`Command`].
+fn handle_command(command: Command) {
+	match command {
+		Command::Spawn(SpawnData {
+			id,
+			name,
+			stack_size,
+			#[cfg(feature = "message")]
+			spawn_receiver,
+			#[cfg(feature = "message")]
+			inbox_sender,
+			task,
+		}) => {
+			spawn::spawn_internal(
+				id,
+				name.as_deref(),
+				stack_size,
+				#[cfg(feature = "message")]
+				spawn_receiver,
+				#[cfg(feature = "message")]
+				inbox_sender,
+				Box::new(task),
+			);
+		}
+		Command::Terminate { id, value, memory } => {
+			wasm_bindgen_futures::spawn_local(async move {
+				WaitAsync::wait(&value, 0).await;
+
+				// SAFETY: We wait until the execution block has exited and block the
+				// thread afterwards.
+				unsafe { memory.release() }.expect("attempted to clean up main thread");
+
+				let state = WORKERS.with(|workers| {
+					workers
+						.borrow_mut()
+						.remove(&id)
+						.expect("`Worker` to be terminated not found")
+				});
+				state.this.terminate();
+				#[cfg(feature = "message")]
+				state.this.set_onmessage(None);
+			});
+		}
+	}
+}
@@ -15,6 +15,7 @@ mod spawn;
 mod url;
 mod wait_async;
 
+use std::any::Any;
 use std::fmt::{self, Debug, Formatter};
 use std::future::Future;
 use std::panic::RefUnwindSafe;
@@ -23,21 +24,30 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, LazyLock};
 use std::task::{Context, Poll};
 use std::time::Duration;
+#[cfg(feature = "audio-worklet")]
+use std::{arch::wasm32, sync::atomic::AtomicI32};
 use std::{io, ptr, thread};
 
 use atomic_waker::AtomicWaker;
 use js_sys::WebAssembly::{Memory, Module};
 use js_sys::{Atomics, Int32Array};
 use wasm_bindgen::JsCast;
-#[cfg(any(feature = "audio-worklet", feature = "message"))]
-use {std::io::Error, wasm_bindgen::JsValue, web_sys::DomException};
+#[cfg(feature = "message")]
+use {
+	crate::web::message::{MessageError, MessageErrorKind, MessageSend},
+	std::io::{Error, ErrorKind},
+};
 
 use self::oneshot::Receiver;
-pub(super) use self::parker::Parker;
+pub(super) use self::parker::{futex_wait, futex_wake, Parker};
+#[cfg(feature = "audio-worklet")]
+pub(super) use self::wait_async::WaitAsync;
+#[cfg(any(feature = "audio-worklet", feature = "message"))]
+use super::error_from_exception;
 use super::js::GlobalExt;
-use super::{ScopedJoinHandle, Thread, ThreadId, THREAD};
 #[cfg(feature = "message")]
-use crate::web::message::MessageSend;
+use super::message_error_from_exception;
+use super::{ScopedJoinHandle, Thread, ThreadId, THREAD};
 
 thread_local! {
 	/// [`Memory`] of the Wasm module.
@@ -102,7 +112,7 @@ impl Builder {
 		self,
 		task: F1,
 		message: M,
-	) -> io::Result<JoinHandle<T>>
+	) -> Result<JoinHandle<T>, MessageError<M>>
 	where
 		F1: 'static + FnOnce(M) -> F2 + Send,
 		F2: 'static + Future<Output = T>,
@@ -112,6 +122,22 @@ impl Builder {
 		unsafe { spawn::message::spawn(task, self.name, self.stack_size, None, message) }
 	}
 
+	/// Implementation for
+	/// [`BuilderExt::spawn_returning_message()`](crate::web::BuilderExt::spawn_returning_message).
+	#[cfg(feature = "message")]
+	pub(super) fn spawn_returning_message_internal<F1, F2, O>(
+		self,
+		task: F1,
+	) -> io::Result<JoinHandle<O>>
+	where
+		F1: 'static + FnOnce() -> F2 + Send,
+		F2: 'static + Future<Output = O>,
+		O: 'static + MessageSend,
+	{
+		// SAFETY: `F` and `O` are `'static`.
+		unsafe { spawn::message::spawn_returning(task, self.name, self.stack_size, None) }
+	}
+
 	/// Implementation of [`std::thread::Builder::spawn_scoped()`].
 	pub(super) fn spawn_scoped<'scope, F, T>(
 		self,
@@ -162,7 +188,7 @@ impl Builder {
 		scope: &Scope,
 		task: F1,
 		message: M,
-	) -> io::Result<ScopedJoinHandle<'scope, T>>
+	) -> Result<ScopedJoinHandle<'scope, T>, MessageError<M>>
 	where
 		F1: 'scope + FnOnce(M) -> F2 + Send,
 		F2: 'scope + Future<Output = T>,
@@ -183,6 +209,32 @@ impl Builder {
 		result.map(|handle| ScopedJoinHandle::new(handle))
 	}
 
+	/// Implementation for
+	/// [`BuilderExt::spawn_scoped_returning_message()`](crate::web::BuilderExt::spawn_scoped_returning_message).
+	#[cfg(feature = "message")]
+	pub(super) fn spawn_scoped_returning_message_internal<'scope, F1, F2, O>(
+		self,
+		scope: &Scope,
+		task: F1,
+	) -> io::Result<ScopedJoinHandle<'scope, O>>
+	where
+		F1: 'scope + FnOnce() -> F2 + Send,
+		F2: 'scope + Future<Output = O>,
+		O: 'static + MessageSend,
+	{
+		// SAFETY: `Scope` will prevent this thread to outlive its lifetime.
+		let result = unsafe {
+			spawn::message::spawn_returning(
+				task,
+				self.name,
+				self.stack_size,
+				Some(Arc::clone(&scope.0)),
+			)
+		};
+
+		result.map(|handle| ScopedJoinHandle::new(handle))
+	}
+
 	/// Implementation of [`std::thread::Builder::stack_size()`].
 	pub(super) fn stack_size(mut self, mut size: usize) -> Self {
 		/// Wasm page size according to the specification is 64 Ki.
@@ -221,7 +273,6 @@ impl<T> JoinHandle<T> {
 	}
 
 	/// Implementation of [`std::thread::JoinHandle::join()`].
-	#[allow(clippy::unnecessary_wraps)]
 	pub(super) fn join(self) -> thread::Result<T> {
 		assert_ne!(
 			self.thread().id(),
@@ -229,11 +280,10 @@ impl<T> JoinHandle<T> {
 			"called `JoinHandle::join()` on the thread to join"
 		);
 
-		Ok(self
-			.receiver
+		self.receiver
 			.expect("`JoinHandle::join()` called after `JoinHandleFuture` polled to completion")
 			.receive()
-			.expect("thread terminated without returning"))
+			.ok_or_else(output_error)
 	}
 
 	/// Implementation of [`std::thread::JoinHandle::thread()`].
@@ -242,6 +292,25 @@ impl<T> JoinHandle<T> {
 		&self.thread
 	}
 
+	/// Implementation for
+	/// [`JoinHandleExt::post_message()`](crate::web::JoinHandleExt::post_message).
+	#[cfg(feature = "message")]
+	pub(super) fn post_message_internal<M>(&self, message: M) -> Result<(), MessageError<M>>
+	where
+		M: 'static + MessageSend,
+	{
+		if self.is_finished() {
+			return Err(MessageError {
+				message,
+				kind: MessageErrorKind::Closed,
+				name: None,
+				error: Error::new(ErrorKind::BrokenPipe, "thread has already finished"),
+			});
+		}
+
+		spawn::message::post_message(self.thread.id(), message)
+	}
+
 	/// Implementation for
 	/// [`JoinHandleFuture::poll()`](crate::web::JoinHandleFuture).
 	pub(super) fn poll(&mut self, cx: &mut Context<'_>) -> Poll<thread::Result<T>> {
@@ -262,11 +331,18 @@ impl<T> JoinHandle<T> {
 				self.receiver = Some(receiver);
 				Poll::Pending
 			}
-			Poll::Ready(None) => unreachable!("thread terminated without returning"),
+			Poll::Ready(None) => Poll::Ready(Err(output_error())),
 		}
 	}
 }
 
+/// Error returned when joining a thread that terminated without delivering
+/// its output, which happens if the output of a thread spawned with
+/// `spawn_returning_message()` was unable to be sent.
+fn output_error() -> Box<dyn Any + Send> {
+	Box::new("failed to send output of thread")
+}
+
 impl Thread {
 	/// Registers the given `thread`.
 	fn register(thread: Self) {
@@ -329,11 +405,36 @@ impl Scope {
 	}
 }
 
+/// Implementation for
+/// [`web::message::receive()`](crate::web::message::receive).
+#[cfg(feature = "message")]
+pub(super) fn poll_message<M>(cx: &Context<'_>) -> Poll<M>
+where
+	M: 'static + MessageSend,
+{
+	spawn::message::poll_message(cx)
+}
+
 /// Implementation of [`std::thread::sleep()`].
 pub(super) fn sleep(dur: Duration) {
 	thread::sleep(dur);
 }
 
+/// Blocks until `value` is notified. Returns directly if `value` doesn't hold
+/// `check`.
+#[cfg(feature = "audio-worklet")]
+pub(super) fn atomic_wait(value: &AtomicI32, check: i32) {
+	// SAFETY: This is safe because `AtomicI32::as_ptr()` returns a valid pointer.
+	unsafe { wasm32::memory_atomic_wait32(value.as_ptr(), check, -1) };
+}
+
+/// Wakes up all threads waiting on `value`.
+#[cfg(feature = "audio-worklet")]
+pub(super) fn atomic_notify_all(value: &AtomicI32) {
+	// SAFETY: This is safe because `AtomicI32::as_ptr()` returns a valid pointer.
+	unsafe { wasm32::memory_atomic_notify(value.as_ptr(), u32::MAX) };
+}
+
 /// Tests if blocking is supported.
 pub(super) fn test_block_support() -> bool {
 	let value = Pin::new(&0);
@@ -389,11 +490,3 @@ fn i32_to_buffer_index(ptr: *const i32) -> u32 {
 	let index = ptr as u32 / 4;
 	index
 }
-
-/// Convert a [`JsValue`] to an [`DomException`] and then to an [`Error`].
-#[cfg(any(feature = "audio-worklet", feature = "message"))]
-fn error_from_exception(error: JsValue) -> Error {
-	let error: DomException = error.unchecked_into();
-
-	Error::other(format!("{}: {}", error.name(), error.message()))
-}
@@ -18,6 +18,8 @@ export function __web_thread_worklet_entry(
 	port?: MessagePort
 ): void
 
+export function __web_thread_worklet_run(task: Pointer<typeof Task>): void
+
 export function __web_thread_worklet_register(data: Pointer<typeof Data>): void
 
 export function __web_thread_worker_entry(
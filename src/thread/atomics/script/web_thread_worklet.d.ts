@@ -11,6 +11,11 @@ export class __WebThreadProcessor {
 		outputs: Float32Array[][],
 		parameters: Record<string, Float32Array>
 	): boolean
+	hasBuffers(): boolean
+	layout(layout: Uint32Array, names: string[]): number
+	processBuffers(): boolean
+	message(message: number, serialized: unknown): void
+	free(): void
 }
 
 export class __WebThreadProcessorConstructor {
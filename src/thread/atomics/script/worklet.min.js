@@ -1 +1 @@
-import{initSync as r,__web_thread_worklet_entry as e}from"@shim.js";globalThis.__web_thread_register_processor=(r,e)=>{globalThis.registerProcessor(r,class extends AudioWorkletProcessor{constructor(r){super(),this.__web_thread_this=e.instantiate(this,r)}process(r,e,s){return this.__web_thread_this.process(r,e,s)}static get parameterDescriptors(){return e.parameterDescriptors()}})},registerProcessor("__web_thread_worklet",class extends AudioWorkletProcessor{constructor(s){super();let[t,o,i,c,_]=s.processorOptions;r({module:t,memory:o,thread_stack_size:i});let a=new Int32Array(o.buffer);Atomics.store(a,c,0),Atomics.notify(a,c),e(_)}process(){return!1}});
\ No newline at end of file
+import{initSync as r,__web_thread_worklet_entry as e,__web_thread_worklet_run as u}from"@shim.js";import{acknowledge as k,dispose as D,initReleased as I,processBuffers as b,registry as g}from"@worklet_common.js";let w;globalThis.__web_thread_register_processor=(r,e)=>{globalThis.registerProcessor(r,class extends AudioWorkletProcessor{constructor(r){super(),this.__web_thread_this=e.instantiate(this,r),this.__web_thread_has_buffers=this.__web_thread_this.hasBuffers(),this.__web_thread_buffers=void 0,this.__web_thread_failed=!1,this.__web_thread_disposed=!1,g.register(this,this.__web_thread_this,this),this.port.addEventListener("message",t=>{D(this,t.data)}),this.port.start()}process(r,e,s){if(this.__web_thread_disposed)return!1;try{return this.__web_thread_has_buffers?b(this,w,r,e,s):this.__web_thread_this.process(r,e,s)}catch(t){throw g.unregister(this),this.__web_thread_failed=!0,t}}static get parameterDescriptors(){return e.parameterDescriptors()}})},registerProcessor("__web_thread_worklet",class extends AudioWorkletProcessor{constructor(s){super();let[t,o,i,c,_,l]=s.processorOptions;r({module:t,memory:o,thread_stack_size:i}),w=o,I(o,l);let a=new Int32Array(o.buffer);Atomics.store(a,c,0),Atomics.notify(a,c),e(_),this.port.onmessage=t=>{let[e,s]=t.data;k(this.port,s,()=>u(e))}}process(){return!1}});
\ No newline at end of file
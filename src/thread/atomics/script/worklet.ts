@@ -1,14 +1,27 @@
-import { initSync, __web_thread_worklet_entry, Pointer, type Task } from '@shim.js'
 import {
-	__WebThreadProcessorConstructor,
-	__WebThreadProcessor,
-	AudioParamDescriptor,
-} from 'web_thread_worklet'
+	initSync,
+	__web_thread_worklet_entry,
+	__web_thread_worklet_run,
+	Pointer,
+	type Task,
+} from '@shim.js'
+import { __WebThreadProcessorConstructor, AudioParamDescriptor } from 'web_thread_worklet'
+import {
+	acknowledge,
+	BuffersProcessor,
+	dispose,
+	DisposableProcessor,
+	initReleased,
+	processBuffers,
+	registry,
+} from '@worklet_common.js'
 
-interface AudioWorkletProcessorExt extends AudioWorkletProcessor {
-	__web_thread_this: __WebThreadProcessor
+interface AudioWorkletProcessorExt extends BuffersProcessor, DisposableProcessor {
+	__web_thread_has_buffers: boolean
 }
 
+let wasmMemory: WebAssembly.Memory
+
 globalThis.__web_thread_register_processor = (
 	name: string,
 	processor: __WebThreadProcessorConstructor
@@ -20,6 +33,15 @@ globalThis.__web_thread_register_processor = (
 				super()
 				const this_ = this as AudioWorkletProcessor as AudioWorkletProcessorExt
 				this_.__web_thread_this = processor.instantiate(this, options)
+				this_.__web_thread_has_buffers = this_.__web_thread_this.hasBuffers()
+				this_.__web_thread_buffers = undefined
+				this_.__web_thread_failed = false
+				this_.__web_thread_disposed = false
+				registry.register(this, this_.__web_thread_this, this)
+				this_.port.addEventListener('message', event => {
+					dispose(this_, event.data)
+				})
+				this_.port.start()
 			}
 
 			process(
@@ -28,7 +50,20 @@ globalThis.__web_thread_register_processor = (
 				outputs: Float32Array[][],
 				parameters: Record<string, Float32Array>
 			): boolean {
-				return this.__web_thread_this.process(inputs, outputs, parameters)
+				if (this.__web_thread_disposed) return false
+
+				try {
+					if (this.__web_thread_has_buffers)
+						return processBuffers(this, wasmMemory, inputs, outputs, parameters)
+
+					return this.__web_thread_this.process(inputs, outputs, parameters)
+				} catch (error) {
+					// After a panic the processor might be in an invalid state, so we leak it
+					// instead of dropping it.
+					registry.unregister(this)
+					this.__web_thread_failed = true
+					throw error
+				}
 			}
 
 			static get parameterDescriptors(): AudioParamDescriptor[] {
@@ -44,20 +79,31 @@ registerProcessor(
 		constructor(options: AudioWorkletNodeOptions) {
 			super()
 
-			const [module, memory, stackSize, workletLock, task] = options.processorOptions as [
-				WebAssembly.Module,
-				WebAssembly.Memory,
-				number | undefined,
-				number,
-				Pointer<typeof Task>,
-			]
+			const [module, memory, stackSize, workletLock, task, released] =
+				options.processorOptions as [
+					WebAssembly.Module,
+					WebAssembly.Memory,
+					number | undefined,
+					number,
+					Pointer<typeof Task>,
+					number,
+				]
 
 			initSync({ module: module, memory: memory, thread_stack_size: stackSize })
+			wasmMemory = memory
+			initReleased(memory, released)
 			const memoryArray = new Int32Array(memory.buffer)
 			Atomics.store(memoryArray, workletLock, 0)
 			Atomics.notify(memoryArray, workletLock)
 
 			__web_thread_worklet_entry(task)
+
+			this.port.onmessage = event => {
+				const [task, id] = event.data as [Pointer<typeof Task>, number]
+				acknowledge(this.port, id, () => {
+					__web_thread_worklet_run(task)
+				})
+			}
 		}
 
 		process(): boolean {
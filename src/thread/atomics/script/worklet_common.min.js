@@ -0,0 +1 @@
+let R,g=new FinalizationRegistry(t=>{v()||t.free()});function I(t,r){R=new Uint8Array(t.buffer,r,1)}function v(){return void 0!==R&&0!==Atomics.load(R,0)}function D(t,r){return"object"==typeof r&&null!==r&&"__web_thread_dispose"in r&&(t.__web_thread_failed||t.__web_thread_disposed||(t.__web_thread_disposed=!0,g.unregister(t),v()||t.__web_thread_this.free()),!0)}function k(t,r,e){try{e()}catch(s){throw t.postMessage([r,!1]),s}t.postMessage([r,!0])}function b(t,w,r,e,s){let o=e[0]?.[0]?.length??r[0]?.[0]?.length??128,i=t.__web_thread_buffers;(void 0===i||!m(i.layout,r,e,o))&&(i=y(t.__web_thread_this,w,r,e,s,o),t.__web_thread_buffers=i);let l=i.samples,n=0;for(let f of r)for(let c of f)l.set(c,n),n+=o;n+=i.outputs.length*o;let a=n+i.names.length*o;for(let f of i.names){let c=s[f];void 0===c?l[a]=0:(l.set(c,n),l[a]=c.length),n+=o,++a}let h=t.__web_thread_this.processBuffers(),u=0;for(let f of e)for(let c of f){let d=i.outputs[u++];void 0!==d&&c.set(d)}return h}function m(t,r,e,s){let o=p(t,0,r);if(-1===o)return!1;let i=p(t,o,e);return -1!==i&&t[i]===s}function p(t,r,e){if(t[r++]!==e.length)return -1;for(let s of e)if(t[r++]!==s.length)return -1;return r}function y(t,w,r,e,s,o){let i=[r.length,...r.map(t=>t.length),e.length,...e.map(t=>t.length),o],l=Object.keys(s),n=t.layout(new Uint32Array(i),l),a=r.reduce((t,r)=>t+r.length,0),h=e.reduce((t,r)=>t+r.length,0),u=new Float32Array(w.buffer,n,(a+h+l.length)*o+l.length),f=Array.from({length:h},(t,r)=>{let e=(a+r)*o;return u.subarray(e,e+o)});return{layout:i,names:l,samples:u,outputs:f}}export{k as acknowledge,D as dispose,I as initReleased,b as processBuffers,g as registry};
\ No newline at end of file
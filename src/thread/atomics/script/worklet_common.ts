@@ -0,0 +1,172 @@
+import { __WebThreadProcessor } from 'web_thread_worklet'
+
+export interface BuffersProcessor {
+	__web_thread_this: __WebThreadProcessor
+	__web_thread_buffers: Buffers | undefined
+}
+
+export interface DisposableProcessor extends AudioWorkletProcessor {
+	__web_thread_this: __WebThreadProcessor
+	__web_thread_failed: boolean
+	__web_thread_disposed: boolean
+}
+
+interface Buffers {
+	layout: number[]
+	names: string[]
+	samples: Float32Array
+	outputs: Float32Array[]
+}
+
+let released: Uint8Array | undefined
+
+export const registry = new FinalizationRegistry<__WebThreadProcessor>(processor => {
+	if (!isReleased()) processor.free()
+})
+
+// Stores the address of the flag set by `AudioWorkletHandle::release()`.
+export function initReleased(memory: WebAssembly.Memory, pointer: number): void {
+	released = new Uint8Array(memory.buffer, pointer, 1)
+}
+
+// After `AudioWorkletHandle::release()` we must not call into the Wasm module
+// anymore.
+function isReleased(): boolean {
+	return released !== undefined && Atomics.load(released, 0) !== 0
+}
+
+// Drops the processor when receiving the message sent by
+// `RustAudioWorkletNode::dispose()`. Returns `true` if `data` was such a message.
+export function dispose(this_: DisposableProcessor, data: unknown): boolean {
+	const isDispose = typeof data === 'object' && data !== null && '__web_thread_dispose' in data
+	if (!isDispose) return false
+	if (this_.__web_thread_failed || this_.__web_thread_disposed) return true
+
+	this_.__web_thread_disposed = true
+	registry.unregister(this_)
+	if (!isReleased()) this_.__web_thread_this.free()
+
+	return true
+}
+
+// Posts `[id, success]` to `port` after running `task`, so the main thread
+// knows when `task` has finished.
+export function acknowledge(port: MessagePort, id: number, task: () => void): void {
+	try {
+		task()
+	} catch (error) {
+		port.postMessage([id, false])
+		throw error
+	}
+
+	port.postMessage([id, true])
+}
+
+export function processBuffers(
+	this_: BuffersProcessor,
+	memory: WebAssembly.Memory,
+	inputs: Float32Array[][],
+	outputs: Float32Array[][],
+	parameters: Record<string, Float32Array>
+): boolean {
+	const frames = outputs[0]?.[0]?.length ?? inputs[0]?.[0]?.length ?? 128
+	let buffers = this_.__web_thread_buffers
+
+	if (buffers === undefined || !matchesLayout(buffers.layout, inputs, outputs, frames)) {
+		buffers = updateLayout(this_.__web_thread_this, memory, inputs, outputs, parameters, frames)
+		this_.__web_thread_buffers = buffers
+	}
+
+	const samples = buffers.samples
+	let offset = 0
+
+	for (const input of inputs) {
+		for (const channel of input) {
+			samples.set(channel, offset)
+			offset += frames
+		}
+	}
+
+	offset += buffers.outputs.length * frames
+	let lengthOffset = offset + buffers.names.length * frames
+
+	for (const name of buffers.names) {
+		const values = parameters[name]
+
+		if (values === undefined) {
+			samples[lengthOffset] = 0
+		} else {
+			samples.set(values, offset)
+			samples[lengthOffset] = values.length
+		}
+
+		offset += frames
+		++lengthOffset
+	}
+
+	const result = this_.__web_thread_this.processBuffers()
+	let index = 0
+
+	for (const output of outputs) {
+		for (const channel of output) {
+			const buffer = buffers.outputs[index++]
+			if (buffer !== undefined) channel.set(buffer)
+		}
+	}
+
+	return result
+}
+
+function matchesLayout(
+	layout: number[],
+	inputs: Float32Array[][],
+	outputs: Float32Array[][],
+	frames: number
+): boolean {
+	const index = matchesPorts(layout, 0, inputs)
+	if (index === -1) return false
+	const end = matchesPorts(layout, index, outputs)
+	return end !== -1 && layout[end] === frames
+}
+
+function matchesPorts(layout: number[], index: number, ports: Float32Array[][]): number {
+	if (layout[index++] !== ports.length) return -1
+
+	for (const port of ports) {
+		if (layout[index++] !== port.length) return -1
+	}
+
+	return index
+}
+
+function updateLayout(
+	processor: __WebThreadProcessor,
+	memory: WebAssembly.Memory,
+	inputs: Float32Array[][],
+	outputs: Float32Array[][],
+	parameters: Record<string, Float32Array>,
+	frames: number
+): Buffers {
+	const layout = [
+		inputs.length,
+		...inputs.map(input => input.length),
+		outputs.length,
+		...outputs.map(output => output.length),
+		frames,
+	]
+	const names = Object.keys(parameters)
+	const pointer = processor.layout(new Uint32Array(layout), names)
+	const inputChannels = inputs.reduce((channels, input) => channels + input.length, 0)
+	const outputChannels = outputs.reduce((channels, output) => channels + output.length, 0)
+	const samples = new Float32Array(
+		memory.buffer,
+		pointer,
+		(inputChannels + outputChannels + names.length) * frames + names.length
+	)
+	const outputViews = Array.from({ length: outputChannels }, (_, channel) => {
+		const start = (inputChannels + channel) * frames
+		return samples.subarray(start, start + frames)
+	})
+
+	return { layout, names, samples, outputs: outputViews }
+}
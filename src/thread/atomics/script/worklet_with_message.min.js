@@ -1 +1 @@
-import{initSync as s,__web_thread_worklet_register as e,__web_thread_worklet_entry as r}from"@shim.js";globalThis.__web_thread_register_processor=(s,e)=>{globalThis.registerProcessor(s,class extends AudioWorkletProcessor{constructor(s){super(),this.__web_thread_this=e.instantiate(this,s)}process(s,e,r){return this.__web_thread_this.process(s,e,r)}static get parameterDescriptors(){return e.parameterDescriptors()}})},registerProcessor("__web_thread_worklet",class extends AudioWorkletProcessor{constructor(t){super();let o=this,[i,c,n,a,_]=t.processorOptions;s({module:i,memory:c,thread_stack_size:n});let p=new Int32Array(c.buffer);Atomics.store(p,a,0),Atomics.notify(p,a),e(_),o.continueProcessing=!0,o.port.onmessage=s=>{o.continueProcessing=!1,o.port.onmessage=null;let[e,t]=s.data;void 0!==e&&r(e,t,s.ports[0])}}process(){return this.continueProcessing}});
\ No newline at end of file
+import{initSync as s,__web_thread_worklet_register as e,__web_thread_worklet_entry as r,__web_thread_worklet_run as u}from"@shim.js";import{acknowledge as k,dispose as D,initReleased as I,processBuffers as b,registry as g}from"@worklet_common.js";let w;globalThis.__web_thread_register_processor=(s,e)=>{globalThis.registerProcessor(s,class extends AudioWorkletProcessor{constructor(s){super(),this.__web_thread_this=e.instantiate(this,s),this.__web_thread_has_buffers=this.__web_thread_this.hasBuffers(),this.__web_thread_buffers=void 0,this.__web_thread_failed=!1,this.__web_thread_disposed=!1,g.register(this,this.__web_thread_this,this),this.port.addEventListener("message",t=>{let s=t.data;D(this,s)||!this.__web_thread_failed&&!this.__web_thread_disposed&&"object"==typeof s&&null!==s&&"__web_thread_message"in s&&this.__web_thread_this.message(s.__web_thread_message,s.__web_thread_serialized)}),this.port.start()}process(s,e,r){if(this.__web_thread_disposed)return!1;try{return this.__web_thread_has_buffers?b(this,w,s,e,r):this.__web_thread_this.process(s,e,r)}catch(t){throw g.unregister(this),this.__web_thread_failed=!0,t}}static get parameterDescriptors(){return e.parameterDescriptors()}})},registerProcessor("__web_thread_worklet",class extends AudioWorkletProcessor{constructor(t){super();let o=this,[i,c,n,a,_,l]=t.processorOptions;s({module:i,memory:c,thread_stack_size:n}),w=c,I(c,l);let p=new Int32Array(c.buffer);Atomics.store(p,a,0),Atomics.notify(p,a),e(_),o.continueProcessing=!0,o.port.onmessage=s=>{o.continueProcessing=!1,o.port.onmessage=t=>{let[e,i]=t.data;k(o.port,i,()=>u(e))};let[e,t]=s.data;void 0!==e&&r(e,t,s.ports[0])}}process(){return this.continueProcessing}});
\ No newline at end of file
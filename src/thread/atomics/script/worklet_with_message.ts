@@ -2,18 +2,35 @@ import {
 	initSync,
 	__web_thread_worklet_register,
 	__web_thread_worklet_entry,
+	__web_thread_worklet_run,
 	Pointer,
 	type Data,
 	type Message,
 	type Task,
 } from '@shim.js'
-import { __WebThreadProcessor, AudioParamDescriptor } from 'web_thread_worklet'
+import { AudioParamDescriptor } from 'web_thread_worklet'
+import {
+	acknowledge,
+	BuffersProcessor,
+	dispose,
+	DisposableProcessor,
+	initReleased,
+	processBuffers,
+	registry,
+} from '@worklet_common.js'
 
-interface AudioWorkletProcessorExt extends AudioWorkletProcessor {
-	__web_thread_this: __WebThreadProcessor
+interface AudioWorkletProcessorExt extends BuffersProcessor, DisposableProcessor {
+	__web_thread_has_buffers: boolean
 	continueProcessing: boolean
 }
 
+interface PortMessage {
+	__web_thread_message: number
+	__web_thread_serialized: unknown
+}
+
+let wasmMemory: WebAssembly.Memory
+
 globalThis.__web_thread_register_processor = (name, processor) => {
 	globalThis.registerProcessor(
 		name,
@@ -22,6 +39,30 @@ globalThis.__web_thread_register_processor = (name, processor) => {
 				super()
 				const this_ = this as AudioWorkletProcessor as AudioWorkletProcessorExt
 				this_.__web_thread_this = processor.instantiate(this, options)
+				this_.__web_thread_has_buffers = this_.__web_thread_this.hasBuffers()
+				this_.__web_thread_buffers = undefined
+				this_.__web_thread_failed = false
+				this_.__web_thread_disposed = false
+				registry.register(this, this_.__web_thread_this, this)
+				this_.port.addEventListener('message', event => {
+					const data = event.data as unknown
+					if (dispose(this_, data)) return
+
+					if (
+						!this_.__web_thread_failed &&
+						!this_.__web_thread_disposed &&
+						typeof data === 'object' &&
+						data !== null &&
+						'__web_thread_message' in data
+					) {
+						const message = data as PortMessage
+						this_.__web_thread_this.message(
+							message.__web_thread_message,
+							message.__web_thread_serialized
+						)
+					}
+				})
+				this_.port.start()
 			}
 
 			process(
@@ -31,7 +72,21 @@ globalThis.__web_thread_register_processor = (name, processor) => {
 				parameters: Record<string, Float32Array>
 			): boolean {
 				const this_ = this as AudioWorkletProcessorExt
-				return this_.__web_thread_this.process(inputs, outputs, parameters)
+				if (this_.__web_thread_disposed) return false
+
+				try {
+					if (this_.__web_thread_has_buffers)
+						return processBuffers(this_, wasmMemory, inputs, outputs, parameters)
+
+					return this_.__web_thread_this.process(inputs, outputs, parameters)
+				} catch (error) {
+					// After a panic the processor might be in an invalid state, so we leak it
+					// instead of dropping it.
+					registry.unregister(this)
+					// Don't deliver any messages to it either.
+					this_.__web_thread_failed = true
+					throw error
+				}
 			}
 
 			static get parameterDescriptors(): AudioParamDescriptor[] {
@@ -48,15 +103,19 @@ registerProcessor(
 			super()
 			const this_ = this as AudioWorkletProcessor as AudioWorkletProcessorExt
 
-			const [module, memory, stackSize, workletLock, data] = options.processorOptions as [
-				WebAssembly.Module,
-				WebAssembly.Memory,
-				number | undefined,
-				number,
-				Pointer<typeof Data>,
-			]
+			const [module, memory, stackSize, workletLock, data, released] =
+				options.processorOptions as [
+					WebAssembly.Module,
+					WebAssembly.Memory,
+					number | undefined,
+					number,
+					Pointer<typeof Data>,
+					number,
+				]
 
 			initSync({ module, memory, thread_stack_size: stackSize })
+			wasmMemory = memory
+			initReleased(memory, released)
 			const memoryArray = new Int32Array(memory.buffer)
 			Atomics.store(memoryArray, workletLock, 0)
 			Atomics.notify(memoryArray, workletLock)
@@ -66,7 +125,12 @@ registerProcessor(
 			this_.continueProcessing = true
 			this_.port.onmessage = event => {
 				this_.continueProcessing = false
-				this_.port.onmessage = null
+				this_.port.onmessage = event => {
+					const [task, id] = event.data as [Pointer<typeof Task>, number]
+					acknowledge(this_.port, id, () => {
+						__web_thread_worklet_run(task)
+					})
+				}
 				const [task, message] = event.data as [
 					Pointer<typeof Task> | undefined,
 					Pointer<typeof Message>,
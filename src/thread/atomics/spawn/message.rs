@@ -1,9 +1,13 @@
 //! Handling message related functionality.
 
+use std::any::{Any, TypeId};
 use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::future::Future;
-use std::sync::Arc;
-use std::{io, mem};
+use std::io::{self, Error, ErrorKind};
+use std::mem;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
 
 use js_sys::{Array, Function};
 use wasm_bindgen::closure::Closure;
@@ -15,16 +19,60 @@ use super::super::super::global::Global;
 use super::super::audio_worklet::register::THREAD_LOCK_INDEXES;
 #[cfg(feature = "audio-worklet")]
 use super::super::js::ArrayExt;
+use super::super::main::WORKERS;
 use super::super::{channel, main, oneshot, JoinHandle, ScopeData, ThreadId};
 use super::{SpawnData, Task};
 use crate::thread::atomics::channel::Receiver;
-use crate::web::message::{ArrayBuilder, MessageSend};
+use crate::thread::port::{take_send, SendSlot};
+use crate::web::message::{
+	self, ArrayBuilder, MessageError, MessageErrorKind, MessageSend, PortSender, RawMessage,
+};
 
 thread_local! {
-	pub(in super::super) static SPAWN_SENDER: RefCell<Option<channel::Sender<SpawnData>>> = const { RefCell::new(None) };
+	pub(in super::super) static SPAWN_SENDER: RefCell<Option<channel::Sender<MessageCommand>>> = const { RefCell::new(None) };
+	/// Messages posted to this thread.
+	static INBOX: RefCell<Option<Inbox>> = const { RefCell::new(None) };
 }
 
-/// Internal spawn function.
+/// [`RawMessage::send`] part of a message posted to a thread.
+pub(in super::super) struct InboxMessage {
+	/// [`TypeId`] of the posted [`MessageSend`].
+	type_id: TypeId,
+	/// [`SendSlot`] holding the [`RawMessage::send`] part.
+	send: Box<dyn Any + Send>,
+}
+
+/// Command sent through [`SPAWN_SENDER`], paired with a message sent to the
+/// main thread by [`send_message()`].
+pub(in super::super) enum MessageCommand {
+	/// Spawn a new thread.
+	Spawn(SpawnData),
+	/// Post a message to a thread.
+	Post {
+		/// [`ThreadId`] of the receiving thread.
+		id: ThreadId,
+		/// [`RawMessage::send`](crate::web::message::RawMessage::send) part of
+		/// the message.
+		send: InboxMessage,
+	},
+}
+
+/// Messages posted to this thread by
+/// [`JoinHandleExt::post_message()`](crate::web::JoinHandleExt::post_message).
+struct Inbox {
+	/// Receives the [`InboxMessage`] for each message.
+	receiver: Receiver<InboxMessage>,
+	/// Received [`MessageEvent::data()`]s paired with their [`InboxMessage`]
+	/// and waiting for [`receive()`](crate::web::message::receive) to be
+	/// called with their type.
+	queue: VecDeque<(JsValue, InboxMessage)>,
+	/// [`Waker`]s of all pending polls.
+	wakers: Vec<Waker>,
+	/// Callback handling messages.
+	_message_handler: Closure<dyn Fn(MessageEvent)>,
+}
+
+/// Internal spawn function. Returns `message` back on error.
 ///
 /// # Safety
 ///
@@ -35,7 +83,7 @@ pub(in super::super) unsafe fn spawn<F1, F2, T, M>(
 	stack_size: Option<usize>,
 	scope: Option<Arc<ScopeData>>,
 	message: M,
-) -> io::Result<JoinHandle<T>>
+) -> Result<JoinHandle<T>, MessageError<M>>
 where
 	F1: FnOnce(M) -> F2 + Send,
 	F2: Future<Output = T>,
@@ -45,31 +93,35 @@ where
 	let thread = super::thread_init(name, scope.as_deref());
 	let (result_sender, result_receiver) = oneshot::channel();
 	let (spawn_sender, spawn_receiver) = channel::channel();
+	let (inbox_sender, inbox_receiver) = channel::channel();
 
 	let mut transfer_builder = ArrayBuilder::new();
-	let raw_message = message.send(&mut transfer_builder);
+	let RawMessage { serialize, send } = message.send(&mut transfer_builder);
 	let transfer = transfer_builder.finish();
+	let slot: SendSlot<M::Send> = Arc::new(Mutex::new(send));
 
 	let task: Task<'_> = Box::new({
 		let thread = thread.clone();
+		let slot = Arc::clone(&slot);
 		move |message| {
 			super::thread_runner(
 				thread,
 				stack_size,
 				result_sender,
 				spawn_sender,
+				inbox_receiver,
 				scope,
 				move || {
 					let message = (!message.is_undefined()).then_some(message);
-					let message = M::receive(message, raw_message.send);
+					let message = M::receive(message, take_send(&slot));
 					task(message)
 				},
 			)
 		}
 	});
 
-	if let Some(serialize) = raw_message.serialize {
-		if super::super::is_main_thread() {
+	if let Some(serialize) = &serialize {
+		let result = if super::super::is_main_thread() {
 			main::init_main_thread();
 
 			spawn_internal(
@@ -77,10 +129,11 @@ where
 				thread.name(),
 				stack_size,
 				spawn_receiver,
-				&serialize,
-				transfer,
+				inbox_sender,
+				serialize,
+				transfer.as_ref(),
 				Box::new(task),
-			)?;
+			)
 		} else {
 			// SAFETY: `task` has to be `'static` or `scope` has to be `Some`, which
 			// prevents this thread from outliving its lifetime.
@@ -91,29 +144,19 @@ where
 				name: thread.0.name.clone(),
 				stack_size,
 				spawn_receiver,
+				inbox_sender,
 				task,
 			};
 
-			SPAWN_SENDER
-				.with(|cell| {
-					cell.borrow()
-						.as_ref()
-						.expect("found no `Sender` in existing thread")
-						.send(data)
-				})
-				.expect("`Receiver` in main thread dropped");
-
-			Global::with(|global| match global {
-				Global::Dedicated(global) => send_message(global, &serialize, transfer),
-				#[cfg(feature = "audio-worklet")]
-				Global::Worklet => super::super::audio_worklet::register::message::MESSAGE_PORT.with(|port| {
-					let port = port
-						.get()
-						.expect("found audio worklet with uninitialized port");
-					send_message(port, &serialize, transfer)
-				}),
-				_ => unreachable!("spawning from thread not registered by `web-thread`"),
-			})?;
+			send_to_main(MessageCommand::Spawn(data), serialize, transfer.as_ref())
+		};
+
+		if let Err(error) = result {
+			return Err(super::super::message_error_from_exception(
+				error,
+				transfer.as_ref(),
+				M::receive(Some(serialize.clone()), take_send(&slot)),
+			));
 		}
 
 		Ok(JoinHandle {
@@ -126,19 +169,253 @@ where
 			stack_size,
 			result_receiver,
 			spawn_receiver,
+			inbox_sender,
 			task,
 		))
 	}
 }
 
-/// Send [`MessageSend`] over any [`HasMessagePortInterface`].
+/// Internal spawn function for threads returning a [`MessageSend`].
+///
+/// # Safety
+///
+/// `task` has to outlive the thread.
+pub(in super::super) unsafe fn spawn_returning<F1, F2, O>(
+	task: F1,
+	name: Option<String>,
+	stack_size: Option<usize>,
+	scope: Option<Arc<ScopeData>>,
+) -> io::Result<JoinHandle<O>>
+where
+	F1: FnOnce() -> F2 + Send,
+	F2: Future<Output = O>,
+	O: 'static + MessageSend,
+{
+	let (sender, mut receiver) = message::channel::<O>();
+
+	// SAFETY: Requirements are forwarded to the caller.
+	let JoinHandle { thread, .. } = unsafe {
+		spawn(
+			|sender: PortSender<O>| async move {
+				let output = task().await;
+				// On failure dropping `sender` closes the channel, which the calling thread
+				// reports as an error when joining.
+				let _ = sender.post_message(output);
+			},
+			name,
+			stack_size,
+			scope,
+			sender,
+		)
+	}
+	.map_err(|error| error.error)?;
+
+	// The output is reassembled on this thread, so it never has to be `Send`.
+	let (result_sender, result_receiver) = oneshot::channel();
+	wasm_bindgen_futures::spawn_local(async move {
+		if let Some(output) = receiver.recv().await {
+			result_sender.send(output);
+		}
+	});
+
+	Ok(JoinHandle {
+		receiver: Some(result_receiver),
+		thread,
+	})
+}
+
+/// Implementation for
+/// [`JoinHandleExt::post_message()`](crate::web::JoinHandleExt::post_message).
+pub(in super::super) fn post_message<M>(id: ThreadId, message: M) -> Result<(), MessageError<M>>
+where
+	M: 'static + MessageSend,
+{
+	let mut transfer_builder = ArrayBuilder::new();
+	let RawMessage { serialize, send } = message.send(&mut transfer_builder);
+	let transfer = transfer_builder.finish();
+	let slot: SendSlot<M::Send> = Arc::new(Mutex::new(send));
+	let send = InboxMessage {
+		type_id: TypeId::of::<M>(),
+		send: Box::new(Arc::clone(&slot)),
+	};
+
+	let result = {
+		let serialize = serialize.clone().unwrap_or(JsValue::UNDEFINED);
+
+		if super::super::is_main_thread() {
+			main::init_main_thread();
+			post_internal(id, send, &serialize, transfer.as_ref())
+		} else {
+			send_to_main(
+				MessageCommand::Post { id, send },
+				&serialize,
+				transfer.as_ref(),
+			)
+			.map_err(Some)
+		}
+	};
+
+	result.map_err(|error| {
+		let message = M::receive(serialize, take_send(&slot));
+
+		if let Some(error) = error {
+			super::super::message_error_from_exception(error, transfer.as_ref(), message)
+		} else {
+			MessageError {
+				message,
+				kind: MessageErrorKind::Closed,
+				name: None,
+				error: Error::new(ErrorKind::BrokenPipe, "thread has already finished"),
+			}
+		}
+	})
+}
+
+/// Send a [`MessageCommand`] and its message to the main thread.
+fn send_to_main(
+	command: MessageCommand,
+	serialize: &JsValue,
+	transfer: Option<&Array>,
+) -> Result<(), JsValue> {
+	SPAWN_SENDER
+		.with(|cell| {
+			cell.borrow()
+				.as_ref()
+				.expect("found no `Sender` in existing thread")
+				.send(command)
+		})
+		.expect("`Receiver` in main thread dropped");
+
+	Global::with(|global| match global {
+		Global::Dedicated(global) => send_message(global, serialize, transfer),
+		#[cfg(feature = "audio-worklet")]
+		Global::Worklet => super::super::audio_worklet::register::message::MESSAGE_PORT.with(|port| {
+			let port = port
+				.get()
+				.expect("found audio worklet with uninitialized port");
+			send_message(port, serialize, transfer)
+		}),
+		_ => unreachable!("sending from thread not registered by `web-thread`"),
+	})
+}
+
+/// Posting a message to a thread from the main thread. Returns [`None`] if the
+/// thread has already finished.
+fn post_internal(
+	id: ThreadId,
+	send: InboxMessage,
+	serialize: &JsValue,
+	transfer: Option<&Array>,
+) -> Result<(), Option<JsValue>> {
+	// The thread might have been spawned by a command still waiting to be handled.
+	main::handle_commands();
+
+	WORKERS.with(|workers| {
+		let workers = workers.borrow();
+		let state = workers.get(&id).ok_or(None)?;
+		state.inbox_sender.send(send).map_err(|_| None)?;
+
+		send_message(&state.this, serialize, transfer).map_err(Some)
+	})
+}
+
+/// Initialize the [`Inbox`] of this thread.
+pub(in super::super) fn init_inbox(receiver: Receiver<InboxMessage>) {
+	let global: DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+	let message_handler = Closure::new(|event: MessageEvent| {
+		INBOX.with(|inbox| {
+			let mut inbox = inbox.borrow_mut();
+			let inbox = inbox.as_mut().expect("found no `Inbox` in existing thread");
+			let send = inbox
+				.receiver
+				.try_recv()
+				.expect("expected data to have been sent before message");
+			let data = event.data();
+
+			// Sending this message failed, discard the `Send` part.
+			if data.is_undefined() {
+				return;
+			}
+
+			inbox.queue.push_back((data, send));
+
+			for waker in inbox.wakers.drain(..) {
+				waker.wake();
+			}
+		});
+	});
+	global.set_onmessage(Some(message_handler.as_ref().unchecked_ref()));
+
+	let old = INBOX.with(|inbox| {
+		inbox.borrow_mut().replace(Inbox {
+			receiver,
+			queue: VecDeque::new(),
+			wakers: Vec::new(),
+			_message_handler: message_handler,
+		})
+	});
+	debug_assert!(old.is_none(), "found existing `Inbox` in new thread");
+}
+
+/// Remove the [`Inbox`] of this thread.
+pub(in super::super) fn drop_inbox() {
+	let global: DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+	global.set_onmessage(None);
+
+	INBOX
+		.with(|inbox| inbox.borrow_mut().take())
+		.expect("found no `Inbox` in existing thread");
+}
+
+/// Implementation for
+/// [`web::message::receive()`](crate::web::message::receive).
+pub(in super::super) fn poll_message<M>(cx: &Context<'_>) -> Poll<M>
+where
+	M: 'static + MessageSend,
+{
+	INBOX.with(|inbox| {
+		let mut inbox = inbox.borrow_mut();
+		let inbox = inbox
+			.as_mut()
+			.expect("called `receive()` from a thread not spawned by `web-thread`");
+
+		// Messages of other types are left in the queue for their own `receive()`.
+		let Some((data, send)) = inbox
+			.queue
+			.iter()
+			.position(|(_, send)| send.type_id == TypeId::of::<M>())
+			.and_then(|index| inbox.queue.remove(index))
+		else {
+			if !inbox.wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+				inbox.wakers.push(cx.waker().clone());
+			}
+
+			return Poll::Pending;
+		};
+
+		let send = send
+			.send
+			.downcast::<SendSlot<M::Send>>()
+			.expect("found message with mismatching `TypeId`");
+		let serialize = data
+			.unchecked_into::<Array>()
+			.into_iter()
+			.next()
+			.filter(|serialize| !serialize.is_undefined());
+
+		Poll::Ready(M::receive(serialize, take_send(&send)))
+	})
+}
+
+/// Send [`MessageSend`] over any [`HasMessagePortInterface`]. Returns the
+/// thrown exception on failure.
 fn send_message(
 	port: &impl HasMessagePortInterface,
 	serialize: &JsValue,
-	transfer: Option<Array>,
-) -> io::Result<()> {
+	transfer: Option<&Array>,
+) -> Result<(), JsValue> {
 	let result = if let Some(transfer) = transfer {
-		port.post_message_with_transfer(&Array::of2(serialize, &transfer), &transfer)
+		port.post_message_with_transfer(&Array::of2(serialize, transfer), transfer)
 	} else {
 		port.post_message(&Array::of1(serialize))
 	};
@@ -148,33 +425,37 @@ fn send_message(
 			"`DedicatedWorkerGlobalScope.postMessage()` is not expected to fail without a \
 			 `transfer` object",
 		);
-		Err(super::super::error_from_exception(error))
+		Err(error)
 	} else {
 		Ok(())
 	}
 }
 
-/// Spawning thread regardless of being nested.
+/// Spawning thread regardless of being nested. Returns the thrown exception on
+/// failure.
+#[allow(clippy::too_many_arguments)]
 fn spawn_internal(
 	id: ThreadId,
 	name: Option<&str>,
 	stack_size: Option<usize>,
-	spawn_receiver: Receiver<SpawnData>,
+	spawn_receiver: Receiver<MessageCommand>,
+	inbox_sender: channel::Sender<InboxMessage>,
 	serialize: &JsValue,
-	transfer: Option<Array>,
+	transfer: Option<&Array>,
 	task: Task<'_>,
-) -> io::Result<()> {
-	let result = super::spawn_common(
+) -> Result<(), JsValue> {
+	super::spawn_common(
 		id,
 		name,
 		spawn_receiver,
+		inbox_sender,
 		task,
 		#[cfg(not(feature = "audio-worklet"))]
 		|worker: &Worker, module, memory, task| {
 			if let Some(transfer) = transfer {
 				worker.post_message_with_transfer(
 					&Array::of5(module, memory, &stack_size.into(), &task, serialize),
-					&transfer,
+					transfer,
 				)
 			} else {
 				worker.post_message(&Array::of5(
@@ -199,7 +480,7 @@ fn spawn_internal(
 							&task,
 							serialize,
 						),
-						&transfer,
+						transfer,
 					)
 				} else {
 					worker.post_message(&ArrayExt::of6(
@@ -213,13 +494,7 @@ fn spawn_internal(
 				}
 			})
 		},
-	);
-
-	if let Err(error) = result {
-		Err(super::super::error_from_exception(error))
-	} else {
-		Ok(())
-	}
+	)
 }
 
 /// Trait over any type having an interface like
@@ -278,10 +553,10 @@ impl HasMessagePortInterface for DedicatedWorkerGlobalScope {
 /// Setup `message` event handler.
 pub(in super::super) fn setup_message_handler(
 	this: &impl HasMessagePortInterface,
-	spawn_receiver: Receiver<SpawnData>,
+	spawn_receiver: Receiver<MessageCommand>,
 ) -> Closure<dyn Fn(MessageEvent)> {
 	let message_handler = Closure::new(move |event: MessageEvent| {
-		let data = spawn_receiver
+		let command = spawn_receiver
 			.try_recv()
 			.expect("expected data to have been sent before message");
 		let message = event.data();
@@ -294,15 +569,26 @@ pub(in super::super) fn setup_message_handler(
 		let serialize = values.next().expect("no serialized data found");
 		let transfer = values.next().map(Array::unchecked_from_js);
 
-		spawn_internal(
-			data.id,
-			data.name.as_deref(),
-			data.stack_size,
-			data.spawn_receiver,
-			&serialize,
-			transfer,
-			Box::new(data.task),
-		)
+		match command {
+			MessageCommand::Spawn(data) => spawn_internal(
+				data.id,
+				data.name.as_deref(),
+				data.stack_size,
+				data.spawn_receiver,
+				data.inbox_sender,
+				&serialize,
+				transfer.as_ref(),
+				Box::new(data.task),
+			)
+			.map_err(super::super::error_from_exception),
+			// If the thread has already finished, the message is dropped.
+			MessageCommand::Post { id, send } => {
+				match post_internal(id, send, &serialize, transfer.as_ref()) {
+					Ok(()) | Err(None) => Ok(()),
+					Err(Some(error)) => Err(super::super::error_from_exception(error)),
+				}
+			}
+		}
 		.expect("unexpected serialization error when serialization succeeded when sending this");
 	});
 	this.set_onmessage(Some(message_handler.as_ref().unchecked_ref()));
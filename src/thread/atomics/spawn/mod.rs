@@ -16,7 +16,10 @@ use wasm_bindgen::prelude::wasm_bindgen;
 use wasm_bindgen::JsValue;
 use web_sys::{Worker, WorkerOptions, WorkerType};
 #[cfg(feature = "message")]
-use {self::message::SPAWN_SENDER, super::channel};
+use {
+	self::message::{InboxMessage, MessageCommand, SPAWN_SENDER},
+	super::channel,
+};
 
 #[cfg(feature = "audio-worklet")]
 use super::audio_worklet::register::THREAD_LOCK_INDEXES;
@@ -41,7 +44,10 @@ pub(super) struct SpawnData {
 	pub(super) stack_size: Option<usize>,
 	/// [`Task`]s with messages to spawn.
 	#[cfg(feature = "message")]
-	pub(super) spawn_receiver: channel::Receiver<SpawnData>,
+	pub(super) spawn_receiver: channel::Receiver<MessageCommand>,
+	/// Messages posted to the thread.
+	#[cfg(feature = "message")]
+	pub(super) inbox_sender: channel::Sender<InboxMessage>,
 	/// Task.
 	pub(super) task: Task<'static>,
 }
@@ -67,6 +73,8 @@ where
 	let (result_sender, result_receiver) = oneshot::channel();
 	#[cfg(feature = "message")]
 	let (spawn_sender, spawn_receiver) = channel::channel();
+	#[cfg(feature = "message")]
+	let (inbox_sender, inbox_receiver) = channel::channel();
 
 	let task: Task<'_> = Box::new({
 		let thread = thread.clone();
@@ -77,6 +85,8 @@ where
 				result_sender,
 				#[cfg(feature = "message")]
 				spawn_sender,
+				#[cfg(feature = "message")]
+				inbox_receiver,
 				scope,
 				task,
 			)
@@ -89,6 +99,8 @@ where
 		result_receiver,
 		#[cfg(feature = "message")]
 		spawn_receiver,
+		#[cfg(feature = "message")]
+		inbox_sender,
 		task,
 	))
 }
@@ -98,7 +110,8 @@ fn spawn_without_message<T>(
 	thread: Thread,
 	stack_size: Option<usize>,
 	result_receiver: oneshot::Receiver<T>,
-	#[cfg(feature = "message")] spawn_receiver: channel::Receiver<SpawnData>,
+	#[cfg(feature = "message")] spawn_receiver: channel::Receiver<MessageCommand>,
+	#[cfg(feature = "message")] inbox_sender: channel::Sender<InboxMessage>,
 	task: Task<'_>,
 ) -> JoinHandle<T> {
 	if super::is_main_thread() {
@@ -110,6 +123,8 @@ fn spawn_without_message<T>(
 			stack_size,
 			#[cfg(feature = "message")]
 			spawn_receiver,
+			#[cfg(feature = "message")]
+			inbox_sender,
 			Box::new(task),
 		);
 	} else {
@@ -123,6 +138,8 @@ fn spawn_without_message<T>(
 			stack_size,
 			#[cfg(feature = "message")]
 			spawn_receiver,
+			#[cfg(feature = "message")]
+			inbox_sender,
 			task,
 		})
 		.send();
@@ -152,7 +169,8 @@ fn thread_runner<'scope, T: 'scope + Send, F1: 'scope + FnOnce() -> F2, F2: Futu
 	thread: Thread,
 	stack_size: Option<usize>,
 	result_sender: oneshot::Sender<T>,
-	#[cfg(feature = "message")] spawn_sender: channel::Sender<SpawnData>,
+	#[cfg(feature = "message")] spawn_sender: channel::Sender<MessageCommand>,
+	#[cfg(feature = "message")] inbox_receiver: channel::Receiver<InboxMessage>,
 	scope: Option<Arc<ScopeData>>,
 	task: F1,
 ) -> Pin<Box<dyn 'scope + Future<Output = u32>>> {
@@ -163,6 +181,7 @@ fn thread_runner<'scope, T: 'scope + Send, F1: 'scope + FnOnce() -> F2, F2: Futu
 		{
 			let old = SPAWN_SENDER.with(|cell| cell.borrow_mut().replace(spawn_sender));
 			debug_assert!(old.is_none(), "found existing `Sender` in new thread");
+			message::init_inbox(inbox_receiver);
 		}
 
 		result_sender.send(task().await);
@@ -175,9 +194,12 @@ fn thread_runner<'scope, T: 'scope + Send, F1: 'scope + FnOnce() -> F2, F2: Futu
 		}
 
 		#[cfg(feature = "message")]
-		SPAWN_SENDER
-			.with(|cell| cell.borrow_mut().take())
-			.expect("found no `Sender` in existing thread");
+		{
+			SPAWN_SENDER
+				.with(|cell| cell.borrow_mut().take())
+				.expect("found no `Sender` in existing thread");
+			message::drop_inbox();
+		}
 
 		let value = Box::pin(AtomicI32::new(0));
 		let index = super::i32_to_buffer_index(value.as_ptr());
@@ -198,7 +220,8 @@ pub(super) fn spawn_internal(
 	id: ThreadId,
 	name: Option<&str>,
 	stack_size: Option<usize>,
-	#[cfg(feature = "message")] spawn_receiver: channel::Receiver<SpawnData>,
+	#[cfg(feature = "message")] spawn_receiver: channel::Receiver<MessageCommand>,
+	#[cfg(feature = "message")] inbox_sender: channel::Sender<InboxMessage>,
 	task: Task<'_>,
 ) {
 	spawn_common(
@@ -206,6 +229,8 @@ pub(super) fn spawn_internal(
 		name,
 		#[cfg(feature = "message")]
 		spawn_receiver,
+		#[cfg(feature = "message")]
+		inbox_sender,
 		task,
 		|worker, module, memory, task| {
 			#[cfg(not(feature = "audio-worklet"))]
@@ -225,7 +250,8 @@ pub(super) fn spawn_internal(
 fn spawn_common(
 	id: ThreadId,
 	name: Option<&str>,
-	#[cfg(feature = "message")] spawn_receiver: channel::Receiver<SpawnData>,
+	#[cfg(feature = "message")] spawn_receiver: channel::Receiver<MessageCommand>,
+	#[cfg(feature = "message")] inbox_sender: channel::Sender<InboxMessage>,
 	task: Task<'_>,
 	post: impl FnOnce(&Worker, &Module, &Memory, JsValue) -> Result<(), JsValue>,
 ) -> Result<(), JsValue> {
@@ -275,6 +301,8 @@ fn spawn_common(
 				this: worker,
 				#[cfg(feature = "message")]
 				_message_handler: message_handler,
+				#[cfg(feature = "message")]
+				inbox_sender,
 			},
 		)
 	});
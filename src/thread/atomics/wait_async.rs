@@ -21,7 +21,7 @@ const POLYFILL_WORKER_CACHE: usize = 10;
 
 /// Mimics the interface we need from [`Atomics`].
 #[derive(Debug)]
-pub(super) struct WaitAsync(Option<State>);
+pub(in super::super) struct WaitAsync(Option<State>);
 
 /// State for [`WaitAsync`] [`Future`] implementation.
 #[derive(Debug)]
@@ -78,7 +78,7 @@ impl Future for WaitAsync {
 
 impl WaitAsync {
 	/// Mimics the interface we need from [`Atomics::wait_async`].
-	pub(super) fn wait(value: &AtomicI32, check: i32) -> Self {
+	pub(in super::super) fn wait(value: &AtomicI32, check: i32) -> Self {
 		thread_local! {
 			static HAS_WAIT_ASYNC: bool = !js::HAS_WAIT_ASYNC.with(JsValue::is_undefined);
 		}
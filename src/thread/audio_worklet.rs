@@ -9,12 +9,17 @@ use web_sys::{AudioWorkletNode, AudioWorkletNodeOptions, BaseAudioContext};
 
 #[cfg(target_feature = "atomics")]
 use super::atomics::audio_worklet;
+pub(crate) use super::sync::{Signal, SignalFuture};
 #[cfg(not(target_feature = "atomics"))]
 use super::unsupported::audio_worklet;
 use super::Thread;
-use crate::web::audio_worklet::{AudioWorkletNodeError, ExtendAudioWorkletProcessor};
 #[cfg(feature = "message")]
-use crate::web::message::MessageSend;
+use crate::web::audio_worklet::ReceiveMessage;
+use crate::web::audio_worklet::{
+	AudioParameters, AudioWorkletNodeError, ExtendAudioWorkletProcessor,
+};
+#[cfg(feature = "message")]
+use crate::web::message::{MessageError, MessageSend};
 
 /// Implementation for
 /// [`crate::web::audio_worklet::BaseAudioContextExt::register_thread()`].
@@ -97,6 +102,20 @@ impl AudioWorkletHandle {
 	}
 }
 
+/// Implementation for
+/// [`crate::web::audio_worklet::DetectingAllocator::alloc()`](std::alloc::GlobalAlloc::alloc).
+#[cfg(all(feature = "detect-allocations", debug_assertions))]
+pub(crate) fn record_allocation(panic: bool) {
+	audio_worklet::record_allocation(panic);
+}
+
+/// Implementation for
+/// [`crate::web::audio_worklet::DetectingAllocator::dealloc()`](std::alloc::GlobalAlloc::dealloc).
+#[cfg(all(feature = "detect-allocations", debug_assertions))]
+pub(crate) fn record_deallocation(panic: bool) {
+	audio_worklet::record_deallocation(panic);
+}
+
 /// Implementation for
 /// [`crate::web::audio_worklet::AudioWorkletGlobalScopeExt::register_processor_ext()`].
 pub(crate) fn register_processor<P: 'static + ExtendAudioWorkletProcessor>(
@@ -112,6 +131,34 @@ pub(crate) fn register_processor<P: 'static + ExtendAudioWorkletProcessor>(
 	}
 }
 
+/// Implementation for
+/// [`crate::web::audio_worklet::BaseAudioContextExt::register_processor()`].
+pub(crate) fn register_processor_from_main<P: 'static + ExtendAudioWorkletProcessor>(
+	context: &BaseAudioContext,
+	name: &str,
+) -> RegisterProcessorFuture {
+	RegisterProcessorFuture(if audio_worklet::is_registered(context) {
+		audio_worklet::register_processor_from_main::<P>(context, name)
+	} else {
+		audio_worklet::RegisterProcessorFuture::error(Error::new(
+			ErrorKind::Other,
+			"`register_thread()` has to be called on this context first",
+		))
+	})
+}
+
+/// Implementation for [`crate::web::audio_worklet::RegisterProcessorFuture`].
+#[derive(Debug)]
+pub(crate) struct RegisterProcessorFuture(audio_worklet::RegisterProcessorFuture);
+
+impl Future for RegisterProcessorFuture {
+	type Output = io::Result<()>;
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		Pin::new(&mut self.0).poll(cx)
+	}
+}
+
 /// Implementation for
 /// [`crate::web::audio_worklet::BaseAudioContextExt::audio_worklet_node()`].
 pub(crate) fn audio_worklet_node<P: 'static + ExtendAudioWorkletProcessor>(
@@ -119,7 +166,10 @@ pub(crate) fn audio_worklet_node<P: 'static + ExtendAudioWorkletProcessor>(
 	name: &str,
 	data: P::Data,
 	options: Option<&AudioWorkletNodeOptions>,
-) -> Result<AudioWorkletNode, AudioWorkletNodeError<P>> {
+) -> Result<AudioWorkletNode, AudioWorkletNodeError<P>>
+where
+	P::Data: Send,
+{
 	if audio_worklet::is_registered(context) {
 		audio_worklet::audio_worklet_node(context, name, data, options)
 	} else {
@@ -132,3 +182,116 @@ pub(crate) fn audio_worklet_node<P: 'static + ExtendAudioWorkletProcessor>(
 		})
 	}
 }
+
+/// Implementation for
+/// [`crate::web::audio_worklet::BaseAudioContextExt::audio_worklet_node_with_message()`].
+#[cfg(feature = "message")]
+pub(crate) fn audio_worklet_node_with_message<P: 'static + ExtendAudioWorkletProcessor>(
+	context: &BaseAudioContext,
+	name: &str,
+	data: P::Data,
+	options: Option<&AudioWorkletNodeOptions>,
+) -> Result<AudioWorkletNode, AudioWorkletNodeError<P>>
+where
+	P::Data: MessageSend,
+{
+	if audio_worklet::is_registered(context) {
+		audio_worklet::audio_worklet_node_with_message(context, name, data, options)
+	} else {
+		Err(AudioWorkletNodeError {
+			data,
+			error: Error::new(
+				ErrorKind::Other,
+				"`register_thread()` has to be called on this context first",
+			),
+		})
+	}
+}
+
+/// Implementation for
+/// [`crate::web::audio_worklet::BaseAudioContextExt::audio_worklet_node_from_fn()`].
+pub(crate) fn audio_worklet_node_from_fn<F>(
+	context: &BaseAudioContext,
+	options: Option<&AudioWorkletNodeOptions>,
+	process: F,
+) -> AudioWorkletNodeFromFnFuture
+where
+	F: 'static + FnMut(&[&[&[f32]]], &mut [&mut [&mut [f32]]], &AudioParameters<'_>) -> bool + Send,
+{
+	AudioWorkletNodeFromFnFuture(if audio_worklet::is_registered(context) {
+		audio_worklet::audio_worklet_node_from_fn(context, options, process)
+	} else {
+		audio_worklet::AudioWorkletNodeFromFnFuture::error(Error::new(
+			ErrorKind::Other,
+			"`register_thread()` has to be called on this context first",
+		))
+	})
+}
+
+/// Implementation for
+/// [`crate::web::audio_worklet::AudioWorkletNodeFromFnFuture`].
+#[derive(Debug)]
+pub(crate) struct AudioWorkletNodeFromFnFuture(audio_worklet::AudioWorkletNodeFromFnFuture);
+
+impl Future for AudioWorkletNodeFromFnFuture {
+	type Output = io::Result<AudioWorkletNode>;
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		Pin::new(&mut self.0).poll(cx)
+	}
+}
+
+/// Implementation for [`crate::web::audio_worklet::render_offline()`].
+pub(crate) async fn render_offline<P: 'static + ExtendAudioWorkletProcessor>(
+	sample_rate: f32,
+	frames: u32,
+	channels: u32,
+	data: P::Data,
+) -> io::Result<Vec<Vec<f32>>>
+where
+	P::Data: Send,
+{
+	if super::has_spawn_support() {
+		audio_worklet::render_offline::<P>(sample_rate, frames, channels, data).await
+	} else {
+		Err(Error::new(
+			ErrorKind::Unsupported,
+			"operation not supported on this platform without the atomics target feature and \
+			 cross-origin isolation",
+		))
+	}
+}
+
+/// Implementation for
+/// [`crate::web::audio_worklet::RustAudioWorkletNode::processor_error()`].
+pub(crate) fn processor_error(node: &AudioWorkletNode) -> ProcessorErrorFuture {
+	ProcessorErrorFuture(audio_worklet::processor_error(node))
+}
+
+/// Implementation for [`crate::web::audio_worklet::ProcessorErrorFuture`].
+#[derive(Debug)]
+pub(crate) struct ProcessorErrorFuture(audio_worklet::ProcessorErrorFuture);
+
+impl Future for ProcessorErrorFuture {
+	type Output = ();
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		Pin::new(&mut self.0).poll(cx)
+	}
+}
+
+/// Implementation for
+/// [`crate::web::audio_worklet::RustAudioWorkletNode::dispose()`].
+pub(crate) fn dispose(node: &AudioWorkletNode) {
+	audio_worklet::dispose(node);
+}
+
+/// Implementation for
+/// [`crate::web::audio_worklet::RustAudioWorkletNode::post()`].
+#[cfg(feature = "message")]
+pub(crate) fn post_message<P: 'static + ReceiveMessage>(
+	node: &AudioWorkletNode,
+	message: P::Message,
+) -> Result<(), MessageError<P::Message>> {
+	audio_worklet::post_message::<P>(node, message)
+}
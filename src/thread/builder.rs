@@ -5,7 +5,7 @@ use std::io::{self, Error, ErrorKind};
 
 use super::{r#impl, JoinHandle, Scope, ScopedJoinHandle};
 #[cfg(feature = "message")]
-use crate::web::message::MessageSend;
+use crate::web::message::{MessageError, MessageErrorKind, MessageSend};
 
 /// See [`std::thread::Builder`].
 #[derive(Debug)]
@@ -74,7 +74,7 @@ impl Builder {
 		self,
 		task: F1,
 		message: M,
-	) -> io::Result<JoinHandle<T>>
+	) -> Result<JoinHandle<T>, MessageError<M>>
 	where
 		F1: 'static + FnOnce(M) -> F2 + Send,
 		F2: 'static + Future<Output = T>,
@@ -85,6 +85,36 @@ impl Builder {
 			self.0
 				.spawn_with_message_internal(task, message)
 				.map(JoinHandle::new)
+		} else {
+			Err(MessageError {
+				message,
+				kind: MessageErrorKind::Other,
+				name: None,
+				error: Error::new(
+					ErrorKind::Unsupported,
+					"operation not supported on this platform without the atomics target feature \
+					 and cross-origin isolation",
+				),
+			})
+		}
+	}
+
+	/// Implementation for
+	/// [`BuilderExt::spawn_returning_message()`](crate::web::BuilderExt::spawn_returning_message).
+	#[cfg(feature = "message")]
+	pub(crate) fn spawn_returning_message_internal<F1, F2, O>(
+		self,
+		task: F1,
+	) -> io::Result<JoinHandle<O>>
+	where
+		F1: 'static + FnOnce() -> F2 + Send,
+		F2: 'static + Future<Output = O>,
+		O: 'static + MessageSend,
+	{
+		if super::has_spawn_support() {
+			self.0
+				.spawn_returning_message_internal(task)
+				.map(JoinHandle::new)
 		} else {
 			Err(Error::new(
 				ErrorKind::Unsupported,
@@ -151,7 +181,7 @@ impl Builder {
 		scope: &'scope Scope<'scope, '_>,
 		task: F1,
 		message: M,
-	) -> io::Result<ScopedJoinHandle<'scope, T>>
+	) -> Result<ScopedJoinHandle<'scope, T>, MessageError<M>>
 	where
 		F1: 'scope + FnOnce(M) -> F2 + Send,
 		F2: 'scope + Future<Output = T>,
@@ -161,6 +191,36 @@ impl Builder {
 		if super::has_spawn_support() {
 			self.0
 				.spawn_scoped_with_message_internal(&scope.this, task, message)
+		} else {
+			Err(MessageError {
+				message,
+				kind: MessageErrorKind::Other,
+				name: None,
+				error: Error::new(
+					ErrorKind::Unsupported,
+					"operation not supported on this platform without the atomics target feature \
+					 and cross-origin isolation",
+				),
+			})
+		}
+	}
+
+	/// Implementation for
+	/// [`BuilderExt::spawn_scoped_returning_message()`](crate::web::BuilderExt::spawn_scoped_returning_message).
+	#[cfg(feature = "message")]
+	pub(crate) fn spawn_scoped_returning_message_internal<'scope, F1, F2, O>(
+		self,
+		scope: &'scope Scope<'scope, '_>,
+		task: F1,
+	) -> io::Result<ScopedJoinHandle<'scope, O>>
+	where
+		F1: 'scope + FnOnce() -> F2 + Send,
+		F2: 'scope + Future<Output = O>,
+		O: 'static + MessageSend,
+	{
+		if super::has_spawn_support() {
+			self.0
+				.spawn_scoped_returning_message_internal(&scope.this, task)
 		} else {
 			Err(Error::new(
 				ErrorKind::Unsupported,
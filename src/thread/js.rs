@@ -67,11 +67,35 @@ extern "C" {
 	#[wasm_bindgen(method, getter)]
 	pub(super) fn scheduler(this: &WindowOrWorkerExt) -> Scheduler;
 
+	/// Returns [`Atomics.pause`](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Atomics/pause).
+	#[wasm_bindgen(thread_local_v2, js_namespace = Atomics, js_name = pause)]
+	pub(super) static HAS_PAUSE: JsValue;
+
+	/// Binding to [`Atomics.pause()`](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Atomics/pause).
+	#[wasm_bindgen(js_namespace = Atomics)]
+	pub(super) fn pause();
+
+	/// Binding to [`console.warn()`](https://developer.mozilla.org/en-US/docs/Web/API/console/warn_static).
+	#[cfg(any(debug_assertions, feature = "message"))]
+	#[wasm_bindgen(js_namespace = console, js_name = warn)]
+	pub(super) fn console_warn(message: &str);
+
 	/// Returns [`crossOriginIsolated`](https://developer.mozilla.org/en-US/docs/Web/API/crossOriginIsolated) global property.
 	#[wasm_bindgen(thread_local, js_name = crossOriginIsolated)]
 	pub(super) static CROSS_ORIGIN_ISOLATED: Option<bool>;
 }
 
+#[cfg(feature = "message")]
+#[wasm_bindgen]
+extern "C" {
+	/// Extension for [`ArrayBuffer`](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/ArrayBuffer).
+	pub(super) type ArrayBufferExt;
+
+	/// Returns the [`ArrayBuffer.detached`](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/ArrayBuffer/detached) property.
+	#[wasm_bindgen(method, getter)]
+	pub(super) fn detached(this: &ArrayBufferExt) -> Option<bool>;
+}
+
 #[cfg(not(web_sys_unstable_apis))]
 #[wasm_bindgen]
 extern "C" {
@@ -7,8 +7,11 @@ pub(crate) mod audio_worklet;
 mod builder;
 mod global;
 mod js;
+#[cfg(feature = "message")]
+pub(crate) mod port;
 mod scope;
 mod spawn;
+pub(crate) mod sync;
 #[cfg(not(target_feature = "atomics"))]
 mod unsupported;
 mod yield_now;
@@ -24,6 +27,18 @@ use std::time::Duration;
 use js::{GlobalExt, CROSS_ORIGIN_ISOLATED};
 use r#impl::Parker;
 use wasm_bindgen::JsCast;
+#[cfg(feature = "message")]
+use {
+	crate::web::message::{MessageError, MessageErrorKind, MessageSend},
+	js::ArrayBufferExt,
+	js_sys::{Array, ArrayBuffer, Object},
+	std::task::{Context, Poll},
+};
+#[cfg(any(
+	feature = "message",
+	all(target_feature = "atomics", feature = "audio-worklet")
+))]
+use {wasm_bindgen::JsValue, web_sys::DomException};
 
 #[cfg(target_feature = "atomics")]
 use self::atomics as r#impl;
@@ -295,6 +310,16 @@ pub(crate) fn has_spawn_support() -> bool {
 	r#impl::has_spawn_support()
 }
 
+/// Implementation for
+/// [`web::message::receive()`](crate::web::message::receive).
+#[cfg(feature = "message")]
+pub(crate) fn poll_message<M>(cx: &Context<'_>) -> Poll<M>
+where
+	M: 'static + MessageSend,
+{
+	r#impl::poll_message(cx)
+}
+
 /// Returns if [`SharedArrayBuffer`][js_sys::SharedArrayBuffer] is supported.
 fn has_shared_array_buffer_support() -> bool {
 	thread_local! {
@@ -309,3 +334,61 @@ fn has_shared_array_buffer_support() -> bool {
 
 	HAS_SHARED_ARRAY_BUFFER_SUPPORT.with(bool::clone)
 }
+
+/// Convert a [`JsValue`] to an [`DomException`] and then to an [`Error`].
+#[cfg(all(
+	target_feature = "atomics",
+	any(feature = "audio-worklet", feature = "message")
+))]
+fn error_from_exception(error: JsValue) -> Error {
+	let error: DomException = error.unchecked_into();
+
+	Error::new(
+		ErrorKind::Other,
+		format!("{}: {}", error.name(), error.message()),
+	)
+}
+
+/// Convert a [`JsValue`] thrown by `postMessage()` to a [`MessageError`].
+#[cfg(feature = "message")]
+fn message_error_from_exception<M>(
+	error: JsValue,
+	transfer: Option<&Array>,
+	message: M,
+) -> MessageError<M> {
+	let error: DomException = error.unchecked_into();
+	let name = error.name();
+
+	// Browsers report all of these as `DataCloneError`, so the transfer list is
+	// inspected to tell them apart.
+	let kind = match (name.as_str(), transfer) {
+		("DataCloneError", Some(transfer)) => {
+			let transfer = transfer.to_vec();
+
+			if transfer.iter().enumerate().any(|(index, value)| {
+				transfer
+					.iter()
+					.take(index)
+					.any(|other| Object::is(value, other))
+			}) {
+				MessageErrorKind::DuplicateTransfer
+			} else if transfer.iter().any(|value| {
+				value.is_instance_of::<ArrayBuffer>()
+					&& value.unchecked_ref::<ArrayBufferExt>().detached() == Some(true)
+			}) {
+				MessageErrorKind::Detached
+			} else {
+				MessageErrorKind::DataClone
+			}
+		}
+		("DataCloneError", None) => MessageErrorKind::DataClone,
+		_ => MessageErrorKind::Other,
+	};
+
+	MessageError {
+		message,
+		kind,
+		error: Error::new(ErrorKind::Other, format!("{name}: {}", error.message())),
+		name: Some(name),
+	}
+}
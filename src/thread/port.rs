@@ -0,0 +1,314 @@
+//! Typed channel over a [`MessageChannel`].
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Formatter};
+use std::io::{Error, ErrorKind};
+use std::rc::Rc;
+use std::sync::{mpsc, Arc, Mutex, PoisonError};
+use std::task::{Context, Poll, Waker};
+
+use js_sys::Array;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{MessageChannel, MessageEvent, MessagePort};
+
+use crate::web::message::{ArrayBuilder, MessageError, MessageErrorKind, MessageSend, RawMessage};
+
+/// Creates a new channel. See
+/// [`web::message::channel()`](crate::web::message::channel).
+pub(crate) fn channel<M: MessageSend>() -> (PortSender<M>, PortReceiver<M>) {
+	let channel = MessageChannel::new().expect("`new MessageChannel` is not expected to fail");
+	let (sender, receiver) = mpsc::channel();
+
+	(
+		PortSender::from_raw(channel.port1(), sender),
+		PortReceiver::from_raw(channel.port2(), receiver, Vec::new(), false),
+	)
+}
+
+/// [`RawMessage::send`] part of a message, shared with the receiving thread
+/// until the message was posted successfully.
+///
+/// If posting fails, the sending thread takes it back before notifying the
+/// receiving thread, so the [`Mutex`] is never contended.
+pub(crate) type SendSlot<T> = Arc<Mutex<Option<T>>>;
+
+/// Takes the [`RawMessage::send`] part out of a [`SendSlot`].
+pub(crate) fn take_send<T>(slot: &SendSlot<T>) -> Option<T> {
+	slot.lock().unwrap_or_else(PoisonError::into_inner).take()
+}
+
+/// Sending half of [`channel()`].
+pub(crate) struct PortSender<M: MessageSend>(Option<SenderInner<M::Send>>);
+
+/// Content of [`PortSender`], taken when sending it to another thread.
+struct SenderInner<T> {
+	/// The [`MessagePort`] carrying the serialized part.
+	port: MessagePort,
+	/// Channel carrying the [`RawMessage::send`] part.
+	sender: mpsc::Sender<SendSlot<T>>,
+}
+
+impl<M: MessageSend> Debug for PortSender<M> {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+		let mut debug = formatter.debug_struct("PortSender");
+
+		if let Some(inner) = &self.0 {
+			debug
+				.field("port", &inner.port)
+				.field("sender", &inner.sender);
+		}
+
+		debug.finish()
+	}
+}
+
+impl<M: MessageSend> Drop for PortSender<M> {
+	fn drop(&mut self) {
+		if let Some(inner) = self.0.take() {
+			// Wake up the `PortReceiver` so it can notice that there are no more messages
+			// coming. If the other end was already closed this is a no-op.
+			let _ = inner.port.post_message(&JsValue::NULL);
+		}
+	}
+}
+
+impl<M: MessageSend> PortSender<M> {
+	/// Creates a [`PortSender`] from its parts.
+	pub(crate) const fn from_raw(
+		port: MessagePort,
+		sender: mpsc::Sender<SendSlot<M::Send>>,
+	) -> Self {
+		Self(Some(SenderInner { port, sender }))
+	}
+
+	/// Returns the parts of this [`PortSender`] without closing the channel.
+	pub(crate) fn into_raw(mut self) -> (MessagePort, mpsc::Sender<SendSlot<M::Send>>) {
+		let inner = self.0.take().expect("`inner` not found");
+		(inner.port, inner.sender)
+	}
+
+	/// Send a message to the [`PortReceiver`].
+	pub(crate) fn post_message(&self, message: M) -> Result<(), MessageError<M>> {
+		let inner = self.0.as_ref().expect("`inner` not found");
+
+		let mut transfer_builder = ArrayBuilder::new();
+		let RawMessage { serialize, send } = message.send(&mut transfer_builder);
+		let slot = Arc::new(Mutex::new(send));
+
+		// The `Send` part has to arrive before the serialized part, so it is ready when
+		// the `PortReceiver` gets the `message` event.
+		if inner.sender.send(Arc::clone(&slot)).is_err() {
+			return Err(MessageError {
+				message: M::receive(serialize, take_send(&slot)),
+				kind: MessageErrorKind::Closed,
+				name: None,
+				error: Error::new(ErrorKind::BrokenPipe, "`PortReceiver` was dropped"),
+			});
+		}
+
+		let data = serialize.as_ref().map_or_else(Array::new, Array::of1);
+		let transfer = transfer_builder.finish();
+
+		let result = if let Some(transfer) = &transfer {
+			inner.port.post_message_with_transferable(&data, transfer)
+		} else {
+			inner.port.post_message(&data)
+		};
+
+		if let Err(error) = result {
+			// Take back the `Send` part before telling the `PortReceiver` to discard it.
+			let message = M::receive(serialize, take_send(&slot));
+			inner.port.post_message(&JsValue::UNDEFINED).expect(
+				"`MessagePort.postMessage()` is not expected to fail without a `transfer` object",
+			);
+			Err(super::message_error_from_exception(
+				error,
+				transfer.as_ref(),
+				message,
+			))
+		} else {
+			Ok(())
+		}
+	}
+}
+
+/// Receiving half of [`channel()`].
+pub(crate) struct PortReceiver<M: MessageSend>(Option<ReceiverInner<M>>);
+
+/// Content of [`PortReceiver`], taken when sending it to another thread.
+struct ReceiverInner<M: MessageSend> {
+	/// The [`MessagePort`] carrying the serialized part.
+	port: MessagePort,
+	/// Channel carrying the [`RawMessage::send`] part.
+	receiver: mpsc::Receiver<SendSlot<M::Send>>,
+	/// Messages received by a previous owner on another thread, returned
+	/// before any message from [`Self::port`].
+	buffered: VecDeque<M>,
+	/// Installed `message` event handler. Only set up when first polled, so no
+	/// messages are lost when sending an unused [`PortReceiver`].
+	handler: Option<Handler>,
+	/// If the [`PortSender`] was dropped.
+	closed: bool,
+}
+
+/// Installed `message` event handler.
+#[derive(Debug)]
+struct Handler {
+	/// State shared with the handler.
+	state: Rc<RefCell<State>>,
+	/// The handler itself, has to be kept alive while installed.
+	_message_handler: Closure<dyn FnMut(MessageEvent)>,
+}
+
+/// Shared state between [`PortReceiver`] and its `message` event handler.
+#[derive(Debug, Default)]
+struct State {
+	/// Received [`MessageEvent::data()`]s.
+	queue: VecDeque<JsValue>,
+	/// [`Waker`] of the last poll.
+	waker: Option<Waker>,
+}
+
+impl<M: MessageSend> Debug for PortReceiver<M> {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+		let mut debug = formatter.debug_struct("PortReceiver");
+
+		if let Some(inner) = &self.0 {
+			debug
+				.field("port", &inner.port)
+				.field("receiver", &inner.receiver)
+				.field("buffered", &inner.buffered.len())
+				.field("handler", &inner.handler)
+				.field("closed", &inner.closed);
+		}
+
+		debug.finish()
+	}
+}
+
+impl<M: MessageSend> Drop for PortReceiver<M> {
+	fn drop(&mut self) {
+		if let Some(inner) = self.0.take() {
+			if inner.handler.is_some() {
+				inner.port.set_onmessage(None);
+			}
+
+			inner.port.close();
+		}
+	}
+}
+
+impl<M: MessageSend> PortReceiver<M> {
+	/// Creates a [`PortReceiver`] from its parts.
+	pub(crate) fn from_raw(
+		port: MessagePort,
+		receiver: mpsc::Receiver<SendSlot<M::Send>>,
+		buffered: Vec<M>,
+		closed: bool,
+	) -> Self {
+		Self(Some(ReceiverInner {
+			port,
+			receiver,
+			buffered: buffered.into(),
+			handler: None,
+			closed,
+		}))
+	}
+
+	/// Returns the parts of this [`PortReceiver`] without closing the channel,
+	/// together with the messages already received but not yet returned by
+	/// [`poll_recv()`](Self::poll_recv) and if the [`PortSender`] was dropped.
+	pub(crate) fn into_raw(
+		mut self,
+	) -> (MessagePort, mpsc::Receiver<SendSlot<M::Send>>, Vec<M>, bool) {
+		let inner = self.0.take().expect("`inner` not found");
+		let mut buffered = Vec::from(inner.buffered);
+		let mut closed = inner.closed;
+
+		if let Some(Handler { state, .. }) = inner.handler {
+			inner.port.set_onmessage(None);
+
+			for data in state.borrow_mut().queue.drain(..) {
+				if data.is_null() {
+					closed = true;
+					break;
+				}
+
+				buffered.extend(Self::receive(&inner.receiver, data));
+			}
+		}
+
+		(inner.port, inner.receiver, buffered, closed)
+	}
+
+	/// Poll for the next message sent by the [`PortSender`].
+	pub(crate) fn poll_recv(&mut self, cx: &Context<'_>) -> Poll<Option<M>> {
+		let inner = self.0.as_mut().expect("`inner` not found");
+
+		if let Some(message) = inner.buffered.pop_front() {
+			return Poll::Ready(Some(message));
+		}
+
+		if inner.closed {
+			return Poll::Ready(None);
+		}
+
+		let Handler { state, .. } = inner.handler.get_or_insert_with(|| {
+			let state = Rc::new(RefCell::new(State::default()));
+			let message_handler = Closure::new({
+				let state = Rc::clone(&state);
+				move |event: MessageEvent| {
+					let mut state = state.borrow_mut();
+					state.queue.push_back(event.data());
+
+					if let Some(waker) = state.waker.take() {
+						waker.wake();
+					}
+				}
+			});
+			// Setting the handler implicitly starts the port.
+			inner
+				.port
+				.set_onmessage(Some(message_handler.as_ref().unchecked_ref()));
+
+			Handler {
+				state,
+				_message_handler: message_handler,
+			}
+		});
+
+		let mut state = state.borrow_mut();
+
+		while let Some(data) = state.queue.pop_front() {
+			if data.is_null() {
+				inner.closed = true;
+				return Poll::Ready(None);
+			}
+
+			if let Some(message) = Self::receive(&inner.receiver, data) {
+				return Poll::Ready(Some(message));
+			}
+		}
+
+		state.waker = Some(cx.waker().clone());
+		Poll::Pending
+	}
+
+	/// Reconstructs the message from `data` received from the port. Returns
+	/// [`None`] if sending this message failed.
+	fn receive(receiver: &mpsc::Receiver<SendSlot<M::Send>>, data: JsValue) -> Option<M> {
+		let sent = receiver
+			.try_recv()
+			.expect("expected `Send` part to have been sent before message");
+
+		// Sending this message failed, discard the `Send` part.
+		if data.is_undefined() {
+			return None;
+		}
+
+		let serialized = data.unchecked_into::<Array>().into_iter().next();
+		Some(M::receive(serialized, take_send(&sent)))
+	}
+}
@@ -128,6 +128,23 @@ impl<'scope, #[allow(single_use_lifetimes)] 'env> Scope<'scope, 'env> {
 			.spawn_scoped_with_message_internal(self, task, message)
 			.expect("failed to spawn thread")
 	}
+
+	/// Implementation for
+	/// [`ScopeExt::spawn_returning_message()`](crate::web::ScopeExt::spawn_returning_message).
+	#[cfg(feature = "message")]
+	pub(crate) fn spawn_returning_message_internal<F1, F2, O>(
+		&'scope self,
+		task: F1,
+	) -> ScopedJoinHandle<'scope, O>
+	where
+		F1: 'scope + FnOnce() -> F2 + Send,
+		F2: 'scope + Future<Output = O>,
+		O: 'static + MessageSend,
+	{
+		Builder::new()
+			.spawn_scoped_returning_message_internal(self, task)
+			.expect("failed to spawn thread")
+	}
 }
 
 /// See [`std::thread::ScopedJoinHandle`].
@@ -6,6 +6,8 @@ use std::task::{Context, Poll};
 use std::{fmt, thread};
 
 use super::{r#impl, Builder, Thread};
+#[cfg(feature = "message")]
+use crate::web::message::{MessageError, MessageSend};
 
 /// See [`std::thread::spawn()`].
 ///
@@ -81,6 +83,16 @@ impl<T> JoinHandle<T> {
 		self.0.thread()
 	}
 
+	/// Implementation for
+	/// [`JoinHandleExt::post_message()`](crate::web::JoinHandleExt::post_message).
+	#[cfg(feature = "message")]
+	pub(crate) fn post_message_internal<M>(&self, message: M) -> Result<(), MessageError<M>>
+	where
+		M: 'static + MessageSend,
+	{
+		self.0.post_message_internal(message)
+	}
+
 	/// Implementation for
 	/// [`JoinHandleFuture::poll()`](crate::web::JoinHandleFuture).
 	pub(crate) fn poll(&mut self, cx: &mut Context<'_>) -> Poll<thread::Result<T>> {
@@ -0,0 +1,279 @@
+//! Implementation of synchronization primitives.
+
+#[cfg(feature = "audio-worklet")]
+use std::future::Future;
+use std::hint;
+#[cfg(feature = "audio-worklet")]
+use std::pin::Pin;
+#[cfg(feature = "audio-worklet")]
+use std::sync::atomic::AtomicI32;
+use std::sync::atomic::{AtomicU32, Ordering};
+#[cfg(feature = "audio-worklet")]
+use std::task::{ready, Context, Poll};
+
+use wasm_bindgen::JsValue;
+
+#[cfg(debug_assertions)]
+use super::global::Global;
+use super::{js, r#impl};
+
+/// The lock is free.
+const UNLOCKED: u32 = 0;
+/// The lock is held and no other thread is waiting for it.
+const LOCKED: u32 = 1;
+/// The lock is held and other threads might be blocked waiting for it.
+const CONTENDED: u32 = 2;
+
+/// Amount of spins a thread supporting blocking does before falling back to
+/// waiting.
+const BLOCKING_SPIN_LIMIT: u32 = 100;
+
+/// Amount of milliseconds the main thread can spin before it is reported in
+/// debug builds.
+#[cfg(debug_assertions)]
+const REPORT_THRESHOLD: f64 = 1.;
+
+/// Lock state of [`SpinMutex`](crate::web::sync::SpinMutex).
+#[derive(Debug)]
+pub(crate) struct RawSpinMutex {
+	/// Current state of the lock.
+	state: AtomicU32,
+	/// Amount of spins before giving up on threads not supporting blocking.
+	spin_limit: u32,
+}
+
+impl RawSpinMutex {
+	/// Creates a new [`RawSpinMutex`].
+	pub(crate) const fn new(spin_limit: u32) -> Self {
+		Self {
+			state: AtomicU32::new(UNLOCKED),
+			spin_limit,
+		}
+	}
+
+	/// Returns the spin limit.
+	pub(crate) const fn spin_limit(&self) -> u32 {
+		self.spin_limit
+	}
+
+	/// Attempts to acquire the lock without spinning.
+	pub(crate) fn try_lock(&self) -> bool {
+		self.state
+			.compare_exchange(UNLOCKED, LOCKED, Ordering::Acquire, Ordering::Relaxed)
+			.is_ok()
+	}
+
+	/// Acquires the lock. Returns [`false`] if the spin limit was exceeded.
+	pub(crate) fn lock(&self) -> bool {
+		if self.try_lock() {
+			return true;
+		}
+
+		if super::has_block_support() {
+			self.lock_blocking();
+			true
+		} else {
+			self.lock_spinning()
+		}
+	}
+
+	/// Releases the lock.
+	pub(crate) fn unlock(&self) {
+		if self.state.swap(UNLOCKED, Ordering::Release) == CONTENDED {
+			r#impl::futex_wake(&self.state);
+		}
+	}
+
+	/// Acquires the lock by spinning shortly and then blocking.
+	#[cold]
+	fn lock_blocking(&self) {
+		// See <https://github.com/rust-lang/rust/blob/1.75.0/library/std/src/sys/unix/locks/futex_mutex.rs>.
+
+		let mut state = self.spin();
+
+		if state == UNLOCKED {
+			match self.state.compare_exchange(
+				UNLOCKED,
+				LOCKED,
+				Ordering::Acquire,
+				Ordering::Relaxed,
+			) {
+				Ok(_) => return,
+				Err(current) => state = current,
+			}
+		}
+
+		loop {
+			// Mark the lock as contended, so the current owner wakes us up.
+			if state != CONTENDED && self.state.swap(CONTENDED, Ordering::Acquire) == UNLOCKED {
+				return;
+			}
+
+			r#impl::futex_wait(&self.state, CONTENDED, None);
+			state = self.spin();
+		}
+	}
+
+	/// Spins until the lock is no longer held without contention or
+	/// [`BLOCKING_SPIN_LIMIT`] is reached. Returns the last observed state.
+	fn spin(&self) -> u32 {
+		let mut spin = BLOCKING_SPIN_LIMIT;
+
+		loop {
+			let state = self.state.load(Ordering::Relaxed);
+
+			if state != LOCKED || spin == 0 {
+				return state;
+			}
+
+			pause();
+			spin -= 1;
+		}
+	}
+
+	/// Acquires the lock by spinning up to the spin limit.
+	#[cold]
+	fn lock_spinning(&self) -> bool {
+		#[cfg(debug_assertions)]
+		let start = js_sys::Date::now();
+		let mut locked = false;
+
+		for _ in 0..self.spin_limit {
+			pause();
+
+			if self.state.load(Ordering::Relaxed) == UNLOCKED && self.try_lock() {
+				locked = true;
+				break;
+			}
+		}
+
+		#[cfg(debug_assertions)]
+		report(start);
+
+		locked
+	}
+}
+
+/// Implementation for [`crate::web::audio_worklet::Signal`].
+#[cfg(feature = "audio-worklet")]
+#[derive(Debug)]
+pub(crate) struct Signal {
+	/// `1` if set, `0` otherwise. Used to wait on.
+	flag: AtomicI32,
+	/// Amount of notifications.
+	count: AtomicU32,
+}
+
+#[cfg(feature = "audio-worklet")]
+impl Signal {
+	/// Creates a new [`Signal`].
+	pub(crate) const fn new() -> Self {
+		Self {
+			flag: AtomicI32::new(0),
+			count: AtomicU32::new(0),
+		}
+	}
+
+	/// Implementation for [`crate::web::audio_worklet::Signal::notify()`].
+	pub(crate) fn notify(&self) {
+		self.count.fetch_add(1, Ordering::Relaxed);
+
+		if self.flag.swap(1, Ordering::Release) == 0 {
+			r#impl::atomic_notify_all(&self.flag);
+		}
+	}
+
+	/// Implementation for [`crate::web::audio_worklet::Signal::is_set()`].
+	pub(crate) fn is_set(&self) -> bool {
+		self.flag.load(Ordering::Acquire) == 1
+	}
+
+	/// Implementation for [`crate::web::audio_worklet::Signal::reset()`].
+	pub(crate) fn reset(&self) -> bool {
+		self.flag.swap(0, Ordering::Acquire) == 1
+	}
+
+	/// Implementation for [`crate::web::audio_worklet::Signal::count()`].
+	pub(crate) fn count(&self) -> u32 {
+		self.count.load(Ordering::Relaxed)
+	}
+
+	/// Implementation for [`crate::web::audio_worklet::Signal::wait()`].
+	pub(crate) fn wait(&self) {
+		assert!(
+			super::has_block_support(),
+			"current thread type cannot be blocked"
+		);
+
+		while !self.reset() {
+			r#impl::atomic_wait(&self.flag, 0);
+		}
+	}
+
+	/// Implementation for [`crate::web::audio_worklet::Signal::wait_async()`].
+	pub(crate) const fn wait_async(&self) -> SignalFuture<'_> {
+		SignalFuture {
+			signal: self,
+			wait: None,
+		}
+	}
+}
+
+/// Implementation for [`crate::web::audio_worklet::SignalFuture`].
+#[cfg(feature = "audio-worklet")]
+#[derive(Debug)]
+pub(crate) struct SignalFuture<'signal> {
+	/// The [`Signal`] to wait on.
+	signal: &'signal Signal,
+	/// Currently running wait.
+	wait: Option<r#impl::WaitAsync>,
+}
+
+#[cfg(feature = "audio-worklet")]
+impl Future for SignalFuture<'_> {
+	type Output = ();
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		loop {
+			if let Some(wait) = &mut self.wait {
+				ready!(Pin::new(wait).poll(cx));
+				self.wait = None;
+			}
+
+			if self.signal.reset() {
+				return Poll::Ready(());
+			}
+
+			self.wait = Some(r#impl::WaitAsync::wait(&self.signal.flag, 0));
+		}
+	}
+}
+
+/// Hints the CPU that we are spinning. Uses [`Atomics.pause()`] if supported.
+///
+/// [`Atomics.pause()`]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Atomics/pause
+fn pause() {
+	thread_local! {
+		static HAS_PAUSE: bool = !js::HAS_PAUSE.with(JsValue::is_undefined);
+	}
+
+	if HAS_PAUSE.with(bool::clone) {
+		js::pause();
+	} else {
+		hint::spin_loop();
+	}
+}
+
+/// Reports to the console if the main thread spun longer than
+/// [`REPORT_THRESHOLD`].
+#[cfg(debug_assertions)]
+fn report(start: f64) {
+	let elapsed = js_sys::Date::now() - start;
+
+	if elapsed > REPORT_THRESHOLD && Global::with(|global| matches!(global, Global::Window(_))) {
+		js::console_warn(&format!(
+			"`SpinMutex` spun on the main thread for {elapsed}ms, consider reducing the time \
+			 spent holding the lock"
+		));
+	}
+}
@@ -9,7 +9,11 @@ use std::task::{Context, Poll};
 use web_sys::{AudioWorkletNode, AudioWorkletNodeOptions, BaseAudioContext};
 
 use super::super::Thread;
+#[cfg(feature = "message")]
+use crate::web::audio_worklet::ReceiveMessage;
 use crate::web::audio_worklet::{AudioWorkletNodeError, ExtendAudioWorkletProcessor};
+#[cfg(feature = "message")]
+use crate::web::message::MessageError;
 
 /// Implementation for
 /// [`crate::web::audio_worklet::BaseAudioContextExt::register_thread()`].
@@ -87,6 +91,22 @@ impl AudioWorkletHandle {
 	}
 }
 
+/// Implementation for
+/// [`crate::web::audio_worklet::DetectingAllocator::alloc()`](std::alloc::GlobalAlloc::alloc).
+#[cfg(all(feature = "detect-allocations", debug_assertions))]
+#[allow(clippy::missing_const_for_fn)]
+pub(in super::super) fn record_allocation(_: bool) {
+	// Processors can't run without being able to register a thread.
+}
+
+/// Implementation for
+/// [`crate::web::audio_worklet::DetectingAllocator::dealloc()`](std::alloc::GlobalAlloc::dealloc).
+#[cfg(all(feature = "detect-allocations", debug_assertions))]
+#[allow(clippy::missing_const_for_fn)]
+pub(in super::super) fn record_deallocation(_: bool) {
+	// Processors can't run without being able to register a thread.
+}
+
 /// Determined if the current thread is the main thread.
 #[allow(clippy::missing_const_for_fn)]
 pub(in super::super) fn is_main_thread() -> bool {
@@ -101,6 +121,43 @@ pub(in super::super) fn register_processor<P>(_: &str) -> Result<(), Error> {
 	unreachable!("reached `register_processor()` on the main thread")
 }
 
+/// Implementation for
+/// [`crate::web::audio_worklet::BaseAudioContextExt::register_processor()`].
+#[allow(clippy::extra_unused_type_parameters)]
+pub(in super::super) fn register_processor_from_main<P>(
+	_: &BaseAudioContext,
+	_: &str,
+) -> RegisterProcessorFuture {
+	unreachable!("reached despite not being able to register a thread")
+}
+
+/// Implementation for [`crate::web::audio_worklet::RegisterProcessorFuture`].
+#[derive(Debug)]
+pub(in super::super) struct RegisterProcessorFuture {
+	/// Only possible state is an error.
+	error: Option<Error>,
+	/// Make sure it doesn't implement [`Send`] or [`Sync`].
+	_marker: PhantomData<*const ()>,
+}
+
+impl Future for RegisterProcessorFuture {
+	type Output = io::Result<()>;
+
+	fn poll(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+		Poll::Ready(Err(self.error.take().expect("polled after completion")))
+	}
+}
+
+impl RegisterProcessorFuture {
+	/// Create a [`RegisterProcessorFuture`] that returns `error`.
+	pub(in super::super) const fn error(error: Error) -> Self {
+		Self {
+			error: Some(error),
+			_marker: PhantomData,
+		}
+	}
+}
+
 /// Returns [`true`] if this context has a registered thread.
 #[allow(clippy::missing_const_for_fn)]
 pub(in super::super) fn is_registered(_: &BaseAudioContext) -> bool {
@@ -117,3 +174,101 @@ pub(in super::super) fn audio_worklet_node<P: ExtendAudioWorkletProcessor>(
 ) -> Result<AudioWorkletNode, AudioWorkletNodeError<P>> {
 	unreachable!("reached despite not being able to register a thread")
 }
+
+/// Implementation for
+/// [`crate::web::audio_worklet::BaseAudioContextExt::audio_worklet_node_with_message()`].
+#[cfg(feature = "message")]
+pub(in super::super) fn audio_worklet_node_with_message<P: ExtendAudioWorkletProcessor>(
+	_: &BaseAudioContext,
+	_: &str,
+	_: P::Data,
+	_: Option<&AudioWorkletNodeOptions>,
+) -> Result<AudioWorkletNode, AudioWorkletNodeError<P>> {
+	unreachable!("reached despite not being able to register a thread")
+}
+
+/// Implementation for
+/// [`crate::web::audio_worklet::RustAudioWorkletNode::processor_error()`].
+pub(in super::super) fn processor_error(_: &AudioWorkletNode) -> ProcessorErrorFuture {
+	unreachable!("reached despite not being able to register a thread")
+}
+
+/// Implementation for [`crate::web::audio_worklet::ProcessorErrorFuture`].
+#[derive(Debug)]
+pub(in super::super) struct ProcessorErrorFuture(
+	/// Make sure it doesn't implement [`Send`] or [`Sync`].
+	PhantomData<*const ()>,
+);
+
+impl Future for ProcessorErrorFuture {
+	type Output = ();
+
+	fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+		unreachable!("reached despite not being able to register a thread")
+	}
+}
+
+/// Implementation for
+/// [`crate::web::audio_worklet::RustAudioWorkletNode::dispose()`].
+pub(in super::super) fn dispose(_: &AudioWorkletNode) {
+	unreachable!("reached despite not being able to register a thread")
+}
+
+/// Implementation for
+/// [`crate::web::audio_worklet::RustAudioWorkletNode::post()`].
+#[cfg(feature = "message")]
+pub(in super::super) fn post_message<P: ReceiveMessage>(
+	_: &AudioWorkletNode,
+	_: P::Message,
+) -> Result<(), MessageError<P::Message>> {
+	unreachable!("reached despite not being able to register a thread")
+}
+
+/// Implementation for
+/// [`crate::web::audio_worklet::BaseAudioContextExt::audio_worklet_node_from_fn()`].
+pub(in super::super) fn audio_worklet_node_from_fn<F>(
+	_: &BaseAudioContext,
+	_: Option<&AudioWorkletNodeOptions>,
+	_: F,
+) -> AudioWorkletNodeFromFnFuture {
+	unreachable!("reached despite not being able to register a thread")
+}
+
+/// Implementation for
+/// [`crate::web::audio_worklet::AudioWorkletNodeFromFnFuture`].
+#[derive(Debug)]
+pub(in super::super) struct AudioWorkletNodeFromFnFuture {
+	/// Only possible state is an error.
+	error: Option<Error>,
+	/// Make sure it doesn't implement [`Send`] or [`Sync`].
+	_marker: PhantomData<*const ()>,
+}
+
+impl Future for AudioWorkletNodeFromFnFuture {
+	type Output = io::Result<AudioWorkletNode>;
+
+	fn poll(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+		Poll::Ready(Err(self.error.take().expect("polled after completion")))
+	}
+}
+
+impl AudioWorkletNodeFromFnFuture {
+	/// Create a [`AudioWorkletNodeFromFnFuture`] that returns `error`.
+	pub(in super::super) const fn error(error: Error) -> Self {
+		Self {
+			error: Some(error),
+			_marker: PhantomData,
+		}
+	}
+}
+
+/// Implementation for [`crate::web::audio_worklet::render_offline()`].
+#[allow(clippy::unused_async)]
+pub(in super::super) async fn render_offline<P: ExtendAudioWorkletProcessor>(
+	_: f32,
+	_: u32,
+	_: u32,
+	_: P::Data,
+) -> io::Result<Vec<Vec<f32>>> {
+	unreachable!("reached `render_offline()` without atomics target feature")
+}
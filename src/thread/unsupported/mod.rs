@@ -8,6 +8,11 @@ mod parker;
 use std::fmt::{self, Debug, Formatter};
 use std::future::Future;
 use std::marker::PhantomData;
+#[cfg(feature = "audio-worklet")]
+use std::pin::Pin;
+#[cfg(feature = "audio-worklet")]
+use std::sync::atomic::AtomicI32;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::task::{Context, Poll};
 use std::time::Duration;
 use std::{io, thread};
@@ -20,6 +25,8 @@ use wasm_bindgen::JsCast;
 pub(super) use self::parker::Parker;
 use super::js::CROSS_ORIGIN_ISOLATED;
 use super::ScopedJoinHandle;
+#[cfg(feature = "message")]
+use crate::web::message::MessageError;
 
 /// Implementation of [`std::thread::Builder`].
 #[derive(Debug)]
@@ -65,7 +72,7 @@ impl Builder {
 		self,
 		_: F1,
 		_: M,
-	) -> io::Result<JoinHandle<T>>
+	) -> Result<JoinHandle<T>, MessageError<M>>
 	where
 		F1: FnOnce(M) -> F2,
 		F2: Future<Output = T>,
@@ -73,6 +80,21 @@ impl Builder {
 		unreachable!("reached `spawn_with_message_internal()` without atomics target feature")
 	}
 
+	/// Implementation for
+	/// [`BuilderExt::spawn_returning_message()`](crate::web::BuilderExt::spawn_returning_message).
+	#[cfg(feature = "message")]
+	#[allow(clippy::unused_self)]
+	pub(super) fn spawn_returning_message_internal<F1, F2, O>(
+		self,
+		_: F1,
+	) -> io::Result<JoinHandle<O>>
+	where
+		F1: FnOnce() -> F2,
+		F2: Future<Output = O>,
+	{
+		unreachable!("reached `spawn_returning_message_internal()` without atomics target feature")
+	}
+
 	/// Implementation of [`std::thread::Builder::spawn_scoped()`].
 	#[allow(clippy::unused_self)]
 	pub(super) fn spawn_scoped<F, T>(self, _: &Scope, _: F) -> io::Result<ScopedJoinHandle<'_, T>> {
@@ -103,7 +125,7 @@ impl Builder {
 		_: &Scope,
 		_: F1,
 		_: M,
-	) -> io::Result<ScopedJoinHandle<'_, T>>
+	) -> Result<ScopedJoinHandle<'_, T>, MessageError<M>>
 	where
 		F1: FnOnce(M) -> F2,
 		F2: Future<Output = T>,
@@ -113,6 +135,24 @@ impl Builder {
 		)
 	}
 
+	/// Implementation for
+	/// [`BuilderExt::spawn_scoped_returning_message()`](crate::web::BuilderExt::spawn_scoped_returning_message).
+	#[cfg(feature = "message")]
+	#[allow(clippy::unused_self)]
+	pub(super) fn spawn_scoped_returning_message_internal<F1, F2, O>(
+		self,
+		_: &Scope,
+		_: F1,
+	) -> io::Result<ScopedJoinHandle<'_, O>>
+	where
+		F1: FnOnce() -> F2,
+		F2: Future<Output = O>,
+	{
+		unreachable!(
+			"reached `spawn_scoped_returning_message_internal()` without atomics target feature"
+		)
+	}
+
 	/// Implementation of [`std::thread::Builder::stack_size()`].
 	#[allow(clippy::missing_const_for_fn)]
 	pub(super) fn stack_size(self, _: usize) -> Self {
@@ -152,6 +192,14 @@ impl<T> JoinHandle<T> {
 		unreachable!("found instanced `JoinHandle` without threading support")
 	}
 
+	/// Implementation for
+	/// [`JoinHandleExt::post_message()`](crate::web::JoinHandleExt::post_message).
+	#[cfg(feature = "message")]
+	#[allow(clippy::unused_self)]
+	pub(super) fn post_message_internal<M>(&self, _: M) -> Result<(), MessageError<M>> {
+		unreachable!("found instanced `JoinHandle` without threading support")
+	}
+
 	/// Implementation for
 	/// [`JoinHandleFuture::poll()`](crate::web::JoinHandleFuture).
 	#[allow(clippy::unused_self)]
@@ -188,6 +236,13 @@ impl Scope {
 	}
 }
 
+/// Implementation for
+/// [`web::message::receive()`](crate::web::message::receive).
+#[cfg(feature = "message")]
+pub(super) fn poll_message<M>(_: &Context<'_>) -> Poll<M> {
+	panic!("called `receive()` from a thread not spawned by `web-thread`")
+}
+
 /// Implementation of [`std::thread::sleep()`].
 pub(super) fn sleep(dur: Duration) {
 	let timeout = duration_to_f64_millis(dur);
@@ -200,6 +255,79 @@ pub(super) fn sleep(dur: Duration) {
 	);
 }
 
+/// Without threading support no other thread can ever change the value of
+/// `futex`, so waiting on it would block forever.
+///
+/// # Panics
+///
+/// If `futex` holds the `expected` value.
+pub(super) fn futex_wait(futex: &AtomicU32, expected: u32, _: Option<Duration>) -> bool {
+	assert_ne!(
+		futex.load(Ordering::Relaxed),
+		expected,
+		"deadlock: waiting on a value no other thread can change"
+	);
+
+	true
+}
+
+/// Without threading support there is never any thread to wake.
+#[allow(clippy::missing_const_for_fn)]
+pub(super) fn futex_wake(_: &AtomicU32) -> bool {
+	false
+}
+
+/// Without threading support no other thread can ever change `value`, so
+/// waiting on it would block forever.
+///
+/// # Panics
+///
+/// If `value` holds `check`.
+#[cfg(feature = "audio-worklet")]
+pub(super) fn atomic_wait(value: &AtomicI32, check: i32) {
+	assert_ne!(
+		value.load(Ordering::Relaxed),
+		check,
+		"deadlock: waiting on a value no other thread can change"
+	);
+}
+
+/// Without threading support there is never any thread to wake.
+#[cfg(feature = "audio-worklet")]
+#[allow(clippy::missing_const_for_fn)]
+pub(super) fn atomic_notify_all(_: &AtomicI32) {}
+
+/// Implementation of `WaitAsync` without threading support.
+#[cfg(feature = "audio-worklet")]
+#[derive(Debug)]
+pub(super) struct WaitAsync(
+	/// Make sure it doesn't implement [`Send`] or [`Sync`].
+	PhantomData<*const ()>,
+);
+
+#[cfg(feature = "audio-worklet")]
+impl Future for WaitAsync {
+	type Output = ();
+
+	fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+		Poll::Ready(())
+	}
+}
+
+#[cfg(feature = "audio-worklet")]
+impl WaitAsync {
+	/// Without threading support no other thread can ever change `value`, so
+	/// waiting on it would never finish.
+	///
+	/// # Panics
+	///
+	/// If `value` holds `check`.
+	pub(super) fn wait(value: &AtomicI32, check: i32) -> Self {
+		atomic_wait(value, check);
+		Self(PhantomData)
+	}
+}
+
 /// Tests if blocking is supported.
 pub(super) fn test_block_support() -> bool {
 	ZERO_ARRAY.with(|array| Atomics::wait_with_timeout(array, 0, 0, 0.).is_ok())
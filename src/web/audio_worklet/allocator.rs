@@ -0,0 +1,102 @@
+//! Detecting allocations in audio worklet processors.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+
+#[cfg(all(target_family = "wasm", target_os = "unknown", debug_assertions))]
+use crate::thread::audio_worklet;
+
+/// [`GlobalAlloc`] wrapper detecting allocations in audio worklet
+/// processors.
+///
+/// Audio worklets run in a realtime context and should not allocate. Every
+/// call into [`ExtendAudioWorkletProcessor::process()`] or
+/// [`ProcessBuffers::process_buffers()`] that allocated or
+/// deallocated is reported to the console together with the total counts of the
+/// offending node. See [`DetectingAllocator::new_panicking()`] to panic
+/// instead.
+///
+/// # Notes
+///
+/// Detection only takes place with `debug_assertions` enabled, otherwise
+/// this only forwards to the wrapped allocator.
+///
+/// # Example
+///
+/// ```
+/// use std::alloc::System;
+///
+/// use web_thread::web::audio_worklet::DetectingAllocator;
+///
+/// #[global_allocator]
+/// static ALLOCATOR: DetectingAllocator<System> = DetectingAllocator::new(System);
+/// # fn main() {}
+/// ```
+///
+/// [`ExtendAudioWorkletProcessor::process()`]: super::ExtendAudioWorkletProcessor::process
+/// [`ProcessBuffers::process_buffers()`]: super::ProcessBuffers::process_buffers
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DetectingAllocator<A = System> {
+	/// The wrapped allocator.
+	allocator: A,
+	/// If detected allocations panic instead of being logged.
+	#[cfg_attr(
+		not(all(target_family = "wasm", target_os = "unknown", debug_assertions)),
+		allow(dead_code)
+	)]
+	panic: bool,
+}
+
+impl<A> DetectingAllocator<A> {
+	/// Creates a new [`DetectingAllocator`] wrapping `allocator`, logging
+	/// detected allocations to the console.
+	pub const fn new(allocator: A) -> Self {
+		Self {
+			allocator,
+			panic: false,
+		}
+	}
+
+	/// Creates a new [`DetectingAllocator`] wrapping `allocator`, panicking
+	/// after a call into a processor allocated or deallocated.
+	pub const fn new_panicking(allocator: A) -> Self {
+		Self {
+			allocator,
+			panic: true,
+		}
+	}
+}
+
+// SAFETY: Forwards all calls to the wrapped allocator.
+unsafe impl<A: GlobalAlloc> GlobalAlloc for DetectingAllocator<A> {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		#[cfg(all(target_family = "wasm", target_os = "unknown", debug_assertions))]
+		audio_worklet::record_allocation(self.panic);
+
+		// SAFETY: Guarantees have to be uphold by the caller.
+		unsafe { self.allocator.alloc(layout) }
+	}
+
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		#[cfg(all(target_family = "wasm", target_os = "unknown", debug_assertions))]
+		audio_worklet::record_deallocation(self.panic);
+
+		// SAFETY: Guarantees have to be uphold by the caller.
+		unsafe { self.allocator.dealloc(ptr, layout) }
+	}
+
+	unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+		#[cfg(all(target_family = "wasm", target_os = "unknown", debug_assertions))]
+		audio_worklet::record_allocation(self.panic);
+
+		// SAFETY: Guarantees have to be uphold by the caller.
+		unsafe { self.allocator.alloc_zeroed(layout) }
+	}
+
+	unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+		#[cfg(all(target_family = "wasm", target_os = "unknown", debug_assertions))]
+		audio_worklet::record_allocation(self.panic);
+
+		// SAFETY: Guarantees have to be uphold by the caller.
+		unsafe { self.allocator.realloc(ptr, layout, new_size) }
+	}
+}
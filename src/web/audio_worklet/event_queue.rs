@@ -0,0 +1,366 @@
+//! Lock-free multi-producer single-consumer queue to schedule timestamped
+//! events into audio worklets.
+
+use std::cell::UnsafeCell;
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Formatter};
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::{any, cmp, iter};
+
+/// Lock-free multi-producer single-consumer queue of timestamped events.
+///
+/// Every event is pushed together with the frame it should take effect at.
+/// Use [`EventQueue::split()`] to get the [`EventQueueProducer`] and
+/// [`EventQueueConsumer`] halves.
+///
+/// # Notes
+///
+/// The [`EventQueueConsumer`] doesn't block or allocate, so it is safe to use
+/// from audio worklets, e.g. in
+/// [`ExtendAudioWorkletProcessor::process()`](super::ExtendAudioWorkletProcessor::process).
+/// It yields events ordered by their frame, regardless of the order they were
+/// pushed in.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+/// # wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+/// # #[cfg_attr(all(target_feature = "atomics", not(unsupported_spawn)), wasm_bindgen_test::wasm_bindgen_test)]
+/// # async fn test() {
+/// use web_thread::web::audio_worklet::EventQueue;
+/// use web_thread::web::JoinHandleExt;
+///
+/// let (producer, mut consumer) = EventQueue::<u8>::new(16).split();
+///
+/// producer.push(200, 64).unwrap();
+/// producer.push(10, 60).unwrap();
+///
+/// // Usually moved into `ExtendAudioWorkletProcessor::process()` and called with
+/// // `AudioWorkletGlobalScope.currentFrame`.
+/// let mut handle = web_thread::spawn(move || {
+/// 	let first: Vec<_> = consumer.drain(0, 128).collect();
+/// 	let second: Vec<_> = consumer.drain(128, 128).collect();
+///
+/// 	(first, second)
+/// });
+///
+/// let (first, second) = handle.join_async().await.unwrap();
+/// assert_eq!(first, [(10, 60)]);
+/// assert_eq!(second, [(72, 64)]);
+/// # }
+/// # #[cfg(not(all(target_feature = "atomics", not(unsupported_spawn))))]
+/// # let _ = test();
+/// ```
+pub struct EventQueue<E>(Arc<Shared<E>>);
+
+/// State shared between [`EventQueueProducer`]s and the
+/// [`EventQueueConsumer`].
+struct Shared<E> {
+	/// Storage.
+	buffer: Box<[Slot<E>]>,
+	/// Position of the next slot to pop.
+	read: AtomicUsize,
+	/// Position of the next slot to push.
+	write: AtomicUsize,
+}
+
+/// A single slot in [`Shared::buffer`].
+struct Slot<E> {
+	/// Equal to the position that is allowed to push into this slot, or one
+	/// larger if the slot is ready to be popped.
+	sequence: AtomicUsize,
+	/// The event and its frame.
+	value: UnsafeCell<MaybeUninit<(u64, E)>>,
+}
+
+// SAFETY: Slots are only accessed by a single producer or the consumer,
+// synchronized through their `sequence`.
+unsafe impl<E: Send> Sync for Shared<E> {}
+
+impl<E> Drop for Shared<E> {
+	fn drop(&mut self) {
+		while self.pop().is_some() {}
+	}
+}
+
+impl<E> Shared<E> {
+	/// Returns the capacity.
+	fn capacity(&self) -> usize {
+		self.buffer.len()
+	}
+
+	/// Returns the slot for `position`.
+	fn slot(&self, position: usize) -> &Slot<E> {
+		self.buffer
+			.get(position % self.capacity())
+			.expect("found out of bounds position")
+	}
+
+	/// Pushes an event. Returns it back if the queue is full.
+	fn push(&self, frame: u64, event: E) -> Result<(), E> {
+		let mut position = self.write.load(Ordering::Relaxed);
+
+		loop {
+			let slot = self.slot(position);
+			let sequence = slot.sequence.load(Ordering::Acquire);
+			// Wrapping is fine because the capacity is a power of two.
+			#[allow(clippy::as_conversions, clippy::cast_possible_wrap)]
+			let difference = sequence.wrapping_sub(position) as isize;
+
+			match difference.cmp(&0) {
+				cmp::Ordering::Equal => {
+					match self.write.compare_exchange_weak(
+						position,
+						position.wrapping_add(1),
+						Ordering::Relaxed,
+						Ordering::Relaxed,
+					) {
+						Ok(_) => {
+							// SAFETY: Claiming `position` gave us exclusive access to this slot
+							// until its `sequence` is advanced.
+							unsafe { slot.value.get().write(MaybeUninit::new((frame, event))) };
+							slot.sequence
+								.store(position.wrapping_add(1), Ordering::Release);
+
+							return Ok(());
+						}
+						Err(current) => position = current,
+					}
+				}
+				// The consumer has not popped this slot since the last lap.
+				cmp::Ordering::Less => return Err(event),
+				// Another producer claimed this slot in the meantime.
+				cmp::Ordering::Greater => position = self.write.load(Ordering::Relaxed),
+			}
+		}
+	}
+
+	/// Pops the oldest event. Must only be called by a single thread at a
+	/// time.
+	fn pop(&self) -> Option<(u64, E)> {
+		let position = self.read.load(Ordering::Relaxed);
+		let slot = self.slot(position);
+
+		if slot.sequence.load(Ordering::Acquire) != position.wrapping_add(1) {
+			return None;
+		}
+
+		// SAFETY: The slot was initialized by a producer before `sequence` was
+		// advanced and is not writable until it is advanced again.
+		let value = unsafe { slot.value.get().read().assume_init() };
+		slot.sequence
+			.store(position.wrapping_add(self.capacity()), Ordering::Release);
+		self.read.store(position.wrapping_add(1), Ordering::Relaxed);
+
+		Some(value)
+	}
+}
+
+impl<E> Debug for EventQueue<E> {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+		formatter
+			.debug_struct("EventQueue")
+			.field("type", &any::type_name::<E>())
+			.field("capacity", &self.0.capacity())
+			.finish()
+	}
+}
+
+impl<E> EventQueue<E> {
+	/// Creates a new [`EventQueue`] able to hold `capacity` events.
+	///
+	/// # Panics
+	///
+	/// If `capacity` is `0` or not a power of two.
+	#[must_use]
+	pub fn new(capacity: usize) -> Self {
+		assert_ne!(capacity, 0, "`capacity` must not be `0`");
+		assert!(
+			capacity.is_power_of_two(),
+			"`capacity` must be a power of two"
+		);
+
+		let buffer = (0..capacity)
+			.map(|sequence| Slot {
+				sequence: AtomicUsize::new(sequence),
+				value: UnsafeCell::new(MaybeUninit::uninit()),
+			})
+			.collect();
+
+		Self(Arc::new(Shared {
+			buffer,
+			read: AtomicUsize::new(0),
+			write: AtomicUsize::new(0),
+		}))
+	}
+
+	/// Returns the amount of events this [`EventQueue`] can hold.
+	#[must_use]
+	pub fn capacity(&self) -> usize {
+		self.0.capacity()
+	}
+
+	/// Splits this [`EventQueue`] into its [`EventQueueProducer`] and
+	/// [`EventQueueConsumer`] halves. The [`EventQueueProducer`] can be
+	/// cloned to push from multiple threads.
+	#[must_use]
+	pub fn split(self) -> (EventQueueProducer<E>, EventQueueConsumer<E>) {
+		let capacity = self.capacity();
+
+		(
+			EventQueueProducer(Arc::clone(&self.0)),
+			EventQueueConsumer {
+				shared: self.0,
+				pending: VecDeque::with_capacity(capacity),
+			},
+		)
+	}
+}
+
+/// Writing half of an [`EventQueue`].
+pub struct EventQueueProducer<E>(Arc<Shared<E>>);
+
+impl<E> Clone for EventQueueProducer<E> {
+	fn clone(&self) -> Self {
+		Self(Arc::clone(&self.0))
+	}
+}
+
+impl<E> Debug for EventQueueProducer<E> {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+		formatter
+			.debug_struct("EventQueueProducer")
+			.field("type", &any::type_name::<E>())
+			.field("capacity", &self.capacity())
+			.finish()
+	}
+}
+
+impl<E> EventQueueProducer<E> {
+	/// Returns the amount of events the [`EventQueue`] can hold.
+	#[must_use]
+	pub fn capacity(&self) -> usize {
+		self.0.capacity()
+	}
+
+	/// Pushes `event` to take effect at `frame`, which is compared against
+	/// [`AudioWorkletGlobalScope.currentFrame`].
+	///
+	/// # Errors
+	///
+	/// If the [`EventQueue`] is full, returning the passed `event`.
+	///
+	/// [`AudioWorkletGlobalScope.currentFrame`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletGlobalScope/currentFrame
+	pub fn push(&self, frame: u64, event: E) -> Result<(), E> {
+		self.0.push(frame, event)
+	}
+}
+
+/// Reading half of an [`EventQueue`]. All methods are lock-free and don't
+/// allocate.
+pub struct EventQueueConsumer<E> {
+	/// State shared with the [`EventQueueProducer`]s.
+	shared: Arc<Shared<E>>,
+	/// Events taken out of [`Self::shared`] that are not due yet, ordered by
+	/// their frame. Never grows beyond its initial capacity.
+	pending: VecDeque<(u64, E)>,
+}
+
+impl<E> Debug for EventQueueConsumer<E> {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+		formatter
+			.debug_struct("EventQueueConsumer")
+			.field("type", &any::type_name::<E>())
+			.field("capacity", &self.shared.capacity())
+			.field("pending", &self.pending.len())
+			.finish()
+	}
+}
+
+impl<E> EventQueueConsumer<E> {
+	/// Returns the amount of events the [`EventQueue`] can hold.
+	#[must_use]
+	pub fn capacity(&self) -> usize {
+		self.shared.capacity()
+	}
+
+	/// Returns an iterator over all events due in the block of `frames`
+	/// starting at `current_frame`, usually
+	/// [`AudioWorkletGlobalScope.currentFrame`]. Events are yielded in order
+	/// together with their offset into the block. Events whose frame has
+	/// already passed are yielded with an offset of `0`.
+	///
+	/// # Notes
+	///
+	/// Events not yielded because the iterator was dropped early are yielded
+	/// again by the next call.
+	///
+	/// Events not due yet are kept by the [`EventQueueConsumer`], which can
+	/// hold up to [`capacity()`](Self::capacity) of them in addition to the
+	/// [`EventQueue`] itself. Events are left in the [`EventQueue`] while it is
+	/// at its limit, so they are only yielded out of order if more than
+	/// [`capacity()`](Self::capacity) events are scheduled ahead.
+	///
+	/// [`AudioWorkletGlobalScope.currentFrame`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletGlobalScope/currentFrame
+	pub fn drain(&mut self, current_frame: u64, frames: usize) -> EventQueueDrain<'_, E> {
+		while self.pending.len() < self.capacity() {
+			let Some((frame, event)) = self.shared.pop() else {
+				break;
+			};
+
+			let index = self
+				.pending
+				.partition_point(|(pending, _)| *pending <= frame);
+			self.pending.insert(index, (frame, event));
+		}
+
+		EventQueueDrain {
+			pending: &mut self.pending,
+			current_frame,
+			end: current_frame.saturating_add(u64::try_from(frames).unwrap_or(u64::MAX)),
+		}
+	}
+}
+
+/// Iterator over due events of an [`EventQueue`]. See
+/// [`EventQueueConsumer::drain()`].
+pub struct EventQueueDrain<'consumer, E> {
+	/// Ordered events not yet yielded.
+	pending: &'consumer mut VecDeque<(u64, E)>,
+	/// First frame of the block.
+	current_frame: u64,
+	/// First frame after the block.
+	end: u64,
+}
+
+impl<E> Debug for EventQueueDrain<'_, E> {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+		formatter
+			.debug_struct("EventQueueDrain")
+			.field("type", &any::type_name::<E>())
+			.field("pending", &self.pending.len())
+			.field("current_frame", &self.current_frame)
+			.field("end", &self.end)
+			.finish()
+	}
+}
+
+impl<E> Iterator for EventQueueDrain<'_, E> {
+	type Item = (usize, E);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.pending.front()?.0 >= self.end {
+			return None;
+		}
+
+		let (frame, event) = self.pending.pop_front()?;
+		let offset = frame.saturating_sub(self.current_frame);
+
+		Some((offset.try_into().expect("offset larger than block"), event))
+	}
+}
+
+impl<E> iter::FusedIterator for EventQueueDrain<'_, E> {}
@@ -0,0 +1,1282 @@
+//! Platform-specific extensions for [`web-thread`](crate) on the Web platform
+//! to spawn and use audio worklets. See
+//! [`BaseAudioContextExt::audio_worklet_node()`] for a usage example.
+
+#[cfg(any(feature = "detect-allocations", docsrs))]
+mod allocator;
+mod event_queue;
+mod node;
+mod parameters;
+mod ring_buffer;
+mod signal;
+
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::future::Future;
+use std::panic::{RefUnwindSafe, UnwindSafe};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::{any, fmt, io};
+
+use js_sys::{Array, Iterator, Object};
+#[cfg(all(
+	target_family = "wasm",
+	target_os = "unknown",
+	feature = "audio-worklet"
+))]
+use web_sys::{AudioWorkletGlobalScope, BaseAudioContext};
+use web_sys::{AudioWorkletNode, AudioWorkletNodeOptions, AudioWorkletProcessor};
+
+#[cfg(any(feature = "detect-allocations", docsrs))]
+pub use self::allocator::DetectingAllocator;
+pub use self::event_queue::{EventQueue, EventQueueConsumer, EventQueueDrain, EventQueueProducer};
+pub use self::node::{ProcessorErrorFuture, RustAudioWorkletNode};
+pub use self::parameters::{
+	AudioParamDescriptor, AudioParameters, AudioParametersIter, AutomationRate,
+};
+pub use self::ring_buffer::{
+	RingBuffer, RingBufferConsumer, RingBufferProducer, WaitForSpaceFuture,
+};
+pub use self::signal::{Signal, SignalFuture};
+#[cfg(any(feature = "message", docsrs))]
+use super::message::MessageSend;
+#[cfg(all(
+	target_family = "wasm",
+	target_os = "unknown",
+	feature = "audio-worklet"
+))]
+use crate::thread::audio_worklet;
+use crate::Thread;
+
+#[cfg(not(all(
+	target_family = "wasm",
+	target_os = "unknown",
+	feature = "audio-worklet"
+)))]
+mod audio_worklet {
+	pub(super) struct AudioWorkletHandle;
+	pub(super) struct RegisterThreadFuture;
+	pub(super) struct RegisterProcessorFuture;
+	pub(super) struct AudioWorkletNodeFromFnFuture;
+}
+#[cfg(not(all(
+	target_family = "wasm",
+	target_os = "unknown",
+	feature = "audio-worklet"
+)))]
+mod web_sys {
+	pub(super) struct AudioWorkletNodeOptions;
+	pub(super) struct AudioWorkletProcessor;
+	pub(super) struct AudioWorkletNode;
+}
+#[cfg(not(all(
+	target_family = "wasm",
+	target_os = "unknown",
+	feature = "audio-worklet"
+)))]
+mod js_sys {
+	pub(super) struct Array;
+	pub(super) struct Iterator;
+	pub(super) struct Object;
+}
+
+/// Extension for [`BaseAudioContext`].
+#[cfg_attr(
+	not(all(
+		target_family = "wasm",
+		target_os = "unknown",
+		feature = "audio-worklet"
+	)),
+	doc = "",
+	doc = "[`BaseAudioContext`]: https://docs.rs/web-sys/0.3.68/web_sys/struct.BaseAudioContext.html"
+)]
+pub trait BaseAudioContextExt {
+	/// Registers a thread at this [`BaseAudioContext`].
+	///
+	/// # Notes
+	///
+	/// Unfortunately there is currently no way to determine when the thread has
+	/// fully shutdown. So this will leak memory unless
+	/// [`AudioWorkletHandle::release()`] is called.
+	///
+	/// # Errors
+	///
+	/// - If a thread was already registered at this [`BaseAudioContext`].
+	/// - If the [`BaseAudioContext`] is [`closed`].
+	/// - If the main thread does not support spawning threads, see
+	///   [`has_spawn_support()`](super::has_spawn_support).
+	///
+	/// # Example
+	///
+	/// ```
+	/// # #[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+	/// # wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+	/// # #[cfg_attr(all(target_feature = "atomics", not(unsupported_spawn)), wasm_bindgen_test::wasm_bindgen_test)]
+	/// # async fn test() {
+	/// use web_sys::AudioContext;
+	/// use web_thread::web::audio_worklet::BaseAudioContextExt;
+	///
+	/// let context = AudioContext::new().unwrap();
+	/// context.clone().register_thread(
+	/// 	None,
+	/// 	|| {
+	/// 		// Do work.
+	/// 	},
+	/// ).await.unwrap();
+	/// # }
+	/// # #[cfg(not(all(target_feature = "atomics", not(unsupported_spawn))))]
+	/// # let _ = test();
+	/// ```
+	///
+	/// [`closed`]: https://developer.mozilla.org/en-US/docs/Web/API/BaseAudioContext/state#closed
+	#[cfg_attr(
+		not(all(
+			target_family = "wasm",
+			target_os = "unknown",
+			feature = "audio-worklet"
+		)),
+		doc = "[`BaseAudioContext`]: https://docs.rs/web-sys/0.3.68/web_sys/struct.BaseAudioContext.html"
+	)]
+	fn register_thread<F>(self, stack_size: Option<usize>, f: F) -> RegisterThreadFuture
+	where
+		F: 'static + FnOnce() + Send;
+
+	/// Registers a thread at this [`BaseAudioContext`].
+	///
+	/// # Notes
+	///
+	/// Unfortunately there is currently no way to determine when the thread has
+	/// fully shutdown. So this will leak memory unless
+	/// [`AudioWorkletHandle::release()`] is called.
+	///
+	/// # Errors
+	///
+	/// - If a thread was already registered at this [`BaseAudioContext`].
+	/// - If the [`BaseAudioContext`] is [`closed`].
+	/// - If the main thread does not support spawning threads, see
+	///   [`has_spawn_support()`](super::has_spawn_support).
+	///
+	/// # Example
+	///
+	/// ```
+	/// # #[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+	/// # wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+	/// # #[cfg_attr(all(target_feature = "atomics", not(unsupported_spawn)), wasm_bindgen_test::wasm_bindgen_test)]
+	/// # async fn test() {
+	/// use js_sys::ArrayBuffer;
+	/// use web_sys::AudioContext;
+	/// use web_thread::web::audio_worklet::BaseAudioContextExt;
+	/// use web_thread::web::message::TransferableWrapper;
+	///
+	/// let context = AudioContext::new().unwrap();
+	/// let buffer = TransferableWrapper(ArrayBuffer::new(1024));
+	/// context
+	/// 	.clone()
+	/// 	.register_thread_with_message(
+	/// 		None,
+	/// 		|message| {
+	/// 			let buffer: ArrayBuffer = message.0;
+	/// 			// Do work.
+	/// #           let _ = buffer;
+	/// 		},
+	/// 		buffer,
+	/// 	)
+	/// 	.await
+	/// 	.unwrap();
+	/// # }
+	/// # #[cfg(not(all(target_feature = "atomics", not(unsupported_spawn))))]
+	/// # let _ = test();
+	/// ```
+	///
+	/// [`closed`]: https://developer.mozilla.org/en-US/docs/Web/API/BaseAudioContext/state#closed
+	#[cfg_attr(
+		not(all(
+			target_family = "wasm",
+			target_os = "unknown",
+			feature = "audio-worklet"
+		)),
+		doc = "[`BaseAudioContext`]: https://docs.rs/web-sys/0.3.68/web_sys/struct.BaseAudioContext.html"
+	)]
+	#[cfg(any(feature = "message", docsrs))]
+	fn register_thread_with_message<F, M>(
+		self,
+		stack_size: Option<usize>,
+		f: F,
+		message: M,
+	) -> RegisterThreadFuture
+	where
+		F: 'static + FnOnce(M) + Send,
+		M: 'static + MessageSend;
+
+	/// Registers the [`ExtendAudioWorkletProcessor`] `P` under `name` from the
+	/// main thread. This dispatches
+	/// [`AudioWorkletGlobalScopeExt::register_processor_ext()`] to the audio
+	/// worklet thread registered with [`Self::register_thread()`] and resolves
+	/// after the processor was registered and is available to
+	/// [`Self::audio_worklet_node()`].
+	///
+	/// # Errors
+	///
+	/// - If [`Self::register_thread()`] was not called and awaited on this
+	///   context yet.
+	/// - If the audio worklet thread was released with
+	///   [`AudioWorkletHandle::release()`].
+	/// - If [`AudioWorkletGlobalScopeExt::register_processor_ext()`] fails.
+	/// - If the audio worklet panicked.
+	///
+	/// # Example
+	///
+	/// ```
+	/// # #[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+	/// # wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+	/// # #[cfg_attr(all(target_feature = "atomics", not(unsupported_spawn)), wasm_bindgen_test::wasm_bindgen_test)]
+	/// # async fn test() {
+	/// use web_sys::{AudioContext, AudioWorkletNodeOptions, AudioWorkletProcessor};
+	/// use web_thread::web::audio_worklet::{BaseAudioContextExt, ExtendAudioWorkletProcessor};
+	///
+	/// struct TestProcessor;
+	///
+	/// impl ExtendAudioWorkletProcessor for TestProcessor {
+	/// 	type Data = ();
+	///
+	/// 	fn new(_: AudioWorkletProcessor, _: Option<Self::Data>, _: AudioWorkletNodeOptions) -> Self {
+	/// 		Self
+	/// 	}
+	/// }
+	///
+	/// let context = AudioContext::new().unwrap();
+	/// context.clone().register_thread(None, || ()).await.unwrap();
+	/// context.register_processor::<TestProcessor>("test").await.unwrap();
+	///
+	/// let node = context.audio_worklet_node::<TestProcessor>("test", (), None).unwrap();
+	/// # let _ = node;
+	/// # }
+	/// # #[cfg(not(all(target_feature = "atomics", not(unsupported_spawn))))]
+	/// # let _ = test();
+	/// ```
+	fn register_processor<P>(&self, name: &str) -> RegisterProcessorFuture
+	where
+		P: 'static + ExtendAudioWorkletProcessor;
+
+	/// Instantiates a [`AudioWorkletProcessor`]. No `data` will be delivered if
+	/// `name` corresponds to a different type registered with
+	/// [`AudioWorkletGlobalScopeExt::register_processor_ext()`]. If `name`
+	/// corresponds to a [`AudioWorkletProcessor`] not registered through
+	/// [`AudioWorkletGlobalScopeExt::register_processor_ext()`], it will leak
+	/// `data`.
+	///
+	/// # Errors
+	///
+	/// - If [`Self::register_thread()`] was not called on this context yet.
+	/// - If [`new AudioWorkletNode`] throws an exception.
+	///
+	/// # Example
+	///
+	/// ```
+	/// # #[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+	/// # wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+	/// # #[cfg_attr(all(target_feature = "atomics", not(unsupported_spawn)), wasm_bindgen_test::wasm_bindgen_test)]
+	/// # async fn test() {
+	/// # use wasm_bindgen::JsCast;
+	/// use web_sys::{AudioContext, AudioWorkletGlobalScope, AudioWorkletNodeOptions, AudioWorkletProcessor};
+	/// use web_thread::web::{self, YieldTime};
+	/// use web_thread::web::audio_worklet::{AudioWorkletGlobalScopeExt, BaseAudioContextExt, ExtendAudioWorkletProcessor};
+	///
+	/// /// Example [`AudioWorkletProcessor`].
+	/// struct TestProcessor;
+	///
+	/// impl ExtendAudioWorkletProcessor for TestProcessor {
+	/// 	type Data = String;
+	///
+	/// 	fn new(
+	/// 		_: AudioWorkletProcessor,
+	/// 		data: Option<Self::Data>,
+	/// 		_: AudioWorkletNodeOptions,
+	/// 	) -> Self {
+	/// 		assert_eq!(data.as_deref(), Some("test"));
+	/// 		Self
+	/// 	}
+	/// }
+	///
+	/// let context = AudioContext::new().unwrap();
+	/// let (sender, receiver) = async_channel::bounded(1);
+	/// context.clone().register_thread(
+	/// 	None,
+	/// 	move || {
+	/// 		let global: AudioWorkletGlobalScope = js_sys::global().unchecked_into();
+	/// 		global
+	/// 			.register_processor_ext::<TestProcessor>("test")
+	/// 			.unwrap();
+	/// 		sender.try_send(()).unwrap();
+	/// 	},
+	/// ).await.unwrap();
+	///
+	/// // Wait until processor is registered.
+	/// receiver.recv().await.unwrap();
+	/// web::yield_now_async(YieldTime::UserBlocking).await;
+	///
+	/// let node = context.audio_worklet_node::<TestProcessor>("test", String::from("test"), None).unwrap();
+	/// # let _ = node;
+	/// # }
+	/// # #[cfg(not(all(target_feature = "atomics", not(unsupported_spawn))))]
+	/// # let _ = test();
+	/// ```
+	///
+	/// [`new AudioWorkletNode`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletNode/AudioWorkletNode
+	/// [`AudioWorkletProcessor`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletProcessor
+	fn audio_worklet_node<P>(
+		&self,
+		name: &str,
+		data: P::Data,
+		options: Option<&AudioWorkletNodeOptions>,
+	) -> Result<AudioWorkletNode, AudioWorkletNodeError<P>>
+	where
+		P: 'static + ExtendAudioWorkletProcessor,
+		P::Data: Send;
+
+	/// Instantiates a [`AudioWorkletProcessor`] like
+	/// [`Self::audio_worklet_node()`], but sends `data` as a [`MessageSend`].
+	/// This allows `data` to contain JS values, e.g. an [`ArrayBuffer`],
+	/// which are [serialized] into
+	/// [`AudioWorkletNodeOptions.processorOptions`] and reconstructed before
+	/// [`ExtendAudioWorkletProcessor::new()`] is called.
+	///
+	/// # Notes
+	///
+	/// [`AudioWorkletNodeOptions.processorOptions`] can only be cloned, so
+	/// [transferable] values, e.g. a [`MessagePort`], are not supported. Use
+	/// [`RustAudioWorkletNode::post()`] to send them after instantiation.
+	///
+	/// # Errors
+	///
+	/// - If [`Self::register_thread()`] was not called on this context yet.
+	/// - If `data` contains [transferable] values.
+	/// - If [`new AudioWorkletNode`] throws an exception.
+	///
+	/// # Example
+	///
+	/// ```
+	/// # #[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+	/// # wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+	/// # #[cfg_attr(all(target_feature = "atomics", not(unsupported_spawn)), wasm_bindgen_test::wasm_bindgen_test)]
+	/// # async fn test() {
+	/// use js_sys::ArrayBuffer;
+	/// use web_sys::{AudioContext, AudioWorkletNodeOptions, AudioWorkletProcessor};
+	/// use web_thread::web::audio_worklet::{BaseAudioContextExt, ExtendAudioWorkletProcessor};
+	/// use web_thread::web::message::SerializableWrapper;
+	///
+	/// # #[allow(dead_code)]
+	/// struct TestProcessor(ArrayBuffer);
+	///
+	/// impl ExtendAudioWorkletProcessor for TestProcessor {
+	/// 	type Data = SerializableWrapper<ArrayBuffer>;
+	///
+	/// 	fn new(
+	/// 		_: AudioWorkletProcessor,
+	/// 		data: Option<Self::Data>,
+	/// 		_: AudioWorkletNodeOptions,
+	/// 	) -> Self {
+	/// 		Self(data.unwrap().0)
+	/// 	}
+	/// }
+	///
+	/// let context = AudioContext::new().unwrap();
+	/// context.clone().register_thread(None, || ()).await.unwrap();
+	/// context
+	/// 	.register_processor::<TestProcessor>("test")
+	/// 	.await
+	/// 	.unwrap();
+	///
+	/// let buffer = ArrayBuffer::new(1024);
+	/// let node = context
+	/// 	.audio_worklet_node_with_message::<TestProcessor>("test", SerializableWrapper(buffer), None)
+	/// 	.unwrap();
+	/// # let _ = node;
+	/// # }
+	/// # #[cfg(not(all(target_feature = "atomics", not(unsupported_spawn))))]
+	/// # let _ = test();
+	/// ```
+	///
+	/// [`AudioWorkletNodeOptions.processorOptions`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletNode/AudioWorkletNode#processoroptions
+	/// [`AudioWorkletProcessor`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletProcessor
+	/// [`new AudioWorkletNode`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletNode/AudioWorkletNode
+	/// [serialized]: https://developer.mozilla.org/en-US/docs/Glossary/Serializable_object
+	/// [transferable]: https://developer.mozilla.org/en-US/docs/Web/API/Web_Workers_API/Transferable_objects
+	#[cfg_attr(
+		all(target_family = "wasm", target_os = "unknown"),
+		doc = "[`MessagePort`]: web_sys::MessagePort",
+		doc = "[`ArrayBuffer`]: js_sys::ArrayBuffer"
+	)]
+	#[cfg_attr(
+		not(all(target_family = "wasm", target_os = "unknown")),
+		doc = "[`MessagePort`]: https://docs.rs/web-sys/0.3.68/web_sys/struct.MessagePort.html",
+		doc = "[`ArrayBuffer`]: https://docs.rs/js-sys/0.3.68/js_sys/struct.ArrayBuffer.html"
+	)]
+	#[cfg(any(feature = "message", docsrs))]
+	fn audio_worklet_node_with_message<P>(
+		&self,
+		name: &str,
+		data: P::Data,
+		options: Option<&AudioWorkletNodeOptions>,
+	) -> Result<AudioWorkletNode, AudioWorkletNodeError<P>>
+	where
+		P: 'static + ExtendAudioWorkletProcessor,
+		P::Data: MessageSend;
+
+	/// Instantiates a [`AudioWorkletProcessor`] running the closure `f` instead
+	/// of a type implementing [`ExtendAudioWorkletProcessor`]. `f` is called
+	/// like [`ProcessBuffers::process_buffers()`].
+	///
+	/// The underlying processor is registered automatically in every thread
+	/// registered with [`Self::register_thread()`], so no additional
+	/// registration is needed. The returned [`AudioWorkletNodeFromFnFuture`]
+	/// waits until the thread is ready to instantiate it.
+	///
+	/// # Errors
+	///
+	/// - If [`Self::register_thread()`] was not called on this context yet.
+	/// - If the [`AudioWorkletHandle`] of this context was released.
+	/// - If the audio worklet panicked.
+	/// - If [`new AudioWorkletNode`] throws an exception.
+	///
+	/// # Example
+	///
+	/// ```
+	/// # #[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+	/// # wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+	/// # #[cfg_attr(all(target_feature = "atomics", not(unsupported_spawn)), wasm_bindgen_test::wasm_bindgen_test)]
+	/// # async fn test() {
+	/// use web_sys::AudioContext;
+	/// use web_thread::web::audio_worklet::BaseAudioContextExt;
+	///
+	/// let context = AudioContext::new().unwrap();
+	/// context.clone().register_thread(None, || ()).await.unwrap();
+	///
+	/// let mut phase = 0_f32;
+	/// let node = context
+	/// 	.audio_worklet_node_from_fn(None, move |_, outputs, _| {
+	/// 		for channel in outputs.iter_mut().flat_map(|output| output.iter_mut()) {
+	/// 			for sample in channel.iter_mut() {
+	/// 				*sample = phase.sin();
+	/// 				phase += 0.01;
+	/// 			}
+	/// 		}
+	///
+	/// 		true
+	/// 	})
+	/// 	.await
+	/// 	.unwrap();
+	/// # let _ = node;
+	/// # }
+	/// # #[cfg(not(all(target_feature = "atomics", not(unsupported_spawn))))]
+	/// # let _ = test();
+	/// ```
+	///
+	/// [`new AudioWorkletNode`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletNode/AudioWorkletNode
+	/// [`AudioWorkletProcessor`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletProcessor
+	fn audio_worklet_node_from_fn<F>(
+		&self,
+		options: Option<&AudioWorkletNodeOptions>,
+		f: F,
+	) -> AudioWorkletNodeFromFnFuture
+	where
+		F: 'static
+			+ FnMut(&[&[&[f32]]], &mut [&mut [&mut [f32]]], &AudioParameters<'_>) -> bool
+			+ Send;
+}
+
+#[cfg(all(
+	target_family = "wasm",
+	target_os = "unknown",
+	feature = "audio-worklet"
+))]
+impl<T> BaseAudioContextExt for T
+where
+	BaseAudioContext: From<T>,
+	T: AsRef<BaseAudioContext>,
+{
+	fn register_thread<F>(
+		self,
+		stack_size: Option<usize>,
+		#[allow(clippy::min_ident_chars)] f: F,
+	) -> RegisterThreadFuture
+	where
+		F: 'static + FnOnce() + Send,
+	{
+		RegisterThreadFuture(audio_worklet::register_thread(self.into(), stack_size, f))
+	}
+
+	#[cfg(any(feature = "message", docsrs))]
+	fn register_thread_with_message<F, M>(
+		self,
+		stack_size: Option<usize>,
+		#[allow(clippy::min_ident_chars)] f: F,
+		message: M,
+	) -> RegisterThreadFuture
+	where
+		F: 'static + FnOnce(M) + Send,
+		M: 'static + MessageSend,
+	{
+		RegisterThreadFuture(audio_worklet::register_thread_with_message(
+			self.into(),
+			stack_size,
+			f,
+			message,
+		))
+	}
+
+	fn register_processor<P>(&self, name: &str) -> RegisterProcessorFuture
+	where
+		P: 'static + ExtendAudioWorkletProcessor,
+	{
+		RegisterProcessorFuture(audio_worklet::register_processor_from_main::<P>(
+			self.as_ref(),
+			name,
+		))
+	}
+
+	fn audio_worklet_node<P>(
+		&self,
+		name: &str,
+		data: P::Data,
+		options: Option<&AudioWorkletNodeOptions>,
+	) -> Result<AudioWorkletNode, AudioWorkletNodeError<P>>
+	where
+		P: 'static + ExtendAudioWorkletProcessor,
+		P::Data: Send,
+	{
+		audio_worklet::audio_worklet_node(self.as_ref(), name, data, options)
+	}
+
+	#[cfg(any(feature = "message", docsrs))]
+	fn audio_worklet_node_with_message<P>(
+		&self,
+		name: &str,
+		data: P::Data,
+		options: Option<&AudioWorkletNodeOptions>,
+	) -> Result<AudioWorkletNode, AudioWorkletNodeError<P>>
+	where
+		P: 'static + ExtendAudioWorkletProcessor,
+		P::Data: MessageSend,
+	{
+		audio_worklet::audio_worklet_node_with_message(self.as_ref(), name, data, options)
+	}
+
+	fn audio_worklet_node_from_fn<F>(
+		&self,
+		options: Option<&AudioWorkletNodeOptions>,
+		#[allow(clippy::min_ident_chars)] f: F,
+	) -> AudioWorkletNodeFromFnFuture
+	where
+		F: 'static
+			+ FnMut(&[&[&[f32]]], &mut [&mut [&mut [f32]]], &AudioParameters<'_>) -> bool
+			+ Send,
+	{
+		AudioWorkletNodeFromFnFuture(audio_worklet::audio_worklet_node_from_fn(
+			self.as_ref(),
+			options,
+			f,
+		))
+	}
+}
+
+/// Renders the [`ExtendAudioWorkletProcessor`] `P` with an
+/// [`OfflineAudioContext`] and returns the rendered samples of each channel.
+///
+/// This registers a thread, registers `P` and connects a single node,
+/// instantiated with `data`, with `channels` output channels to the
+/// destination. Useful for deterministic tests of audio processing code.
+///
+/// # Notes
+///
+/// The memory of the registered audio worklet thread is never released, see
+/// [`BaseAudioContextExt::register_thread()`].
+///
+/// # Errors
+///
+/// - If the main thread does not support spawning threads, see
+///   [`has_spawn_support()`](super::has_spawn_support).
+/// - If any of the involved Web APIs throws an exception, e.g. when passing an
+///   invalid `sample_rate`.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+/// # wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+/// # #[cfg_attr(all(target_feature = "atomics", not(unsupported_spawn)), wasm_bindgen_test::wasm_bindgen_test)]
+/// # async fn test() {
+/// use web_sys::{AudioWorkletNodeOptions, AudioWorkletProcessor};
+/// use web_thread::web::audio_worklet::{
+/// 	self, AudioParameters, ExtendAudioWorkletProcessor, ProcessBuffers, ProcessBuffersFn,
+/// };
+///
+/// struct Constant(f32);
+///
+/// impl ExtendAudioWorkletProcessor for Constant {
+/// 	type Data = f32;
+///
+/// 	const PROCESS_BUFFERS: Option<ProcessBuffersFn<Self>> = Some(ProcessBuffersFn::new());
+///
+/// 	fn new(_: AudioWorkletProcessor, data: Option<Self::Data>, _: AudioWorkletNodeOptions) -> Self {
+/// 		Self(data.unwrap())
+/// 	}
+/// }
+///
+/// impl ProcessBuffers for Constant {
+/// 	fn process_buffers(
+/// 		&mut self,
+/// 		_: &[&[&[f32]]],
+/// 		outputs: &mut [&mut [&mut [f32]]],
+/// 		_: &AudioParameters<'_>,
+/// 	) -> bool {
+/// 		for channel in outputs.iter_mut().flat_map(|output| output.iter_mut()) {
+/// 			channel.fill(self.0);
+/// 		}
+///
+/// 		true
+/// 	}
+/// }
+///
+/// let channels = audio_worklet::render_offline::<Constant>(8000., 128, 2, 0.5).await.unwrap();
+/// assert_eq!(channels.len(), 2);
+/// assert!(channels.iter().flatten().all(|sample| (*sample - 0.5).abs() < f32::EPSILON));
+/// # }
+/// # #[cfg(not(all(target_feature = "atomics", not(unsupported_spawn))))]
+/// # let _ = test();
+/// ```
+///
+/// [`OfflineAudioContext`]: https://developer.mozilla.org/en-US/docs/Web/API/OfflineAudioContext
+pub async fn render_offline<P>(
+	sample_rate: f32,
+	frames: u32,
+	channels: u32,
+	data: P::Data,
+) -> io::Result<Vec<Vec<f32>>>
+where
+	P: 'static + ExtendAudioWorkletProcessor,
+	P::Data: Send,
+{
+	audio_worklet::render_offline::<P>(sample_rate, frames, channels, data).await
+}
+
+/// Error returned by [`BaseAudioContextExt::audio_worklet_node()`].
+pub struct AudioWorkletNodeError<P>
+where
+	P: ExtendAudioWorkletProcessor,
+{
+	/// The passed [`ExtendAudioWorkletProcessor::Data`].
+	pub data: P::Data,
+	/// The error thrown by [`new AudioWorkletNode`].
+	///
+	/// [`new AudioWorkletNode`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletNode/AudioWorkletNode
+	pub error: io::Error,
+}
+
+impl<P> Debug for AudioWorkletNodeError<P>
+where
+	P: ExtendAudioWorkletProcessor,
+{
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+		formatter
+			.debug_struct("AudioWorkletNodeError")
+			.field("data", &any::type_name::<P::Data>())
+			.field("error", &self.error)
+			.finish()
+	}
+}
+
+impl<P> Display for AudioWorkletNodeError<P>
+where
+	P: ExtendAudioWorkletProcessor,
+{
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+		Display::fmt(&self.error, formatter)
+	}
+}
+
+impl<P> Error for AudioWorkletNodeError<P>
+where
+	P: ExtendAudioWorkletProcessor,
+{
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		Some(&self.error)
+	}
+}
+
+/// Waits for the associated thread to register. See
+/// [`BaseAudioContextExt::register_thread()`].
+#[derive(Debug)]
+pub struct RegisterThreadFuture(audio_worklet::RegisterThreadFuture);
+
+impl Future for RegisterThreadFuture {
+	type Output = io::Result<AudioWorkletHandle>;
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		Pin::new(&mut self.0).poll(cx).map_ok(AudioWorkletHandle)
+	}
+}
+
+impl RefUnwindSafe for RegisterThreadFuture {}
+
+/// Waits for the processor to be registered. See
+/// [`BaseAudioContextExt::register_processor()`].
+#[derive(Debug)]
+pub struct RegisterProcessorFuture(audio_worklet::RegisterProcessorFuture);
+
+impl Future for RegisterProcessorFuture {
+	type Output = io::Result<()>;
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		Pin::new(&mut self.0).poll(cx)
+	}
+}
+
+impl RefUnwindSafe for RegisterProcessorFuture {}
+
+/// Waits for the audio worklet to be able to instantiate the processor. See
+/// [`BaseAudioContextExt::audio_worklet_node_from_fn()`].
+#[derive(Debug)]
+pub struct AudioWorkletNodeFromFnFuture(audio_worklet::AudioWorkletNodeFromFnFuture);
+
+impl Future for AudioWorkletNodeFromFnFuture {
+	type Output = io::Result<AudioWorkletNode>;
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		Pin::new(&mut self.0).poll(cx)
+	}
+}
+
+impl RefUnwindSafe for AudioWorkletNodeFromFnFuture {}
+
+/// Handle to the audio worklet. See [`BaseAudioContextExt::register_thread()`].
+#[derive(Debug)]
+pub struct AudioWorkletHandle(audio_worklet::AudioWorkletHandle);
+
+impl AudioWorkletHandle {
+	/// Extracts a handle to the underlying thread.
+	///
+	/// # Example
+	///
+	/// ```
+	/// # #[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+	/// # wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+	/// # #[cfg_attr(all(target_feature = "atomics", not(unsupported_spawn)), wasm_bindgen_test::wasm_bindgen_test)]
+	/// # async fn test() {
+	/// use web_sys::{AudioContext, console};
+	/// use web_thread::web::audio_worklet::BaseAudioContextExt;
+	///
+	/// let context = AudioContext::new().unwrap();
+	/// let handle = context.clone().register_thread(
+	/// 	None,
+	/// 	|| {
+	/// 		// Do work.
+	/// 	},
+	/// ).await.unwrap();
+	///
+	/// console::log_1(&format!("thread id: {:?}", handle.thread().id()).into());
+	/// # }
+	/// # #[cfg(not(all(target_feature = "atomics", not(unsupported_spawn))))]
+	/// # let _ = test();
+	/// ```
+	#[must_use]
+	pub const fn thread(&self) -> &Thread {
+		self.0.thread()
+	}
+
+	/// This releases memory allocated for the corresponding audio worklet
+	/// thread.
+	///
+	/// # Safety
+	///
+	/// The corresponding thread must not currently or in the future access this
+	/// Wasm module.
+	///
+	/// # Errors
+	///
+	/// If called from its corresponding audio worklet thread.
+	///
+	/// # Example
+	///
+	/// ```
+	/// # #[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+	/// # wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+	/// # #[cfg_attr(all(target_feature = "atomics", not(unsupported_spawn)), wasm_bindgen_test::wasm_bindgen_test)]
+	/// # async fn test() {
+	/// use wasm_bindgen_futures::JsFuture;
+	/// use web_sys::AudioContext;
+	/// use web_thread::web::audio_worklet::BaseAudioContextExt;
+	///
+	/// let context = AudioContext::new().unwrap();
+	/// let (sender, receiver) = async_channel::bounded(1);
+	/// let handle = context.clone().register_thread(
+	/// 	None,
+	/// 	move || {
+	/// 		// Do work.
+	/// 		sender.try_send(()).unwrap();
+	/// 	},
+	/// ).await.unwrap();
+	///
+	/// // Wait until audio worklet is finished.
+	/// receiver.recv().await.unwrap();
+	/// JsFuture::from(context.close().unwrap()).await.unwrap();
+	/// // SAFETY: We are sure we are done with the audio worklet and didn't register any
+	/// // events or promises that could call into the Wasm module later.
+	/// unsafe { handle.release() }.unwrap();
+	/// # }
+	/// # #[cfg(not(all(target_feature = "atomics", not(unsupported_spawn))))]
+	/// # let _ = test();
+	/// ```
+	pub unsafe fn release(self) -> Result<(), ReleaseError> {
+		// SAFETY: See `ThreadMemory::release()`. Other safety guarantees have to be
+		// uphold by the caller.
+		unsafe { self.0.release() }
+			.map_err(Self)
+			.map_err(ReleaseError)
+	}
+}
+
+impl RefUnwindSafe for AudioWorkletHandle {}
+
+impl UnwindSafe for AudioWorkletHandle {}
+
+/// Returned on error in [`AudioWorkletHandle::release()`].
+#[derive(Debug)]
+pub struct ReleaseError(pub AudioWorkletHandle);
+
+impl Display for ReleaseError {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+		formatter.write_str(
+			"called `AudioWorkletHandle::release()` from its corresponding audio worklet thread",
+		)
+	}
+}
+
+impl Error for ReleaseError {}
+
+/// Extension for [`AudioWorkletGlobalScope`].
+#[cfg_attr(
+	not(all(
+		target_family = "wasm",
+		target_os = "unknown",
+		feature = "audio-worklet"
+	)),
+	doc = "",
+	doc = "[`AudioWorkletGlobalScope`]: https://docs.rs/web-sys/0.3.68/web_sys/struct.AudioWorkletGlobalScope.html"
+)]
+pub trait AudioWorkletGlobalScopeExt {
+	/// Creates a class that extends [`AudioWorkletProcessor`] and calls
+	/// [`AudioWorkletGlobalScope.registerProcessor()`]. This is a workaround
+	/// for [`wasm-bindgen`] currently unable to extend classes, see
+	/// [this `wasm-bindgen` issue](https://github.com/rustwasm/wasm-bindgen/issues/210).
+	///
+	/// # Notes
+	///
+	/// [`AudioWorkletGlobalScope.registerProcessor()`] does not sync with it's
+	/// corresponding [`AudioWorkletNode`] immediately and requires at least one
+	/// yield to the event loop cycle in the [`AudioWorkletNode`]s thread for
+	/// [`AudioWorkletNode::new()`] to successfully find the requested
+	/// [`AudioWorkletProcessor`] by its name. See [`yield_now_async()`].
+	///
+	/// # Errors
+	///
+	/// - If the `name` is empty.
+	/// - If a processor with this `name` is already registered.
+	/// - If this thread was not spawned by [`web-thread`](crate).
+	///
+	/// # Example
+	///
+	/// ```
+	/// # #[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+	/// # wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+	/// # #[cfg_attr(all(target_feature = "atomics", not(unsupported_spawn)), wasm_bindgen_test::wasm_bindgen_test)]
+	/// # async fn test() {
+	/// # use wasm_bindgen::JsCast;
+	/// use web_sys::{AudioContext, AudioWorkletGlobalScope, AudioWorkletNode};
+	/// # use web_sys::{AudioWorkletNodeOptions, AudioWorkletProcessor};
+	/// use web_thread::web::{self, YieldTime};
+	/// use web_thread::web::audio_worklet::{AudioWorkletGlobalScopeExt, BaseAudioContextExt};
+	/// # use web_thread::web::audio_worklet::ExtendAudioWorkletProcessor;
+	///
+	/// # struct TestProcessor;
+	/// # impl ExtendAudioWorkletProcessor for TestProcessor {
+	/// # 	type Data = ();
+	/// # 	fn new(
+	/// # 		_: AudioWorkletProcessor,
+	/// # 		_: Option<Self::Data>,
+	/// # 		_: AudioWorkletNodeOptions,
+	/// # 	) -> Self {
+	/// # 		Self
+	/// # 	}
+	/// # }
+	/// #
+	/// let context = AudioContext::new().unwrap();
+	/// let (sender, receiver) = async_channel::bounded(1);
+	/// context.clone().register_thread(
+	/// 	None,
+	/// 	move || {
+	/// 		let global: AudioWorkletGlobalScope = js_sys::global().unchecked_into();
+	/// 		global
+	/// 			.register_processor_ext::<TestProcessor>("test")
+	/// 			.unwrap();
+	/// 		sender.try_send(()).unwrap();
+	/// 	},
+	/// ).await.unwrap();
+	///
+	/// // Wait until processor is registered.
+	/// receiver.recv().await.unwrap();
+	/// web::yield_now_async(YieldTime::UserBlocking).await;
+	///
+	/// let node = AudioWorkletNode::new(&context, "test").unwrap();
+	/// # let _ = node;
+	/// # }
+	/// # #[cfg(not(all(target_feature = "atomics", not(unsupported_spawn))))]
+	/// # let _ = test();
+	/// ```
+	///
+	/// [`AudioWorkletGlobalScope.registerProcessor()`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletGlobalScope/registerProcessor
+	/// [`AudioWorkletProcessor`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletProcessor
+	/// [`yield_now_async()`]: super::yield_now_async
+	#[cfg_attr(
+		all(
+			target_family = "wasm",
+			target_os = "unknown",
+			feature = "audio-worklet"
+		),
+		doc = "[`AudioWorkletNode`]: web_sys::AudioWorkletNode",
+		doc = "[`AudioWorkletNode::new()`]: web_sys::AudioWorkletNode::new"
+	)]
+	#[cfg_attr(
+		not(all(
+			target_family = "wasm",
+			target_os = "unknown",
+			feature = "audio-worklet"
+		)),
+		doc = "[`AudioWorkletNode`]: https://docs.rs/web-sys/0.3.68/web_sys/struct.AudioWorkletNode.html",
+		doc = "[`AudioWorkletNode::new()`]: https://docs.rs/web-sys/0.3.68/web_sys/struct.AudioWorkletNode.html#method.new"
+	)]
+	#[cfg_attr(
+		all(target_family = "wasm", target_os = "unknown"),
+		doc = "[`wasm-bindgen`]: wasm_bindgen"
+	)]
+	#[cfg_attr(
+		not(all(target_family = "wasm", target_os = "unknown")),
+		doc = "[`wasm-bindgen`]: https://docs.rs/wasm-bindgen/0.2.91"
+	)]
+	fn register_processor_ext<P>(&self, name: &str) -> Result<(), io::Error>
+	where
+		P: 'static + ExtendAudioWorkletProcessor;
+}
+
+#[cfg(all(
+	target_family = "wasm",
+	target_os = "unknown",
+	feature = "audio-worklet"
+))]
+impl AudioWorkletGlobalScopeExt for AudioWorkletGlobalScope {
+	fn register_processor_ext<P>(&self, name: &str) -> Result<(), io::Error>
+	where
+		P: 'static + ExtendAudioWorkletProcessor,
+	{
+		audio_worklet::register_processor::<P>(name)
+	}
+}
+
+/// Extends type with [`AudioWorkletProcessor`].
+///
+/// # Notes
+///
+/// The processor is dropped when the [`AudioWorkletProcessor`] is garbage
+/// collected, which usually happens some time after its [`AudioWorkletNode`]
+/// is gone. If the processor holds on to the [`AudioWorkletProcessor`] passed
+/// to [`Self::new()`], it can never be garbage collected and will leak.
+///
+/// If [`Self::process()`] or [`ProcessBuffers::process_buffers()`] panics, the
+/// [`AudioWorkletNode`] receives a [`processorerror`] event and the processor
+/// is leaked instead of dropped, as its state might be invalid. Use
+/// [`RustAudioWorkletNode::processor_error()`] to get notified about this.
+///
+/// Use [`RustAudioWorkletNode::dispose()`] to drop the processor without
+/// waiting for garbage collection. Processors still alive after
+/// [`AudioWorkletHandle::release()`] are never dropped.
+///
+/// [`AudioWorkletProcessor`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletProcessor
+/// [`processorerror`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletNode/processorerror_event
+#[cfg_attr(
+	not(all(
+		target_family = "wasm",
+		target_os = "unknown",
+		feature = "audio-worklet"
+	)),
+	doc = "[`AudioWorkletNode`]: https://docs.rs/web-sys/0.3.68/web_sys/struct.AudioWorkletNode.html"
+)]
+pub trait ExtendAudioWorkletProcessor {
+	/// Data passed into [`Self::new()`] when using
+	/// [`BaseAudioContextExt::audio_worklet_node()`], which requires it to
+	/// implement [`Send`].
+	#[cfg_attr(
+		any(feature = "message", docsrs),
+		doc = "",
+		doc = "Alternatively sent with \
+		       [`BaseAudioContextExt::audio_worklet_node_with_message()`], which requires it to \
+		       implement [`MessageSend`]."
+	)]
+	type Data: 'static;
+
+	/// Equivalent to [`AudioWorkletProcessor()`].
+	///
+	/// [`AudioWorkletProcessor()`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletProcessor/AudioWorkletProcessor
+	fn new(
+		this: AudioWorkletProcessor,
+		data: Option<Self::Data>,
+		options: AudioWorkletNodeOptions,
+	) -> Self;
+
+	/// Descriptions of the [`AudioParam`]s of this processor. If not empty,
+	/// [`Self::parameter_descriptors()`] is ignored.
+	///
+	/// Values in [`ProcessBuffers::process_buffers()`] are ordered the same, so
+	/// they can be accessed with [`AudioParameters::get_by_index()`], e.g.
+	/// with a Rust enum.
+	///
+	/// [`AudioParam`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioParam
+	///
+	/// # Example
+	///
+	/// ```
+	/// # use web_sys::{AudioWorkletNodeOptions, AudioWorkletProcessor};
+	/// use web_thread::web::audio_worklet::{
+	/// 	AudioParamDescriptor, AudioParameters, AutomationRate, ExtendAudioWorkletProcessor,
+	/// 	ProcessBuffers, ProcessBuffersFn,
+	/// };
+	///
+	/// enum Parameter {
+	/// 	Gain,
+	/// 	Pan,
+	/// }
+	///
+	/// impl From<Parameter> for usize {
+	/// 	fn from(parameter: Parameter) -> Self {
+	/// 		parameter as usize
+	/// 	}
+	/// }
+	///
+	/// struct Mixer;
+	///
+	/// impl ExtendAudioWorkletProcessor for Mixer {
+	/// 	type Data = ();
+	///
+	/// 	const PARAMETERS: &'static [AudioParamDescriptor] = &[
+	/// 		AudioParamDescriptor::new("gain")
+	/// 			.with_default_value(1.)
+	/// 			.with_min_value(0.),
+	/// 		AudioParamDescriptor::new("pan")
+	/// 			.with_min_value(-1.)
+	/// 			.with_max_value(1.)
+	/// 			.with_automation_rate(AutomationRate::KRate),
+	/// 	];
+	/// 	const PROCESS_BUFFERS: Option<ProcessBuffersFn<Self>> = Some(ProcessBuffersFn::new());
+	///
+	/// 	fn new(
+	/// 		_: AudioWorkletProcessor,
+	/// 		_: Option<Self::Data>,
+	/// 		_: AudioWorkletNodeOptions,
+	/// 	) -> Self {
+	/// 		Self
+	/// 	}
+	/// }
+	///
+	/// impl ProcessBuffers for Mixer {
+	/// 	fn process_buffers(
+	/// 		&mut self,
+	/// 		_: &[&[&[f32]]],
+	/// 		_: &mut [&mut [&mut [f32]]],
+	/// 		parameters: &AudioParameters<'_>,
+	/// 	) -> bool {
+	/// 		let gain = parameters.value_by_index(Parameter::Gain, 0);
+	/// 		let pan = parameters.value_by_index(Parameter::Pan, 0);
+	/// 		// Mix.
+	/// 		# let _ = (gain, pan);
+	/// 		true
+	/// 	}
+	/// }
+	/// # let _ = Mixer;
+	/// ```
+	const PARAMETERS: &'static [AudioParamDescriptor] = &[];
+
+	/// If [`Some`], [`ProcessBuffers::process_buffers()`] is called instead of
+	/// [`Self::process()`]. See [`ProcessBuffersFn::new()`].
+	const PROCESS_BUFFERS: Option<ProcessBuffersFn<Self>> = None;
+
+	/// Equivalent to [`AudioWorkletProcessor.process()`].
+	///
+	/// [`AudioWorkletProcessor.process()`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletProcessor/process
+	#[allow(unused_variables)]
+	fn process(&mut self, inputs: Array, outputs: Array, parameters: Object) -> bool {
+		false
+	}
+
+	/// Called when [`Self::process()`] or [`ProcessBuffers::process_buffers()`]
+	/// returns [`false`]. Is only called again after they returned [`true`] in
+	/// between.
+	///
+	/// # Notes
+	///
+	/// Returning [`false`] only signals that the processor can be shut down if
+	/// it has no active inputs left, so processing might continue afterwards.
+	fn on_stop(&mut self) {}
+
+	/// Equivalent to [`AudioWorkletProcessor.parameterDescriptors`]. Only used
+	/// if [`Self::PARAMETERS`] is empty.
+	///
+	/// [`AudioWorkletProcessor.parameterDescriptors`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletProcessor/parameterDescriptors
+	#[allow(clippy::must_use_candidate)]
+	fn parameter_descriptors() -> Iterator {
+		Array::new().values()
+	}
+}
+
+/// Processes samples copied into buffers living in Wasm memory instead of JS
+/// objects. Enabled by setting
+/// [`ExtendAudioWorkletProcessor::PROCESS_BUFFERS`].
+pub trait ProcessBuffers: ExtendAudioWorkletProcessor {
+	/// Equivalent to [`AudioWorkletProcessor.process()`], but with samples
+	/// copied into buffers living in Wasm memory. Only called if
+	/// [`ExtendAudioWorkletProcessor::PROCESS_BUFFERS`] is [`Some`].
+	///
+	/// Unlike [`ExtendAudioWorkletProcessor::process()`], this doesn't create
+	/// any JS objects. The buffers are re-used across calls and only
+	/// re-allocated when the amount of inputs, outputs, channels or frames
+	/// changes. `outputs` are zeroed before every call.
+	///
+	/// [`AudioWorkletProcessor.process()`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletProcessor/process
+	///
+	/// # Notes
+	///
+	/// Re-allocating the buffers after such a layout change happens on the
+	/// audio rendering thread right before this is called. It is not covered
+	/// by allocation detection, but can cause a glitch, e.g. when connecting
+	/// an input with a different channel count while rendering.
+	///
+	/// # Example
+	///
+	/// ```
+	/// # use web_sys::{AudioWorkletNodeOptions, AudioWorkletProcessor};
+	/// use web_thread::web::audio_worklet::{
+	/// 	AudioParameters, ExtendAudioWorkletProcessor, ProcessBuffers, ProcessBuffersFn,
+	/// };
+	///
+	/// struct Gain;
+	///
+	/// impl ExtendAudioWorkletProcessor for Gain {
+	/// 	type Data = ();
+	///
+	/// 	const PROCESS_BUFFERS: Option<ProcessBuffersFn<Self>> = Some(ProcessBuffersFn::new());
+	///
+	/// 	fn new(
+	/// 		_: AudioWorkletProcessor,
+	/// 		_: Option<Self::Data>,
+	/// 		_: AudioWorkletNodeOptions,
+	/// 	) -> Self {
+	/// 		Self
+	/// 	}
+	/// }
+	///
+	/// impl ProcessBuffers for Gain {
+	/// 	fn process_buffers(
+	/// 		&mut self,
+	/// 		inputs: &[&[&[f32]]],
+	/// 		outputs: &mut [&mut [&mut [f32]]],
+	/// 		parameters: &AudioParameters<'_>,
+	/// 	) -> bool {
+	/// 		let (Some(input), Some(output)) = (inputs.first(), outputs.first_mut()) else {
+	/// 			return true;
+	/// 		};
+	///
+	/// 		for (input, output) in input.iter().zip(output.iter_mut()) {
+	/// 			for (frame, (input, output)) in input.iter().zip(output.iter_mut()).enumerate() {
+	/// 				*output = input * parameters.value("gain", frame).unwrap_or(1.);
+	/// 			}
+	/// 		}
+	///
+	/// 		true
+	/// 	}
+	/// }
+	/// # let _ = Gain;
+	/// ```
+	fn process_buffers(
+		&mut self,
+		inputs: &[&[&[f32]]],
+		outputs: &mut [&mut [&mut [f32]]],
+		parameters: &AudioParameters<'_>,
+	) -> bool;
+}
+
+/// Enables [`ProcessBuffers::process_buffers()`] when set as
+/// [`ExtendAudioWorkletProcessor::PROCESS_BUFFERS`].
+pub struct ProcessBuffersFn<P: ?Sized>(
+	#[cfg_attr(
+		not(all(
+			target_family = "wasm",
+			target_os = "unknown",
+			target_feature = "atomics",
+			feature = "audio-worklet"
+		)),
+		allow(dead_code)
+	)]
+	pub(crate) ProcessBuffersPtr<P>,
+);
+
+/// Function pointer to [`ProcessBuffers::process_buffers()`].
+type ProcessBuffersPtr<P> =
+	fn(&mut P, &[&[&[f32]]], &mut [&mut [&mut [f32]]], &AudioParameters<'_>) -> bool;
+
+impl<P: ProcessBuffers> ProcessBuffersFn<P> {
+	/// Creates a new [`ProcessBuffersFn`] calling
+	/// [`ProcessBuffers::process_buffers()`].
+	#[must_use]
+	pub const fn new() -> Self {
+		Self(P::process_buffers)
+	}
+}
+
+impl<P: ProcessBuffers> Default for ProcessBuffersFn<P> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<P: ?Sized> Clone for ProcessBuffersFn<P> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<P: ?Sized> Copy for ProcessBuffersFn<P> {}
+
+impl<P: ?Sized> Debug for ProcessBuffersFn<P> {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+		formatter
+			.debug_tuple("ProcessBuffersFn")
+			.field(&any::type_name::<P>())
+			.finish()
+	}
+}
+
+/// Receives messages sent with [`RustAudioWorkletNode::post()`].
+#[cfg(any(feature = "message", docsrs))]
+pub trait ReceiveMessage: ExtendAudioWorkletProcessor {
+	/// Message received by [`Self::on_message()`].
+	type Message: 'static + MessageSend;
+
+	/// Called when receiving a [`Self::Message`] sent with
+	/// [`RustAudioWorkletNode::post()`].
+	///
+	/// # Notes
+	///
+	/// Messages are only delivered to the processor type they were sent for,
+	/// which is the case unless a different processor was registered with
+	/// the same name. Otherwise they are dropped with a warning logged to the
+	/// console.
+	fn on_message(&mut self, message: Self::Message);
+}
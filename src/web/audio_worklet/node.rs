@@ -0,0 +1,342 @@
+//! Typed wrapper around an [`AudioWorkletNode`] running an
+//! [`ExtendAudioWorkletProcessor`].
+//!
+//! [`AudioWorkletNode`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletNode
+
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::panic::{RefUnwindSafe, UnwindSafe};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+#[cfg(all(
+	target_family = "wasm",
+	target_os = "unknown",
+	feature = "audio-worklet"
+))]
+use web_sys::{AudioWorkletNode, AudioWorkletNodeOptions, BaseAudioContext};
+
+#[cfg(any(feature = "message", docsrs))]
+use super::ReceiveMessage;
+use super::{AudioWorkletNodeError, ExtendAudioWorkletProcessor};
+#[cfg(all(
+	target_family = "wasm",
+	target_os = "unknown",
+	feature = "audio-worklet"
+))]
+use crate::thread::audio_worklet;
+#[cfg(any(feature = "message", docsrs))]
+use crate::web::message::MessageError;
+
+#[cfg(not(all(
+	target_family = "wasm",
+	target_os = "unknown",
+	feature = "audio-worklet"
+)))]
+mod audio_worklet {
+	pub(super) struct ProcessorErrorFuture;
+}
+#[cfg(not(all(
+	target_family = "wasm",
+	target_os = "unknown",
+	feature = "audio-worklet"
+)))]
+mod web_sys {
+	pub(super) struct AudioWorkletNode;
+	pub(super) struct AudioWorkletNodeOptions;
+	pub(super) struct BaseAudioContext;
+}
+#[cfg(not(all(
+	target_family = "wasm",
+	target_os = "unknown",
+	feature = "audio-worklet"
+)))]
+use self::web_sys::{AudioWorkletNode, AudioWorkletNodeOptions, BaseAudioContext};
+
+/// Wrapper around an [`AudioWorkletNode`] running the
+/// [`ExtendAudioWorkletProcessor`] `P`.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+/// # wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+/// # #[cfg_attr(all(target_feature = "atomics", not(unsupported_spawn)), wasm_bindgen_test::wasm_bindgen_test)]
+/// # async fn test() {
+/// use wasm_bindgen::JsCast;
+/// use web_sys::{AudioContext, AudioWorkletGlobalScope, AudioWorkletNodeOptions, AudioWorkletProcessor, console};
+/// use web_thread::web::{self, YieldTime};
+/// use web_thread::web::audio_worklet::{AudioWorkletGlobalScopeExt, BaseAudioContextExt, ExtendAudioWorkletProcessor, RustAudioWorkletNode};
+///
+/// struct Synth;
+///
+/// impl ExtendAudioWorkletProcessor for Synth {
+/// 	type Data = ();
+///
+/// 	fn new(_: AudioWorkletProcessor, _: Option<Self::Data>, _: AudioWorkletNodeOptions) -> Self {
+/// 		Self
+/// 	}
+/// }
+///
+/// let context = AudioContext::new().unwrap();
+/// let (sender, receiver) = async_channel::bounded(1);
+/// context.clone().register_thread(
+/// 	None,
+/// 	move || {
+/// 		let global: AudioWorkletGlobalScope = js_sys::global().unchecked_into();
+/// 		global.register_processor_ext::<Synth>("synth").unwrap();
+/// 		sender.try_send(()).unwrap();
+/// 	},
+/// ).await.unwrap();
+///
+/// // Wait until processor is registered.
+/// receiver.recv().await.unwrap();
+/// web::yield_now_async(YieldTime::UserBlocking).await;
+///
+/// let node = RustAudioWorkletNode::<Synth>::new(&context, "synth", (), None).unwrap();
+/// let error = node.processor_error();
+///
+/// wasm_bindgen_futures::spawn_local(async move {
+/// 	error.await;
+/// 	console::error_1(&"processor failed".into());
+/// });
+/// # }
+/// # #[cfg(not(all(target_feature = "atomics", not(unsupported_spawn))))]
+/// # let _ = test();
+/// ```
+#[cfg_attr(
+	not(all(
+		target_family = "wasm",
+		target_os = "unknown",
+		feature = "audio-worklet"
+	)),
+	doc = "",
+	doc = "[`AudioWorkletNode`]: https://docs.rs/web-sys/0.3.68/web_sys/struct.AudioWorkletNode.html"
+)]
+pub struct RustAudioWorkletNode<P: ExtendAudioWorkletProcessor> {
+	/// The wrapped [`AudioWorkletNode`].
+	node: AudioWorkletNode,
+	/// The [`ExtendAudioWorkletProcessor`] running on the other side.
+	_processor: PhantomData<P>,
+}
+
+impl<P: ExtendAudioWorkletProcessor> Clone for RustAudioWorkletNode<P> {
+	fn clone(&self) -> Self {
+		Self {
+			node: self.node.clone(),
+			_processor: PhantomData,
+		}
+	}
+}
+
+impl<P: ExtendAudioWorkletProcessor> Debug for RustAudioWorkletNode<P> {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+		formatter
+			.debug_struct("RustAudioWorkletNode")
+			.field("node", &self.node)
+			.finish()
+	}
+}
+
+impl<P: 'static + ExtendAudioWorkletProcessor> RustAudioWorkletNode<P> {
+	/// Creates a new [`RustAudioWorkletNode`]. See
+	/// [`BaseAudioContextExt::audio_worklet_node()`](super::BaseAudioContextExt::audio_worklet_node).
+	///
+	/// # Errors
+	///
+	/// See
+	/// [`BaseAudioContextExt::audio_worklet_node()`](super::BaseAudioContextExt::audio_worklet_node).
+	pub fn new(
+		context: &BaseAudioContext,
+		name: &str,
+		data: P::Data,
+		options: Option<&AudioWorkletNodeOptions>,
+	) -> Result<Self, AudioWorkletNodeError<P>>
+	where
+		P::Data: Send,
+	{
+		audio_worklet::audio_worklet_node(context, name, data, options).map(|node| Self {
+			node,
+			_processor: PhantomData,
+		})
+	}
+
+	/// Waits for the processor to fail, e.g. when its constructor or
+	/// [`ExtendAudioWorkletProcessor::process()`] panics. Corresponds to the
+	/// [`processorerror`] event.
+	///
+	/// # Notes
+	///
+	/// Only failures happening after calling this method are observed. After
+	/// a failure the processor is not called anymore and is never dropped.
+	///
+	/// [`processorerror`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletNode/processorerror_event
+	#[must_use]
+	pub fn processor_error(&self) -> ProcessorErrorFuture {
+		ProcessorErrorFuture(audio_worklet::processor_error(&self.node))
+	}
+
+	/// Drops the processor without waiting for its [`AudioWorkletProcessor`]
+	/// to be garbage collected. Afterwards the node stops processing.
+	///
+	/// # Notes
+	///
+	/// The processor is dropped when the audio worklet receives this request.
+	/// Messages sent with `RustAudioWorkletNode::post()` before are still
+	/// delivered. If the processor has failed, see [`Self::processor_error()`],
+	/// it is never dropped.
+	///
+	/// [`AudioWorkletProcessor`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletProcessor
+	pub fn dispose(&self) {
+		audio_worklet::dispose(&self.node);
+	}
+}
+
+#[cfg(any(feature = "message", docsrs))]
+impl<P: 'static + ReceiveMessage> RustAudioWorkletNode<P> {
+	/// Sends a message to the processor, where it is received by
+	/// [`ReceiveMessage::on_message()`].
+	///
+	/// # Notes
+	///
+	/// If the processor is never instantiated or garbage collected before
+	/// receiving the message,
+	/// [`MessageSend::Send`](crate::web::message::MessageSend::Send) is leaked.
+	///
+	/// # Errors
+	///
+	/// If [`MessagePort.postMessage()`] fails, e.g. if the message could not be
+	/// cloned.
+	///
+	/// # Example
+	///
+	/// ```
+	/// # #[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+	/// # wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+	/// # #[cfg_attr(all(target_feature = "atomics", not(unsupported_spawn)), wasm_bindgen_test::wasm_bindgen_test)]
+	/// # async fn test() {
+	/// use wasm_bindgen::JsCast;
+	/// use web_sys::{AudioContext, AudioWorkletGlobalScope, AudioWorkletNodeOptions, AudioWorkletProcessor};
+	/// use web_thread::web::{self, YieldTime};
+	/// use web_thread::web::audio_worklet::{AudioWorkletGlobalScopeExt, BaseAudioContextExt, ExtendAudioWorkletProcessor, ReceiveMessage, RustAudioWorkletNode};
+	/// use web_thread::web::message::SendWrapper;
+	///
+	/// struct Synth {
+	/// 	frequency: f32,
+	/// }
+	///
+	/// impl ExtendAudioWorkletProcessor for Synth {
+	/// 	type Data = ();
+	///
+	/// 	fn new(_: AudioWorkletProcessor, _: Option<Self::Data>, _: AudioWorkletNodeOptions) -> Self {
+	/// 		Self { frequency: 440. }
+	/// 	}
+	/// }
+	///
+	/// impl ReceiveMessage for Synth {
+	/// 	type Message = SendWrapper<f32>;
+	///
+	/// 	fn on_message(&mut self, SendWrapper(frequency): Self::Message) {
+	/// 		self.frequency = frequency;
+	/// 	}
+	/// }
+	///
+	/// let context = AudioContext::new().unwrap();
+	/// let (sender, receiver) = async_channel::bounded(1);
+	/// context.clone().register_thread(
+	/// 	None,
+	/// 	move || {
+	/// 		let global: AudioWorkletGlobalScope = js_sys::global().unchecked_into();
+	/// 		global.register_processor_ext::<Synth>("synth").unwrap();
+	/// 		sender.try_send(()).unwrap();
+	/// 	},
+	/// ).await.unwrap();
+	///
+	/// // Wait until processor is registered.
+	/// receiver.recv().await.unwrap();
+	/// web::yield_now_async(YieldTime::UserBlocking).await;
+	///
+	/// let node = RustAudioWorkletNode::<Synth>::new(&context, "synth", (), None).unwrap();
+	/// node.post(SendWrapper(880.)).unwrap();
+	/// # }
+	/// # #[cfg(not(all(target_feature = "atomics", not(unsupported_spawn))))]
+	/// # let _ = test();
+	/// ```
+	///
+	/// [`MessagePort.postMessage()`]: https://developer.mozilla.org/en-US/docs/Web/API/MessagePort/postMessage
+	pub fn post(&self, message: P::Message) -> Result<(), MessageError<P::Message>> {
+		audio_worklet::post_message::<P>(&self.node, message)
+	}
+}
+
+impl<P: ExtendAudioWorkletProcessor> RustAudioWorkletNode<P> {
+	/// Returns the wrapped [`AudioWorkletNode`].
+	#[cfg_attr(
+		not(all(
+			target_family = "wasm",
+			target_os = "unknown",
+			feature = "audio-worklet"
+		)),
+		doc = "",
+		doc = "[`AudioWorkletNode`]: https://docs.rs/web-sys/0.3.68/web_sys/struct.AudioWorkletNode.html"
+	)]
+	#[must_use]
+	pub const fn node(&self) -> &AudioWorkletNode {
+		&self.node
+	}
+
+	/// Converts into the wrapped [`AudioWorkletNode`].
+	#[cfg_attr(
+		not(all(
+			target_family = "wasm",
+			target_os = "unknown",
+			feature = "audio-worklet"
+		)),
+		doc = "",
+		doc = "[`AudioWorkletNode`]: https://docs.rs/web-sys/0.3.68/web_sys/struct.AudioWorkletNode.html"
+	)]
+	#[must_use]
+	#[allow(clippy::missing_const_for_fn)]
+	pub fn into_node(self) -> AudioWorkletNode {
+		self.node
+	}
+}
+
+impl<P: ExtendAudioWorkletProcessor> AsRef<AudioWorkletNode> for RustAudioWorkletNode<P> {
+	fn as_ref(&self) -> &AudioWorkletNode {
+		&self.node
+	}
+}
+
+impl<P: ExtendAudioWorkletProcessor> Deref for RustAudioWorkletNode<P> {
+	type Target = AudioWorkletNode;
+
+	fn deref(&self) -> &Self::Target {
+		&self.node
+	}
+}
+
+impl<P: ExtendAudioWorkletProcessor> From<RustAudioWorkletNode<P>> for AudioWorkletNode {
+	fn from(value: RustAudioWorkletNode<P>) -> Self {
+		value.node
+	}
+}
+
+/// Waits for the processor to fail. See
+/// [`RustAudioWorkletNode::processor_error()`].
+#[derive(Debug)]
+pub struct ProcessorErrorFuture(audio_worklet::ProcessorErrorFuture);
+
+impl Future for ProcessorErrorFuture {
+	type Output = ();
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		Pin::new(&mut self.0).poll(cx)
+	}
+}
+
+impl RefUnwindSafe for ProcessorErrorFuture {}
+
+impl UnwindSafe for ProcessorErrorFuture {}
@@ -0,0 +1,261 @@
+//! Typed description of and access to audio parameters. See
+//! [`ExtendAudioWorkletProcessor::PARAMETERS`](super::ExtendAudioWorkletProcessor::PARAMETERS)
+//! and
+//! [`ProcessBuffers::process_buffers()`](super::ProcessBuffers::process_buffers).
+
+use std::iter::Zip;
+use std::slice::Iter;
+
+/// Description of an [`AudioParam`]. Equivalent to an
+/// [`AudioParamDescriptor`] object.
+///
+/// See
+/// [`ExtendAudioWorkletProcessor::PARAMETERS`](super::ExtendAudioWorkletProcessor::PARAMETERS).
+///
+/// [`AudioParam`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioParam
+/// [`AudioParamDescriptor`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletProcessor/parameterDescriptors
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AudioParamDescriptor {
+	/// Name of the parameter.
+	name: &'static str,
+	/// Default value of the parameter.
+	default_value: f32,
+	/// Minimum value of the parameter.
+	min_value: f32,
+	/// Maximum value of the parameter.
+	max_value: f32,
+	/// Automation rate of the parameter.
+	automation_rate: AutomationRate,
+}
+
+impl AudioParamDescriptor {
+	/// Creates a new [`AudioParamDescriptor`] with the given `name`. All
+	/// other values are set to their defaults: a default value of `0`, the
+	/// full range of [`f32`] and [`AutomationRate::ARate`].
+	#[must_use]
+	pub const fn new(name: &'static str) -> Self {
+		Self {
+			name,
+			default_value: 0.,
+			min_value: f32::MIN,
+			max_value: f32::MAX,
+			automation_rate: AutomationRate::ARate,
+		}
+	}
+
+	/// Sets the default value.
+	#[must_use]
+	pub const fn with_default_value(mut self, value: f32) -> Self {
+		self.default_value = value;
+		self
+	}
+
+	/// Sets the minimum value.
+	#[must_use]
+	pub const fn with_min_value(mut self, value: f32) -> Self {
+		self.min_value = value;
+		self
+	}
+
+	/// Sets the maximum value.
+	#[must_use]
+	pub const fn with_max_value(mut self, value: f32) -> Self {
+		self.max_value = value;
+		self
+	}
+
+	/// Sets the [`AutomationRate`].
+	#[must_use]
+	pub const fn with_automation_rate(mut self, rate: AutomationRate) -> Self {
+		self.automation_rate = rate;
+		self
+	}
+
+	/// Returns the name.
+	#[must_use]
+	pub const fn name(&self) -> &'static str {
+		self.name
+	}
+
+	/// Returns the default value.
+	#[must_use]
+	pub const fn default_value(&self) -> f32 {
+		self.default_value
+	}
+
+	/// Returns the minimum value.
+	#[must_use]
+	pub const fn min_value(&self) -> f32 {
+		self.min_value
+	}
+
+	/// Returns the maximum value.
+	#[must_use]
+	pub const fn max_value(&self) -> f32 {
+		self.max_value
+	}
+
+	/// Returns the [`AutomationRate`].
+	#[must_use]
+	pub const fn automation_rate(&self) -> AutomationRate {
+		self.automation_rate
+	}
+}
+
+/// Automation rate of an [`AudioParam`]. See
+/// [`AudioParam.automationRate`].
+///
+/// [`AudioParam`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioParam
+/// [`AudioParam.automationRate`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioParam#automationrate
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum AutomationRate {
+	/// A value is provided for every frame: `a-rate`.
+	#[default]
+	ARate,
+	/// A single value is provided for the whole render quantum: `k-rate`.
+	KRate,
+}
+
+impl AutomationRate {
+	/// Returns the JS string representation.
+	#[must_use]
+	pub const fn as_str(self) -> &'static str {
+		match self {
+			Self::ARate => "a-rate",
+			Self::KRate => "k-rate",
+		}
+	}
+}
+
+/// Values of all [`AudioParam`]s of a processor for the current render
+/// quantum. See
+/// [`ProcessBuffers::process_buffers()`](super::ProcessBuffers::process_buffers).
+///
+/// Each parameter either holds a value for every frame or, if it is constant
+/// during this render quantum, a single value.
+///
+/// [`AudioParam`]: https://developer.mozilla.org/en-US/docs/Web/API/AudioParam
+#[derive(Clone, Copy, Debug)]
+pub struct AudioParameters<'parameters> {
+	/// Names of the parameters.
+	names: &'parameters [String],
+	/// Values of the parameters.
+	values: &'parameters [&'parameters [f32]],
+}
+
+impl<'parameters> AudioParameters<'parameters> {
+	/// Creates a new [`AudioParameters`].
+	#[cfg(all(
+		target_family = "wasm",
+		target_os = "unknown",
+		target_feature = "atomics",
+		feature = "audio-worklet"
+	))]
+	pub(crate) fn new(
+		names: &'parameters [String],
+		values: &'parameters [&'parameters [f32]],
+	) -> Self {
+		debug_assert_eq!(
+			names.len(),
+			values.len(),
+			"found different amount of names and values"
+		);
+
+		Self { names, values }
+	}
+
+	/// Returns the values of the parameter with the given `name`.
+	#[must_use]
+	pub fn get(&self, name: &str) -> Option<&'parameters [f32]> {
+		self.iter()
+			.find_map(|(current, values)| (current == name).then_some(values))
+	}
+
+	/// Returns the value of the parameter with the given `name` at `frame`.
+	/// Takes care of parameters holding only a single value.
+	#[must_use]
+	pub fn value(&self, name: &str, frame: usize) -> Option<f32> {
+		Self::value_at(self.get(name)?, frame)
+	}
+
+	/// Returns the value at `frame`, taking care of `values` holding only a
+	/// single value.
+	fn value_at(values: &[f32], frame: usize) -> Option<f32> {
+		match values {
+			[value] => Some(*value),
+			values => values.get(frame).copied(),
+		}
+	}
+
+	/// Returns the values of the parameter at `index`. Parameters are ordered
+	/// the same as
+	/// [`ExtendAudioWorkletProcessor::PARAMETERS`](super::ExtendAudioWorkletProcessor::PARAMETERS),
+	/// so `index` can be a Rust enum implementing [`Into<usize>`].
+	#[must_use]
+	pub fn get_by_index<I: Into<usize>>(&self, index: I) -> Option<&'parameters [f32]> {
+		self.values.get(index.into()).copied()
+	}
+
+	/// Returns the value of the parameter at `index` at `frame`. See
+	/// [`AudioParameters::get_by_index()`] and [`AudioParameters::value()`].
+	#[must_use]
+	pub fn value_by_index<I: Into<usize>>(&self, index: I, frame: usize) -> Option<f32> {
+		Self::value_at(self.get_by_index(index)?, frame)
+	}
+
+	/// Returns the amount of parameters.
+	#[must_use]
+	pub const fn len(&self) -> usize {
+		self.names.len()
+	}
+
+	/// Returns [`true`] if there are no parameters.
+	#[must_use]
+	pub const fn is_empty(&self) -> bool {
+		self.names.is_empty()
+	}
+
+	/// Returns an iterator over all parameter names and their values.
+	#[must_use]
+	pub fn iter(&self) -> AudioParametersIter<'parameters> {
+		AudioParametersIter(self.names.iter().zip(self.values.iter()))
+	}
+}
+
+impl<'parameters> IntoIterator for AudioParameters<'parameters> {
+	type Item = (&'parameters str, &'parameters [f32]);
+	type IntoIter = AudioParametersIter<'parameters>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+impl<'parameters> IntoIterator for &AudioParameters<'parameters> {
+	type Item = (&'parameters str, &'parameters [f32]);
+	type IntoIter = AudioParametersIter<'parameters>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+/// Iterator over [`AudioParameters`]. See [`AudioParameters::iter()`].
+#[derive(Clone, Debug)]
+pub struct AudioParametersIter<'parameters>(
+	Zip<Iter<'parameters, String>, Iter<'parameters, &'parameters [f32]>>,
+);
+
+impl<'parameters> Iterator for AudioParametersIter<'parameters> {
+	type Item = (&'parameters str, &'parameters [f32]);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.0.next().map(|(name, values)| (name.as_str(), *values))
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.0.size_hint()
+	}
+}
+
+impl ExactSizeIterator for AudioParametersIter<'_> {}
@@ -0,0 +1,433 @@
+//! Lock-free single-producer single-consumer ring buffer to stream data
+//! between audio worklets and other threads.
+
+use std::cell::UnsafeCell;
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::mem::MaybeUninit;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{ready, Context, Poll};
+use std::{any, iter, slice};
+
+use js_sys::Float32Array;
+
+use super::{Signal, SignalFuture};
+
+#[cfg(not(all(
+	target_family = "wasm",
+	target_os = "unknown",
+	feature = "audio-worklet"
+)))]
+mod js_sys {
+	pub(super) struct Float32Array;
+}
+
+/// Lock-free single-producer single-consumer ring buffer. Use
+/// [`RingBuffer::split()`] to get the [`RingBufferProducer`] and
+/// [`RingBufferConsumer`] halves.
+///
+/// # Notes
+///
+/// The [`RingBufferConsumer`] is wait-free and doesn't allocate, so it is safe
+/// to use from audio worklets, e.g. in
+/// [`ExtendAudioWorkletProcessor::process()`](super::ExtendAudioWorkletProcessor::process).
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+/// # wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+/// # #[cfg_attr(all(target_feature = "atomics", not(unsupported_spawn)), wasm_bindgen_test::wasm_bindgen_test)]
+/// # async fn test() {
+/// use web_thread::web::audio_worklet::RingBuffer;
+/// use web_thread::web::JoinHandleExt;
+///
+/// let (mut producer, mut consumer) = RingBuffer::<f32>::new(1024).split();
+///
+/// // Usually moved into `ExtendAudioWorkletProcessor::process()`.
+/// let mut handle = web_thread::spawn(move || {
+/// 	let mut samples = [0.; 128];
+///
+/// 	while consumer.pop_slice(&mut samples) == 0 {}
+///
+/// 	samples
+/// });
+///
+/// producer.wait_for_space(128).await;
+/// assert_eq!(producer.push_slice(&[1.; 128]), 128);
+///
+/// let samples = handle.join_async().await.unwrap();
+/// assert_eq!(samples, [1.; 128]);
+/// # }
+/// # #[cfg(not(all(target_feature = "atomics", not(unsupported_spawn))))]
+/// # let _ = test();
+/// ```
+pub struct RingBuffer<T: Copy>(Arc<Shared<T>>);
+
+/// A single storage slot of a [`RingBuffer`].
+type Slot<T> = UnsafeCell<MaybeUninit<T>>;
+
+/// State shared between [`RingBufferProducer`] and [`RingBufferConsumer`].
+struct Shared<T> {
+	/// Storage.
+	buffer: Box<[Slot<T>]>,
+	/// Read position in the range of `0..capacity * 2`.
+	read: AtomicUsize,
+	/// Write position in the range of `0..capacity * 2`.
+	write: AtomicUsize,
+	/// Notified by the [`RingBufferConsumer`] when space was freed.
+	space: Signal,
+}
+
+// SAFETY: Slots are only accessed by either the producer or the consumer,
+// synchronized through `read` and `write`.
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+impl<T> Shared<T> {
+	/// Returns the capacity.
+	fn capacity(&self) -> usize {
+		self.buffer.len()
+	}
+
+	/// Returns the amount of values between `read` and `write`.
+	fn len(&self, read: usize, write: usize) -> usize {
+		if write >= read {
+			write - read
+		} else {
+			self.capacity() * 2 - read + write
+		}
+	}
+
+	/// Advances a position by `amount`.
+	fn advance(&self, position: usize, amount: usize) -> usize {
+		let end = self.capacity() * 2;
+
+		if position >= end - amount {
+			position - (end - amount)
+		} else {
+			position + amount
+		}
+	}
+
+	/// Returns `amount` slots starting at `position`.
+	fn slots(&self, position: usize, amount: usize) -> impl Iterator<Item = &Slot<T>> {
+		let (first, second) = self.regions(position, amount);
+		first.iter().chain(second)
+	}
+
+	/// Returns `amount` slots starting at `position` as the at most two
+	/// contiguous regions they occupy.
+	fn regions(&self, position: usize, amount: usize) -> (&[Slot<T>], &[Slot<T>]) {
+		let index = if position >= self.capacity() {
+			position - self.capacity()
+		} else {
+			position
+		};
+		let (head, tail) = self.buffer.split_at(index);
+		let tail_amount = amount.min(tail.len());
+
+		(
+			tail.split_at(tail_amount).0,
+			head.split_at(amount - tail_amount).0,
+		)
+	}
+}
+
+impl<T: Copy> Debug for RingBuffer<T> {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+		formatter
+			.debug_struct("RingBuffer")
+			.field("type", &any::type_name::<T>())
+			.field("capacity", &self.0.capacity())
+			.finish()
+	}
+}
+
+impl<T: Copy> RingBuffer<T> {
+	/// Creates a new [`RingBuffer`] able to hold `capacity` values.
+	///
+	/// # Panics
+	///
+	/// If `capacity` is `0` or larger than [`isize::MAX`] `/ 2`.
+	#[must_use]
+	pub fn new(capacity: usize) -> Self {
+		assert_ne!(capacity, 0, "`capacity` must not be `0`");
+		assert!(
+			capacity <= isize::MAX.unsigned_abs() / 2,
+			"`capacity` must not be larger than `isize::MAX / 2`"
+		);
+
+		let buffer = iter::repeat_with(|| UnsafeCell::new(MaybeUninit::uninit()))
+			.take(capacity)
+			.collect();
+
+		Self(Arc::new(Shared {
+			buffer,
+			read: AtomicUsize::new(0),
+			write: AtomicUsize::new(0),
+			space: Signal::new(),
+		}))
+	}
+
+	/// Returns the amount of values this [`RingBuffer`] can hold.
+	#[must_use]
+	pub fn capacity(&self) -> usize {
+		self.0.capacity()
+	}
+
+	/// Splits this [`RingBuffer`] into its [`RingBufferProducer`] and
+	/// [`RingBufferConsumer`] halves.
+	#[must_use]
+	pub fn split(self) -> (RingBufferProducer<T>, RingBufferConsumer<T>) {
+		(
+			RingBufferProducer(Arc::clone(&self.0)),
+			RingBufferConsumer(self.0),
+		)
+	}
+}
+
+/// Writing half of a [`RingBuffer`].
+pub struct RingBufferProducer<T: Copy>(Arc<Shared<T>>);
+
+impl<T: Copy> Debug for RingBufferProducer<T> {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+		formatter
+			.debug_struct("RingBufferProducer")
+			.field("type", &any::type_name::<T>())
+			.field("capacity", &self.capacity())
+			.field("free_len", &self.free_len())
+			.finish()
+	}
+}
+
+impl<T: Copy> RingBufferProducer<T> {
+	/// Returns the amount of values the [`RingBuffer`] can hold.
+	#[must_use]
+	pub fn capacity(&self) -> usize {
+		self.0.capacity()
+	}
+
+	/// Returns the amount of values that can currently be pushed.
+	#[must_use]
+	pub fn free_len(&self) -> usize {
+		let read = self.0.read.load(Ordering::Acquire);
+		let write = self.0.write.load(Ordering::Relaxed);
+
+		self.capacity() - self.0.len(read, write)
+	}
+
+	/// Pushes a single value.
+	///
+	/// # Errors
+	///
+	/// If the [`RingBuffer`] is full, returning the passed `value`.
+	pub fn push(&mut self, value: T) -> Result<(), T> {
+		if self.push_slice(&[value]) == 1 {
+			Ok(())
+		} else {
+			Err(value)
+		}
+	}
+
+	/// Pushes as many values from `values` as there is space for. Returns the
+	/// amount of values pushed.
+	// Requiring `&mut self` prevents concurrent pushes.
+	#[allow(clippy::needless_pass_by_ref_mut)]
+	pub fn push_slice(&mut self, values: &[T]) -> usize {
+		let read = self.0.read.load(Ordering::Acquire);
+		let write = self.0.write.load(Ordering::Relaxed);
+		let amount = values.len().min(self.capacity() - self.0.len(read, write));
+
+		for (slot, value) in self.0.slots(write, amount).zip(values) {
+			// SAFETY: These slots are not readable by the consumer until `write` is
+			// advanced.
+			unsafe { slot.get().write(MaybeUninit::new(*value)) };
+		}
+
+		self.0
+			.write
+			.store(self.0.advance(write, amount), Ordering::Release);
+
+		amount
+	}
+
+	/// Waits until at least `len` values can be pushed. Useful on threads not
+	/// supporting blocking, e.g. the main thread.
+	///
+	/// # Panics
+	///
+	/// If `len` is larger than the capacity of the [`RingBuffer`].
+	#[must_use]
+	pub fn wait_for_space(&self, len: usize) -> WaitForSpaceFuture<'_, T> {
+		assert!(
+			len <= self.capacity(),
+			"`len` must not be larger than the capacity"
+		);
+
+		WaitForSpaceFuture {
+			producer: self,
+			len,
+			wait: None,
+		}
+	}
+}
+
+/// Reading half of a [`RingBuffer`]. All methods are wait-free and don't
+/// allocate.
+pub struct RingBufferConsumer<T: Copy>(Arc<Shared<T>>);
+
+impl<T: Copy> Debug for RingBufferConsumer<T> {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+		formatter
+			.debug_struct("RingBufferConsumer")
+			.field("type", &any::type_name::<T>())
+			.field("capacity", &self.capacity())
+			.field("len", &self.len())
+			.finish()
+	}
+}
+
+impl<T: Copy> RingBufferConsumer<T> {
+	/// Returns the amount of values the [`RingBuffer`] can hold.
+	#[must_use]
+	pub fn capacity(&self) -> usize {
+		self.0.capacity()
+	}
+
+	/// Returns the amount of values that can currently be popped.
+	#[must_use]
+	pub fn len(&self) -> usize {
+		let read = self.0.read.load(Ordering::Relaxed);
+		let write = self.0.write.load(Ordering::Acquire);
+
+		self.0.len(read, write)
+	}
+
+	/// Returns [`true`] if there are no values to pop.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Pops a single value. Returns [`None`] if the [`RingBuffer`] is empty.
+	pub fn pop(&mut self) -> Option<T> {
+		let mut result = None;
+		self.pop_with(1, |_, values| result = values.first().copied());
+
+		result
+	}
+
+	/// Pops as many values into `values` as available. Returns the amount of
+	/// values popped.
+	pub fn pop_slice(&mut self, values: &mut [T]) -> usize {
+		let amount = values.len();
+
+		self.pop_with(amount, |offset, region| {
+			values
+				.split_at_mut(offset)
+				.1
+				.split_at_mut(region.len())
+				.0
+				.copy_from_slice(region);
+		})
+	}
+
+	/// Pops up to `amount` values and passes the at most two contiguous
+	/// regions they occupy to `task`, together with the offset of each region
+	/// in the popped values. Returns the amount of values popped.
+	// Requiring `&mut self` prevents concurrent pops.
+	#[allow(clippy::needless_pass_by_ref_mut)]
+	fn pop_with<F: FnMut(usize, &[T])>(&mut self, amount: usize, mut task: F) -> usize {
+		let read = self.0.read.load(Ordering::Relaxed);
+		let write = self.0.write.load(Ordering::Acquire);
+		let amount = amount.min(self.0.len(read, write));
+
+		if amount == 0 {
+			return 0;
+		}
+
+		let (first, second) = self.0.regions(read, amount);
+
+		for (offset, region) in [(0, first), (first.len(), second)] {
+			if !region.is_empty() {
+				// SAFETY: `Slot<T>` has the same layout as `T`. These slots were initialized
+				// by the producer before `write` was advanced and are not writable by the
+				// producer until `read` is advanced.
+				let region =
+					unsafe { slice::from_raw_parts(region.as_ptr().cast::<T>(), region.len()) };
+				task(offset, region);
+			}
+		}
+
+		self.0
+			.read
+			.store(self.0.advance(read, amount), Ordering::Release);
+		self.0.space.notify();
+
+		amount
+	}
+}
+
+impl RingBufferConsumer<f32> {
+	/// Pops as many values into `array` as available. Returns the amount of
+	/// values popped.
+	///
+	/// # Notes
+	///
+	/// The values are copied one by one with [`Float32Array::set_index()`],
+	/// which doesn't create any JS objects, so this doesn't allocate.
+	#[allow(clippy::missing_panics_doc)]
+	pub fn pop_float32_array(&mut self, array: &Float32Array) -> usize {
+		let length = array.length().try_into().expect("found 64-bit Wasm");
+
+		self.pop_with(length, |offset, region| {
+			for (index, value) in (offset..).zip(region) {
+				let index = u32::try_from(index).expect("found 64-bit Wasm");
+				array.set_index(index, *value);
+			}
+		})
+	}
+}
+
+/// Waits until there is enough space in the [`RingBuffer`]. See
+/// [`RingBufferProducer::wait_for_space()`].
+pub struct WaitForSpaceFuture<'producer, T: Copy> {
+	/// The [`RingBufferProducer`] to wait on.
+	producer: &'producer RingBufferProducer<T>,
+	/// Required amount of space.
+	len: usize,
+	/// Currently running wait.
+	wait: Option<SignalFuture<'producer>>,
+}
+
+impl<T: Copy> Debug for WaitForSpaceFuture<'_, T> {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+		formatter
+			.debug_struct("WaitForSpaceFuture")
+			.field("producer", &self.producer)
+			.field("len", &self.len)
+			.field("wait", &self.wait)
+			.finish()
+	}
+}
+
+impl<T: Copy> Future for WaitForSpaceFuture<'_, T> {
+	type Output = ();
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		loop {
+			if let Some(wait) = &mut self.wait {
+				ready!(Pin::new(wait).poll(cx));
+				self.wait = None;
+			}
+
+			if self.producer.free_len() >= self.len {
+				return Poll::Ready(());
+			}
+
+			self.wait = Some(self.producer.0.space.wait_async());
+		}
+	}
+}
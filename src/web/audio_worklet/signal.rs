@@ -0,0 +1,133 @@
+//! Wait-free signalling between audio worklets and other threads.
+
+use std::future::Future;
+use std::panic::{RefUnwindSafe, UnwindSafe};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+#[cfg(all(
+	target_family = "wasm",
+	target_os = "unknown",
+	feature = "audio-worklet"
+))]
+use crate::thread::audio_worklet;
+
+#[cfg(not(all(
+	target_family = "wasm",
+	target_os = "unknown",
+	feature = "audio-worklet"
+)))]
+mod audio_worklet {
+	pub(super) struct Signal;
+	pub(super) struct SignalFuture<'signal>(&'signal ());
+}
+
+/// A flag and counter that can be notified from an audio worklet without
+/// blocking or allocating, e.g. from
+/// [`ExtendAudioWorkletProcessor::process()`](super::ExtendAudioWorkletProcessor::process).
+///
+/// # Notes
+///
+/// Every call to [`Signal::notify()`] sets the flag and increments the counter.
+/// Waiting with [`Signal::wait()`] or [`Signal::wait_async()`] resets the flag
+/// when it returns, so each notification is only observed by a single waiter.
+/// Multiple notifications before a waiter has observed the flag are collapsed,
+/// use [`Signal::count()`] to detect them.
+///
+/// Waiting is not supported in audio worklets.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+/// # wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+/// # #[cfg_attr(all(target_feature = "atomics", not(unsupported_spawn)), wasm_bindgen_test::wasm_bindgen_test)]
+/// # async fn test() {
+/// use web_thread::web::audio_worklet::Signal;
+///
+/// static REFILL: Signal = Signal::new();
+///
+/// // Usually called from `ExtendAudioWorkletProcessor::process()`.
+/// web_thread::spawn(|| REFILL.notify());
+///
+/// REFILL.wait_async().await;
+/// // Refill buffer.
+/// # }
+/// # #[cfg(not(all(target_feature = "atomics", not(unsupported_spawn))))]
+/// # let _ = test();
+/// ```
+#[derive(Debug)]
+pub struct Signal(audio_worklet::Signal);
+
+impl Default for Signal {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Signal {
+	/// Creates a new [`Signal`].
+	#[must_use]
+	pub const fn new() -> Self {
+		Self(audio_worklet::Signal::new())
+	}
+
+	/// Sets the flag, increments the counter and wakes up all waiters.
+	///
+	/// This is wait-free and doesn't allocate, so it is safe to call from
+	/// audio worklets.
+	pub fn notify(&self) {
+		self.0.notify();
+	}
+
+	/// Returns [`true`] if the flag is set.
+	#[must_use]
+	pub fn is_set(&self) -> bool {
+		self.0.is_set()
+	}
+
+	/// Resets the flag. Returns [`true`] if the flag was set.
+	#[allow(clippy::must_use_candidate)]
+	pub fn reset(&self) -> bool {
+		self.0.reset()
+	}
+
+	/// Returns the amount of times [`Signal::notify()`] was called. Wraps
+	/// around on overflow.
+	#[must_use]
+	pub fn count(&self) -> u32 {
+		self.0.count()
+	}
+
+	/// Blocks until the flag is set and then resets it.
+	///
+	/// # Panics
+	///
+	/// This call will panic if the calling thread doesn't support blocking, see
+	/// [`web::has_block_support()`](crate::web::has_block_support).
+	pub fn wait(&self) {
+		self.0.wait();
+	}
+
+	/// Waits asynchronously until the flag is set and then resets it. Useful on
+	/// threads not supporting blocking, e.g. the main thread.
+	pub const fn wait_async(&self) -> SignalFuture<'_> {
+		SignalFuture(self.0.wait_async())
+	}
+}
+
+/// Waits for a [`Signal`] to be notified. See [`Signal::wait_async()`].
+#[derive(Debug)]
+pub struct SignalFuture<'signal>(audio_worklet::SignalFuture<'signal>);
+
+impl Future for SignalFuture<'_> {
+	type Output = ();
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		Pin::new(&mut self.0).poll(cx)
+	}
+}
+
+impl RefUnwindSafe for SignalFuture<'_> {}
+
+impl UnwindSafe for SignalFuture<'_> {}
@@ -4,15 +4,23 @@
 //! [`Serializable`]: https://developer.mozilla.org/en-US/docs/Glossary/Serializable_object
 //! [`Transferable`]: https://developer.mozilla.org/en-US/docs/Web/API/Web_Workers_API/Transferable_objects
 
-use std::{array, iter, mem};
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::{BuildHasher, Hash};
+use std::sync::{mpsc, Arc, Mutex};
+use std::task::{Context, Poll};
+use std::{any, array, error, future, io, iter, mem};
 
 use js_sys::Array;
 #[cfg(all(target_family = "wasm", target_os = "unknown"))]
 use js_sys::{
 	ArrayBuffer, BigInt, BigInt64Array, BigUint64Array, Boolean, DataView, Date, Error,
 	Float32Array, Float64Array, Int16Array, Int32Array, Int8Array, JsString, Map, Number, RegExp,
-	Set, Uint16Array, Uint32Array, Uint8Array, Uint8ClampedArray,
+	Set, SharedArrayBuffer, Uint16Array, Uint32Array, Uint8Array, Uint8ClampedArray,
+	WebAssembly::{Memory, Module},
 };
+#[cfg(all(target_family = "wasm", target_os = "unknown", feature = "serde"))]
+use serde::{de::DeserializeOwned, Serialize};
 use wasm_bindgen::{JsCast, JsValue};
 #[cfg(all(
 	target_family = "wasm",
@@ -20,14 +28,23 @@ use wasm_bindgen::{JsCast, JsValue};
 	feature = "message",
 	web_sys_unstable_apis
 ))]
-use web_sys::{AudioData, GpuCompilationInfo, GpuCompilationMessage, VideoFrame};
+use web_sys::{
+	AudioData, GpuCompilationInfo, GpuCompilationMessage, RtcEncodedAudioFrame,
+	RtcEncodedVideoFrame, VideoFrame,
+};
 #[cfg(all(target_family = "wasm", target_os = "unknown", feature = "message"))]
 use web_sys::{
 	Blob, CryptoKey, DomException, DomMatrix, DomMatrixReadOnly, DomPoint, DomPointReadOnly,
 	DomQuad, DomRect, DomRectReadOnly, File, FileList, FileSystemDirectoryHandle,
-	FileSystemFileHandle, FileSystemHandle, ImageBitmap, ImageData, MessagePort, OffscreenCanvas,
-	ReadableStream, RtcCertificate, RtcDataChannel, TransformStream, WritableStream,
+	FileSystemFileHandle, FileSystemHandle, ImageBitmap, ImageData, MediaStreamTrack, MessagePort,
+	OffscreenCanvas, ReadableStream, RtcCertificate, RtcDataChannel, TransformStream,
+	WritableStream,
 };
+#[cfg(all(target_family = "wasm", target_os = "unknown", feature = "derive"))]
+pub use web_thread_macro::MessageSend;
+
+#[cfg(all(target_family = "wasm", target_os = "unknown"))]
+use crate::thread;
 
 #[cfg(not(all(target_family = "wasm", target_os = "unknown")))]
 mod js_sys {
@@ -40,6 +57,14 @@ mod wasm_bindgen {
 	pub(super) trait JsCast {}
 }
 
+#[cfg(not(all(target_family = "wasm", target_os = "unknown")))]
+mod thread {
+	pub(super) mod port {
+		pub(crate) struct PortSender<M>(M);
+		pub(crate) struct PortReceiver<M>(M);
+	}
+}
+
 /// Implement a trait for a tuple.
 macro_rules! impl_for_tuple {
 	($_0:literal, $trait:ident, $($generic:ident => $_1:tt),+) => {
@@ -139,9 +164,73 @@ macro_rules! impl_for_tuples {
 /// # let _ = test();
 /// ```
 ///
+/// # Deriving
+///
+/// With the `derive` crate feature, structs and enums can use
+/// [`derive(MessageSend)`]. Fields are annotated with how they should be sent:
+/// - `#[message(serialize)]` with [`SerializableWrapper`].
+/// - `#[message(transfer)]` with [`TransferableWrapper`].
+/// - `#[message(send)]` with [`SendWrapper`].
+///
+/// Fields without an annotation have to implement [`MessageSend`] themselves.
+/// If [`web-thread`](crate) is not reachable under `::web_thread`, e.g. when
+/// re-exported by another crate, its path can be set with
+/// `#[message(crate = path::to::web_thread)]` on the struct or enum.
+#[cfg_attr(feature = "derive", doc = "", doc = "```")]
+#[cfg_attr(not(feature = "derive"), doc = "", doc = "```ignore")]
+/// # #[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+/// # wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+/// # #[cfg_attr(all(target_feature = "atomics", not(unsupported_spawn)), wasm_bindgen_test::wasm_bindgen_test)]
+/// # async fn test() {
+/// use js_sys::ArrayBuffer;
+/// use wasm_bindgen::JsCast;
+/// use web_sys::{HtmlCanvasElement, OffscreenCanvas};
+/// use web_thread::web::{self, JoinHandleExt};
+/// use web_thread::web::message::MessageSend;
+///
+/// #[derive(MessageSend)]
+/// struct Struct {
+/// 	#[message(send)]
+/// 	a: u8,
+/// 	#[message(serialize)]
+/// 	b: ArrayBuffer,
+/// 	#[message(transfer)]
+/// 	c: OffscreenCanvas,
+/// }
+///
+/// # let canvas = web_sys::window().unwrap().document().unwrap().create_element("canvas").unwrap().unchecked_into();
+/// let canvas: HtmlCanvasElement = canvas;
+/// let message = Struct {
+/// 	a: 42,
+/// 	b: ArrayBuffer::new(1000),
+/// 	c: canvas.transfer_control_to_offscreen().unwrap(),
+/// };
+/// web::spawn_with_message(
+/// 	|message| async move {
+/// 		// Do work.
+/// #       let _ = message;
+/// 	},
+/// 	message,
+/// )
+/// .join_async()
+/// .await
+/// .unwrap();
+/// # }
+/// # #[cfg(not(all(target_feature = "atomics", not(unsupported_spawn))))]
+/// # let _ = test();
+/// ```
+/// 
 /// [`Serializable`]: https://developer.mozilla.org/en-US/docs/Glossary/Serializable_object
 /// [`Transferable`]: https://developer.mozilla.org/en-US/docs/Web/API/Web_Workers_API/Transferable_objects
 /// [transferred]: https://developer.mozilla.org/en-US/docs/Web/API/Web_Workers_API/Transferable_objects
+#[cfg_attr(
+	all(target_family = "wasm", target_os = "unknown", feature = "derive"),
+	doc = "[`derive(MessageSend)`]: macro@MessageSend"
+)]
+#[cfg_attr(
+	not(all(target_family = "wasm", target_os = "unknown", feature = "derive")),
+	doc = "[`derive(MessageSend)`]: https://docs.rs/web-thread-macro"
+)]
 #[cfg_attr(
 	not(all(target_family = "wasm", target_os = "unknown", feature = "message")),
 	doc = "[`JsValue::UNDEFINED`]: https://docs.rs/wasm-bindgen/0.2.92/wasm_bindgen/struct.JsValue.html#associatedconstant.UNDEFINED"
@@ -298,7 +387,239 @@ macro_rules! message_send_for_tuple {
 
 impl_for_tuples!(message_send_for_tuple, MessageSend);
 
+impl<T: MessageSend> MessageSend for Vec<T> {
+	type Send = (usize, Vec<Option<T::Send>>);
+
+	fn send<E: Extend<JsValue>>(self, transfer: &mut E) -> RawMessage<Self::Send> {
+		let len = self.len();
+		let mut serialize_builder = SequenceBuilder::new();
+		// Only allocate when encountering the first `Send` value.
+		let mut send = Vec::new();
+
+		for (index, message) in self.into_iter().enumerate() {
+			if let Some(value) = serialize_builder.push(message, transfer) {
+				if send.is_empty() {
+					send.reserve_exact(len);
+					send.extend(iter::repeat_with(|| None).take(index));
+				}
+
+				send.push(Some(value));
+			} else if !send.is_empty() {
+				send.push(None);
+			}
+		}
+
+		RawMessage {
+			serialize: serialize_builder.finish(),
+			send: Some((len, send)),
+		}
+	}
+
+	fn receive(serialized: Option<JsValue>, sent: Option<Self::Send>) -> Self {
+		let (len, sent) = sent.expect("expected `Send` value");
+		let serialized = serialized.map(Array::unchecked_from_js);
+
+		if let Some(serialized) = &serialized {
+			debug_assert_eq!(
+				serialized.length(),
+				usize_is_u32(len),
+				"unexpected array size during message receival"
+			);
+		}
+
+		let mut sent = sent.into_iter();
+
+		(0..len)
+			.map(|index| {
+				let serialized = serialized
+					.as_ref()
+					.map(|serialized| serialized.get(usize_is_u32(index)))
+					.filter(|value| !value.is_null());
+				T::receive(serialized, sent.next().flatten())
+			})
+			.collect()
+	}
+}
+
+impl<T: MessageSend> MessageSend for Option<T> {
+	type Send = Option<T::Send>;
+
+	fn send<E: Extend<JsValue>>(self, transfer: &mut E) -> RawMessage<Self::Send> {
+		if let Some(message) = self {
+			let RawMessage { serialize, send } = message.send(transfer);
+
+			RawMessage {
+				serialize,
+				send: Some(send),
+			}
+		} else {
+			RawMessage {
+				serialize: None,
+				send: None,
+			}
+		}
+	}
+
+	fn receive(serialized: Option<JsValue>, sent: Option<Self::Send>) -> Self {
+		if let Some(sent) = sent {
+			Some(T::receive(serialized, sent))
+		} else {
+			debug_assert_eq!(serialized, None, "unexpected serialized `JsValue`");
+			None
+		}
+	}
+}
+
+impl<T: MessageSend> MessageSend for Box<T> {
+	type Send = T::Send;
+
+	fn send<E: Extend<JsValue>>(self, transfer: &mut E) -> RawMessage<Self::Send> {
+		(*self).send(transfer)
+	}
+
+	fn receive(serialized: Option<JsValue>, sent: Option<Self::Send>) -> Self {
+		Self::new(T::receive(serialized, sent))
+	}
+}
+
+impl<T: MessageSend, E: MessageSend> MessageSend for Result<T, E> {
+	type Send = Result<Option<T::Send>, Option<E::Send>>;
+
+	fn send<X: Extend<JsValue>>(self, transfer: &mut X) -> RawMessage<Self::Send> {
+		match self {
+			Ok(message) => {
+				let RawMessage { serialize, send } = message.send(transfer);
+
+				RawMessage {
+					serialize,
+					send: Some(Ok(send)),
+				}
+			}
+			Err(message) => {
+				let RawMessage { serialize, send } = message.send(transfer);
+
+				RawMessage {
+					serialize,
+					send: Some(Err(send)),
+				}
+			}
+		}
+	}
+
+	fn receive(serialized: Option<JsValue>, sent: Option<Self::Send>) -> Self {
+		match sent.expect("expected `Send` value") {
+			Ok(sent) => Ok(T::receive(serialized, sent)),
+			Err(sent) => Err(E::receive(serialized, sent)),
+		}
+	}
+}
+
+impl<K, V, S> MessageSend for HashMap<K, V, S>
+where
+	K: Eq + Hash + Send,
+	V: MessageSend,
+	S: BuildHasher + Default,
+{
+	type Send = Vec<(K, Option<V::Send>)>;
+
+	fn send<E: Extend<JsValue>>(self, transfer: &mut E) -> RawMessage<Self::Send> {
+		let mut serialize_builder = SequenceBuilder::new();
+		let mut send = Vec::with_capacity(self.len());
+
+		for (key, message) in self {
+			send.push((key, serialize_builder.push(message, transfer)));
+		}
+
+		RawMessage {
+			serialize: serialize_builder.finish(),
+			send: Some(send),
+		}
+	}
+
+	fn receive(serialized: Option<JsValue>, sent: Option<Self::Send>) -> Self {
+		let sent = sent.expect("expected `Send` value");
+		let serialized = serialized.map(Array::unchecked_from_js);
+
+		if let Some(serialized) = &serialized {
+			debug_assert_eq!(
+				serialized.length(),
+				usize_is_u32(sent.len()),
+				"unexpected array size during message receival"
+			);
+		}
+
+		let mut map = Self::with_capacity_and_hasher(sent.len(), S::default());
+
+		for (index, (key, sent)) in sent.into_iter().enumerate() {
+			let serialized = serialized
+				.as_ref()
+				.map(|serialized| serialized.get(usize_is_u32(index)))
+				.filter(|value| !value.is_null());
+			map.insert(key, V::receive(serialized, sent));
+		}
+
+		map
+	}
+}
+
+/// Collects the serialized parts of a sequence of [`MessageSend`]s into an
+/// [`Array`], only allocating it when encountering the first serialized value.
+struct SequenceBuilder {
+	/// The [`ArrayBuilder`] if any values were serialized.
+	builder: Option<ArrayBuilder>,
+	/// Amount of leading values without anything to serialize.
+	empty_count: usize,
+}
+
+impl SequenceBuilder {
+	/// Creates a new [`SequenceBuilder`].
+	const fn new() -> Self {
+		Self {
+			builder: None,
+			empty_count: 0,
+		}
+	}
+
+	/// Sends a [`MessageSend`] and stores its serialized value. Returns its
+	/// [`RawMessage::send`].
+	fn push<T: MessageSend, E: Extend<JsValue>>(
+		&mut self,
+		message: T,
+		transfer: &mut E,
+	) -> Option<T::Send> {
+		let message = message.send(transfer);
+
+		if let Some(serialize) = message.serialize {
+			let empty_count = self.empty_count;
+			self.builder
+				.get_or_insert_with(|| {
+					let mut builder = ArrayBuilder::new();
+					builder.extend(iter::repeat(JsValue::NULL).take(empty_count));
+					builder
+				})
+				.push(serialize);
+		} else if let Some(builder) = &mut self.builder {
+			builder.push(JsValue::NULL);
+		} else {
+			self.empty_count += 1;
+		}
+
+		message.send
+	}
+
+	/// Finish building the [`Array`]. Returns [`None`] if no values were
+	/// serialized.
+	fn finish(self) -> Option<JsValue> {
+		self.builder
+			.and_then(ArrayBuilder::finish)
+			.map(Array::unchecked_into)
+	}
+}
+
 /// Value can be [serialized](https://developer.mozilla.org/en-US/docs/Glossary/Serializable_object).
+///
+/// [`WebAssembly.Memory`](js_sys::WebAssembly::Memory) can only be serialized
+/// if it is [shared](https://developer.mozilla.org/en-US/docs/WebAssembly/JavaScript_interface/Memory/Memory#shared).
 pub trait Serializable {}
 
 impl<const SIZE: usize, T: Serializable> Serializable for [T; SIZE] {}
@@ -328,9 +649,15 @@ impl Serializable for Error {}
 #[cfg(all(target_family = "wasm", target_os = "unknown"))]
 impl Serializable for Map {}
 #[cfg(all(target_family = "wasm", target_os = "unknown"))]
+impl Serializable for Memory {}
+#[cfg(all(target_family = "wasm", target_os = "unknown"))]
+impl Serializable for Module {}
+#[cfg(all(target_family = "wasm", target_os = "unknown"))]
 impl Serializable for RegExp {}
 #[cfg(all(target_family = "wasm", target_os = "unknown"))]
 impl Serializable for Set {}
+#[cfg(all(target_family = "wasm", target_os = "unknown"))]
+impl Serializable for SharedArrayBuffer {}
 
 // `TypedArray`
 #[cfg(all(target_family = "wasm", target_os = "unknown"))]
@@ -424,6 +751,22 @@ impl Serializable for RtcCertificate {}
 	web_sys_unstable_apis
 ))]
 #[cfg_attr(docsrs, doc(cfg(web_sys_unstable_apis)))]
+impl Serializable for RtcEncodedAudioFrame {}
+#[cfg(all(
+	target_family = "wasm",
+	target_os = "unknown",
+	feature = "message",
+	web_sys_unstable_apis
+))]
+#[cfg_attr(docsrs, doc(cfg(web_sys_unstable_apis)))]
+impl Serializable for RtcEncodedVideoFrame {}
+#[cfg(all(
+	target_family = "wasm",
+	target_os = "unknown",
+	feature = "message",
+	web_sys_unstable_apis
+))]
+#[cfg_attr(docsrs, doc(cfg(web_sys_unstable_apis)))]
 impl Serializable for VideoFrame {}
 
 /// Wrapper that implements [`MessageSend`] for values implementing
@@ -487,6 +830,94 @@ impl<T: Into<JsValue> + JsCast + Serializable> MessageSend for SerializableWrapp
 	}
 }
 
+/// Wrapper that implements [`MessageSend`] for values implementing
+/// [`Serialize`] and [`DeserializeOwned`].
+///
+/// The value is converted to a [`JsValue`] that can be [serialized], e.g.
+/// structs to objects, sequences to arrays and bytes to [`Uint8Array`]s. This
+/// works with threads that don't share memory and with plain JS consumers.
+///
+/// # Panics
+///
+/// - When sending, if the value was unable to be converted to a [`JsValue`].
+/// - When receiving, if the [`JsValue`] was unable to be converted back to the
+///   value.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+/// # wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+/// # #[cfg_attr(all(target_feature = "atomics", not(unsupported_spawn)), wasm_bindgen_test::wasm_bindgen_test)]
+/// # async fn test() {
+/// use serde::{Deserialize, Serialize};
+/// use web_thread::web::{self, JoinHandleExt};
+/// use web_thread::web::message::SerdeWrapper;
+///
+/// #[derive(Deserialize, Serialize)]
+/// struct Message {
+/// 	name: String,
+/// 	values: Vec<u32>,
+/// }
+///
+/// let message = SerdeWrapper(Message {
+/// 	name: String::from("test"),
+/// 	values: vec![1, 2, 3],
+/// });
+/// web::spawn_with_message(
+/// 	|SerdeWrapper(message)| async move {
+/// 		// Do work.
+/// #   	let _ = message;
+/// 	},
+/// 	message,
+/// )
+/// .join_async()
+/// .await
+/// .unwrap();
+/// # }
+/// # #[cfg(not(all(target_feature = "atomics", not(unsupported_spawn))))]
+/// # let _ = test();
+/// ```
+///
+/// [serialized]: https://developer.mozilla.org/en-US/docs/Glossary/Serializable_object
+#[cfg(all(target_family = "wasm", target_os = "unknown", feature = "serde"))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SerdeWrapper<T>(pub T)
+where
+	T: Serialize + DeserializeOwned;
+
+#[cfg(all(target_family = "wasm", target_os = "unknown", feature = "serde"))]
+impl<T: Serialize + DeserializeOwned> From<T> for SerdeWrapper<T> {
+	fn from(value: T) -> Self {
+		Self(value)
+	}
+}
+
+#[cfg(all(target_family = "wasm", target_os = "unknown", feature = "serde"))]
+impl<T: Serialize + DeserializeOwned> MessageSend for SerdeWrapper<T> {
+	type Send = ();
+
+	fn send<E: Extend<JsValue>>(self, _: &mut E) -> RawMessage<Self::Send> {
+		RawMessage {
+			serialize: Some(
+				serde_wasm_bindgen::to_value(&self.0)
+					.expect("failed to convert value to `JsValue`"),
+			),
+			send: None,
+		}
+	}
+
+	fn receive(serialized: Option<JsValue>, sent: Option<Self::Send>) -> Self {
+		debug_assert_eq!(sent, None, "unexpected `Send` value");
+
+		Self(
+			// E.g. `None` or `()` serialize to `undefined`, which arrives as `None`.
+			serde_wasm_bindgen::from_value(serialized.unwrap_or(JsValue::UNDEFINED))
+				.expect("failed to convert `JsValue` to value"),
+		)
+	}
+}
+
 /// Value can be [transferred](https://developer.mozilla.org/en-US/docs/Web/API/Web_Workers_API/Transferable_objects).
 pub trait Transferable {}
 
@@ -506,6 +937,8 @@ impl Transferable for AudioData {}
 #[cfg(all(target_family = "wasm", target_os = "unknown", feature = "message"))]
 impl Transferable for ImageBitmap {}
 #[cfg(all(target_family = "wasm", target_os = "unknown", feature = "message"))]
+impl Transferable for MediaStreamTrack {}
+#[cfg(all(target_family = "wasm", target_os = "unknown", feature = "message"))]
 impl Transferable for MessagePort {}
 #[cfg(all(target_family = "wasm", target_os = "unknown", feature = "message"))]
 impl Transferable for OffscreenCanvas {}
@@ -566,6 +999,126 @@ impl<T: Into<JsValue> + JsCast + Transferable> MessageSend for TransferableWrapp
 	}
 }
 
+/// Owned bytes that are [transferred] as an [`ArrayBuffer`].
+///
+/// Bytes in Wasm memory can't be transferred, so they are copied once into a
+/// new [`ArrayBuffer`] with [`TransferableBytes::copy_from_slice()`]. Sending
+/// and receiving [`TransferableBytes`] doesn't copy them again. On the
+/// receiving side they can be handed to JS APIs without copying with
+/// [`TransferableBytes::into_array_buffer()`] or
+/// [`TransferableBytes::into_uint8_array()`], or copied back into Wasm memory
+/// with [`TransferableBytes::to_vec()`].
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+/// # wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+/// # #[cfg_attr(all(target_feature = "atomics", not(unsupported_spawn)), wasm_bindgen_test::wasm_bindgen_test)]
+/// # async fn test() {
+/// use web_thread::web::{self, JoinHandleExt};
+/// use web_thread::web::message::TransferableBytes;
+///
+/// let bytes = vec![42; 1000];
+/// let message = TransferableBytes::copy_from_slice(&bytes);
+/// web::spawn_with_message(
+/// 	|message| async move {
+/// 		let array = message.into_uint8_array();
+/// 		// Do work.
+/// #   	let _ = array;
+/// 	},
+/// 	message,
+/// )
+/// .join_async()
+/// .await
+/// .unwrap();
+/// # }
+/// # #[cfg(not(all(target_feature = "atomics", not(unsupported_spawn))))]
+/// # let _ = test();
+/// ```
+///
+/// [transferred]: https://developer.mozilla.org/en-US/docs/Web/API/Web_Workers_API/Transferable_objects
+#[cfg(all(target_family = "wasm", target_os = "unknown"))]
+#[derive(Debug)]
+pub struct TransferableBytes(ArrayBuffer);
+
+#[cfg(all(target_family = "wasm", target_os = "unknown"))]
+impl TransferableBytes {
+	/// Copies `bytes` into a new [`ArrayBuffer`].
+	#[must_use]
+	pub fn copy_from_slice(bytes: &[u8]) -> Self {
+		let array = Uint8Array::new_with_length(usize_is_u32(bytes.len()));
+		array.copy_from(bytes);
+
+		Self(array.buffer())
+	}
+
+	/// Returns the number of bytes.
+	///
+	/// # Notes
+	///
+	/// This returns `0` if the [`ArrayBuffer`] was detached, e.g. by
+	/// transferring it.
+	#[must_use]
+	#[allow(clippy::as_conversions)]
+	pub fn len(&self) -> usize {
+		self.0.byte_length() as usize
+	}
+
+	/// Returns [`true`] if there are no bytes.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Copies the bytes into a new [`Vec`].
+	#[must_use]
+	pub fn to_vec(&self) -> Vec<u8> {
+		Uint8Array::new(&self.0).to_vec()
+	}
+
+	/// Returns the underlying [`ArrayBuffer`] without copying.
+	#[must_use]
+	pub fn into_array_buffer(self) -> ArrayBuffer {
+		self.0
+	}
+
+	/// Returns a [`Uint8Array`] over the underlying [`ArrayBuffer`] without
+	/// copying.
+	#[must_use]
+	pub fn into_uint8_array(self) -> Uint8Array {
+		Uint8Array::new(&self.0)
+	}
+}
+
+#[cfg(all(target_family = "wasm", target_os = "unknown"))]
+impl From<ArrayBuffer> for TransferableBytes {
+	fn from(value: ArrayBuffer) -> Self {
+		Self(value)
+	}
+}
+
+#[cfg(all(target_family = "wasm", target_os = "unknown"))]
+impl From<TransferableBytes> for ArrayBuffer {
+	fn from(value: TransferableBytes) -> Self {
+		value.0
+	}
+}
+
+#[cfg(all(target_family = "wasm", target_os = "unknown"))]
+impl MessageSend for TransferableBytes {
+	type Send = ();
+
+	fn send<E: Extend<JsValue>>(self, transfer: &mut E) -> RawMessage<Self::Send> {
+		TransferableWrapper(self.0).send(transfer)
+	}
+
+	fn receive(serialized: Option<JsValue>, sent: Option<Self::Send>) -> Self {
+		let TransferableWrapper(buffer) = TransferableWrapper::receive(serialized, sent);
+		Self(buffer)
+	}
+}
+
 /// Wrapper that implements [`MessageSend`] for values implementing [`Send`].
 ///
 /// # Example
@@ -628,6 +1181,274 @@ impl<T: Send> MessageSend for SendWrapper<T> {
 	}
 }
 
+/// Waits for the next message of type `M` posted to this thread with
+/// [`JoinHandleExt::post_message()`](super::JoinHandleExt::post_message).
+///
+/// Messages of other types stay queued until [`receive()`] is called with
+/// their type.
+///
+/// # Panics
+///
+/// If called from a thread not spawned by [`web-thread`](crate), e.g. the
+/// main thread or an audio worklet.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+/// # wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+/// # #[cfg_attr(all(target_feature = "atomics", not(unsupported_spawn)), wasm_bindgen_test::wasm_bindgen_test)]
+/// # async fn test() {
+/// use js_sys::ArrayBuffer;
+/// use web_thread::web::message::{self, TransferableWrapper};
+/// use web_thread::web::{self, JoinHandleExt};
+///
+/// let mut handle = web::spawn_async(|| async {
+/// 	let TransferableWrapper(buffer) =
+/// 		message::receive::<TransferableWrapper<ArrayBuffer>>().await;
+/// 	buffer.byte_length()
+/// });
+///
+/// handle
+/// 	.post_message(TransferableWrapper(ArrayBuffer::new(1000)))
+/// 	.unwrap();
+/// assert_eq!(handle.join_async().await.unwrap(), 1000);
+/// # }
+/// # #[cfg(not(all(target_feature = "atomics", not(unsupported_spawn))))]
+/// # let _ = test();
+/// ```
+pub async fn receive<M>() -> M
+where
+	M: 'static + MessageSend,
+{
+	future::poll_fn(|cx| thread::poll_message(cx)).await
+}
+
+/// Creates a new channel to send [`MessageSend`] values to threads that are
+/// already running.
+///
+/// Both halves implement [`MessageSend`] themselves, so they can be sent to
+/// other threads, e.g. with [`spawn_with_message()`](super::spawn_with_message)
+/// or through another channel.
+///
+/// # Panics
+///
+/// If called from an audio worklet, which can't create a [`MessageChannel`].
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+/// # wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+/// # #[cfg_attr(all(target_feature = "atomics", not(unsupported_spawn)), wasm_bindgen_test::wasm_bindgen_test)]
+/// # async fn test() {
+/// use js_sys::ArrayBuffer;
+/// use web_thread::web::message::{self, TransferableWrapper};
+/// use web_thread::web::{self, JoinHandleExt};
+///
+/// let (sender, receiver) = message::channel::<TransferableWrapper<ArrayBuffer>>();
+/// let mut handle = web::spawn_with_message(
+/// 	|mut receiver| async move {
+/// 		while let Some(TransferableWrapper(buffer)) = receiver.recv().await {
+/// 			// Do work.
+/// #           let _ = buffer;
+/// 		}
+/// 	},
+/// 	receiver,
+/// );
+///
+/// for _ in 0..10 {
+/// 	sender
+/// 		.post_message(TransferableWrapper(ArrayBuffer::new(1000)))
+/// 		.unwrap();
+/// }
+///
+/// drop(sender);
+/// handle.join_async().await.unwrap();
+/// # }
+/// # #[cfg(not(all(target_feature = "atomics", not(unsupported_spawn))))]
+/// # let _ = test();
+/// ```
+///
+/// [`MessageChannel`]: https://developer.mozilla.org/en-US/docs/Web/API/MessageChannel
+#[must_use]
+pub fn channel<M: MessageSend>() -> (PortSender<M>, PortReceiver<M>) {
+	let (sender, receiver) = thread::port::channel();
+	(PortSender(sender), PortReceiver(receiver))
+}
+
+/// Sending half of [`channel()`].
+#[derive(Debug)]
+pub struct PortSender<M: MessageSend>(thread::port::PortSender<M>);
+
+impl<M: MessageSend> PortSender<M> {
+	/// Sends a message to the corresponding [`PortReceiver`].
+	///
+	/// # Errors
+	///
+	/// - If the corresponding [`PortReceiver`] was dropped.
+	/// - If `message` was unable to be cloned.
+	pub fn post_message(&self, message: M) -> Result<(), MessageError<M>> {
+		self.0.post_message(message)
+	}
+}
+
+impl<M: MessageSend> MessageSend for PortSender<M> {
+	type Send = mpsc::Sender<Arc<Mutex<Option<M::Send>>>>;
+
+	fn send<E: Extend<JsValue>>(self, transfer: &mut E) -> RawMessage<Self::Send> {
+		let (port, sender) = self.0.into_raw();
+		let RawMessage { serialize, .. } = TransferableWrapper(port).send(transfer);
+
+		RawMessage {
+			serialize,
+			send: Some(sender),
+		}
+	}
+
+	fn receive(serialized: Option<JsValue>, sent: Option<Self::Send>) -> Self {
+		let TransferableWrapper(port) = TransferableWrapper::receive(serialized, None);
+
+		Self(thread::port::PortSender::from_raw(
+			port,
+			sent.expect("expected `Send` value"),
+		))
+	}
+}
+
+/// Receiving half of [`channel()`].
+#[derive(Debug)]
+pub struct PortReceiver<M: MessageSend>(thread::port::PortReceiver<M>);
+
+impl<M: MessageSend> PortReceiver<M> {
+	/// Waits for the next message. Returns [`None`] if the corresponding
+	/// [`PortSender`] was dropped.
+	pub async fn recv(&mut self) -> Option<M> {
+		future::poll_fn(|cx| self.poll_recv(cx)).await
+	}
+
+	/// Polls for the next message. Returns [`None`] if the corresponding
+	/// [`PortSender`] was dropped.
+	///
+	/// This is useful to implement a [`Stream`].
+	///
+	/// [`Stream`]: https://docs.rs/futures-core/0.3/futures_core/stream/trait.Stream.html
+	// Matches the signature of `Future::poll()`.
+	#[allow(clippy::needless_pass_by_ref_mut)]
+	pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<M>> {
+		self.0.poll_recv(cx)
+	}
+}
+
+/// Parts of a [`PortReceiver`] sent to another thread: the [`MessagePort`],
+/// messages already received but not yet returned and the [`Send`] parts of
+/// messages still to come together with whether the [`PortSender`] was
+/// dropped.
+type RawPortReceiver<M> = (
+	TransferableWrapper<MessagePort>,
+	Vec<M>,
+	SendWrapper<(
+		mpsc::Receiver<Arc<Mutex<Option<<M as MessageSend>::Send>>>>,
+		bool,
+	)>,
+);
+
+impl<M: MessageSend> MessageSend for PortReceiver<M> {
+	type Send = <RawPortReceiver<M> as MessageSend>::Send;
+
+	fn send<E: Extend<JsValue>>(self, transfer: &mut E) -> RawMessage<Self::Send> {
+		let (port, receiver, buffered, closed) = self.0.into_raw();
+		let raw: RawPortReceiver<M> = (
+			TransferableWrapper(port),
+			buffered,
+			SendWrapper((receiver, closed)),
+		);
+		raw.send(transfer)
+	}
+
+	fn receive(serialized: Option<JsValue>, sent: Option<Self::Send>) -> Self {
+		let (TransferableWrapper(port), buffered, SendWrapper((receiver, closed))) =
+			RawPortReceiver::<M>::receive(serialized, sent);
+
+		Self(thread::port::PortReceiver::from_raw(
+			port, receiver, buffered, closed,
+		))
+	}
+}
+
+/// Error returned when posting a message failed. Returns the unsent message,
+/// e.g. to retry sending a clone of it.
+///
+/// Returned by:
+/// - [`PortSender::post_message()`]
+/// - [`JoinHandleExt::post_message()`](crate::web::JoinHandleExt::post_message)
+/// - [`BuilderExt::spawn_with_message()`](crate::web::BuilderExt::spawn_with_message)
+/// - [`BuilderExt::spawn_scoped_with_message()`](crate::web::BuilderExt::spawn_scoped_with_message)
+#[cfg_attr(
+	any(feature = "audio-worklet", docsrs),
+	doc = "- [`RustAudioWorkletNode::post()`](crate::web::audio_worklet::RustAudioWorkletNode::post)"
+)]
+pub struct MessageError<M> {
+	/// The message that failed to be posted.
+	pub message: M,
+	/// The kind of error.
+	pub kind: MessageErrorKind,
+	/// The [`name`] of the [`DOMException`] thrown by `postMessage()`. [`None`]
+	/// if the error was not caused by an exception.
+	///
+	/// [`name`]: https://developer.mozilla.org/en-US/docs/Web/API/DOMException/name
+	/// [`DOMException`]: https://developer.mozilla.org/en-US/docs/Web/API/DOMException
+	pub name: Option<String>,
+	/// The underlying error.
+	pub error: io::Error,
+}
+
+impl<M> Debug for MessageError<M> {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+		formatter
+			.debug_struct("MessageError")
+			.field("message", &any::type_name::<M>())
+			.field("kind", &self.kind)
+			.field("name", &self.name)
+			.field("error", &self.error)
+			.finish()
+	}
+}
+
+impl<M> Display for MessageError<M> {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+		Display::fmt(&self.error, formatter)
+	}
+}
+
+impl<M> error::Error for MessageError<M> {
+	fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+		Some(&self.error)
+	}
+}
+
+/// Kind of [`MessageError`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum MessageErrorKind {
+	/// The message could not be [cloned], e.g. because it contains a value
+	/// that is neither [`Serializable`] nor [`Transferable`].
+	///
+	/// [cloned]: https://developer.mozilla.org/en-US/docs/Web/API/Web_Workers_API/Structured_clone_algorithm
+	/// [`Serializable`]: https://developer.mozilla.org/en-US/docs/Glossary/Serializable_object
+	/// [`Transferable`]: https://developer.mozilla.org/en-US/docs/Web/API/Web_Workers_API/Transferable_objects
+	DataClone,
+	/// A [`ArrayBuffer`](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/ArrayBuffer)
+	/// to be transferred was already detached.
+	Detached,
+	/// The same object was added to the transfer list more than once.
+	DuplicateTransfer,
+	/// The receiving end was dropped or its thread has already finished.
+	Closed,
+	/// Any other error.
+	Other,
+}
+
 /// Helper type to minimize FFI calls when building [`Array`]s.
 pub(crate) struct ArrayBuilder {
 	/// The [`Array`].
@@ -740,42 +1561,173 @@ impl Extend<JsValue> for ArrayBuilder {
 #[doc(hidden)]
 #[cfg(all(target_family = "wasm", target_os = "unknown"))]
 pub mod __internal {
-	use wasm_bindgen::{JsCast, JsValue};
+	use std::marker::PhantomData;
+
+	use wasm_bindgen::JsCast;
+	pub use wasm_bindgen::JsValue;
 
 	use super::{
 		MessageSend, RawMessage, SendWrapper, Serializable, SerializableWrapper, Transferable,
 		TransferableWrapper,
 	};
 
-	macro_rules! impl_internal {
-		($name:ident for $priority:ty, $wrapper:ident, $($bound:path)|+) => {
-			pub trait $name<T> {
-				type Send;
+	/// A value together with the way it is sent, determined by [`message!`].
+	///
+	/// [`message!`]: crate::web::message!
+	#[derive(Debug)]
+	pub struct Routed<T, V>(pub T, pub PhantomData<V>);
 
-				fn __web_thread_send<E: Extend<JsValue>>(self, extend: &mut E) -> RawMessage<Self::Send>;
+	/// Routes a value through its own [`MessageSend`] implementation.
+	#[derive(Clone, Copy, Debug)]
+	pub struct ViaMessageSend;
 
-				fn __web_thread_receive(self, serialized: Option<JsValue>, sent: Option<Self::Send>) -> T;
+	pub trait InternalMessageSend<T> {
+		fn __web_thread_route(self) -> Routed<T, ViaMessageSend>;
+	}
+
+	#[allow(clippy::mut_mut)]
+	impl<T: MessageSend> InternalMessageSend<T> for &mut &mut &mut Option<T> {
+		fn __web_thread_route(self) -> Routed<T, ViaMessageSend> {
+			Routed(
+				self.take().expect("found empty `Option` while routing"),
+				PhantomData,
+			)
+		}
+	}
+
+	impl<T: MessageSend> MessageSend for Routed<T, ViaMessageSend> {
+		type Send = T::Send;
+
+		fn send<E: Extend<JsValue>>(self, transfer: &mut E) -> RawMessage<Self::Send> {
+			self.0.send(transfer)
+		}
+
+		fn receive(serialized: Option<JsValue>, sent: Option<Self::Send>) -> Self {
+			Self(T::receive(serialized, sent), PhantomData)
+		}
+	}
+
+	macro_rules! impl_internal {
+		($name:ident for $priority:ty, $via:ident, $wrapper:ident, $($bound:path)|+) => {
+			#[doc = concat!("Routes a value through [`", stringify!($wrapper), "`].")]
+			#[derive(Clone, Copy, Debug)]
+			pub struct $via;
+
+			pub trait $name<T> {
+				fn __web_thread_route(self) -> Routed<T, $via>;
 			}
 
 			#[allow(clippy::mut_mut)]
 			impl<T: $($bound+)+> $name<T> for $priority {
+				fn __web_thread_route(self) -> Routed<T, $via> {
+					Routed(self.take().expect("found empty `Option` while routing"), PhantomData)
+				}
+			}
+
+			impl<T: $($bound+)+> MessageSend for Routed<T, $via> {
 				type Send = <$wrapper<T> as MessageSend>::Send;
 
-				fn __web_thread_send<E: Extend<JsValue>>(self, extend: &mut E) -> RawMessage<Self::Send> {
-					$wrapper(self.take().expect("found empty `Option` while sending")).send(extend)
+				fn send<E: Extend<JsValue>>(self, transfer: &mut E) -> RawMessage<Self::Send> {
+					$wrapper(self.0).send(transfer)
 				}
 
-				fn __web_thread_receive(self, serialized: Option<JsValue>, sent: Option<Self::Send>) -> T {
-					debug_assert!(self.is_none(), "found filled `Option` while receiving");
-					$wrapper::receive(serialized, sent).0
+				fn receive(serialized: Option<JsValue>, sent: Option<Self::Send>) -> Self {
+					Self($wrapper::receive(serialized, sent).0, PhantomData)
 				}
 			}
 		};
 	}
 
-	impl_internal!(InternalSerializable for &mut &mut Option<T>, SerializableWrapper, Serializable | Into<JsValue> | JsCast);
-	impl_internal!(InternalTransferable for &mut Option<T>, TransferableWrapper, Transferable | Into<JsValue> | JsCast);
-	impl_internal!(InternalSend for &mut Option<T>, SendWrapper, Send);
+	impl_internal!(InternalSerializable for &mut &mut Option<T>, ViaSerializable, SerializableWrapper, Serializable | Into<JsValue> | JsCast);
+	impl_internal!(InternalTransferable for &mut Option<T>, ViaTransferable, TransferableWrapper, Transferable | Into<JsValue> | JsCast);
+	impl_internal!(InternalSend for &mut Option<T>, ViaSend, SendWrapper, Send);
+}
+
+/// Creates a [`MessageSend`] out of the given values. How each value is sent
+/// is determined by its type, in this order:
+/// - Types implementing [`MessageSend`] are sent as is, e.g.
+///   [`TransferableWrapper`].
+/// - Types implementing [`Serializable`] are [serialized].
+/// - Types implementing [`Transferable`] are [transferred].
+/// - Types implementing [`Send`] are sent as is.
+///
+/// Use [`message_pattern!`](crate::web::message_pattern) to destructure the
+/// resulting message again.
+///
+/// # Notes
+///
+/// Up to 12 values are supported. Types have to be known when calling this
+/// macro, the selection doesn't work in generic contexts.
+///
+/// Values that are both [`Serializable`] and [`Transferable`], e.g.
+/// [`ArrayBuffer`], are serialized. Use [`TransferableWrapper`] to transfer
+/// them instead.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+/// # wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+/// # #[cfg_attr(all(target_feature = "atomics", not(unsupported_spawn)), wasm_bindgen_test::wasm_bindgen_test)]
+/// # async fn test() {
+/// use js_sys::{ArrayBuffer, Uint8Array};
+/// use web_thread::web::message::TransferableWrapper;
+/// use web_thread::web::{self, JoinHandleExt};
+///
+/// let serialized = ArrayBuffer::new(1);
+/// let transferred = ArrayBuffer::new(1);
+/// web::spawn_with_message(
+/// 	|web::message_pattern!(serialized, TransferableWrapper(transferred), value)| async move {
+/// 		Uint8Array::new(&serialized).set_index(0, value);
+/// 		Uint8Array::new(&transferred).set_index(0, value);
+/// 	},
+/// 	web::message!(serialized.clone(), TransferableWrapper(transferred.clone()), 42_u8),
+/// )
+/// .join_async()
+/// .await
+/// .unwrap();
+///
+/// assert_eq!(serialized.byte_length(), 1);
+/// assert_eq!(transferred.byte_length(), 0);
+/// # }
+/// # #[cfg(not(all(target_feature = "atomics", not(unsupported_spawn))))]
+/// # let _ = test();
+/// ```
+///
+/// [serialized]: https://developer.mozilla.org/en-US/docs/Glossary/Serializable_object
+/// [transferred]: https://developer.mozilla.org/en-US/docs/Web/API/Web_Workers_API/Transferable_objects
+#[cfg_attr(
+	all(target_family = "wasm", target_os = "unknown"),
+	doc = "[`ArrayBuffer`]: js_sys::ArrayBuffer"
+)]
+#[cfg_attr(
+	not(all(target_family = "wasm", target_os = "unknown")),
+	doc = "[`ArrayBuffer`]: https://docs.rs/js-sys/0.3.68/js_sys/struct.ArrayBuffer.html"
+)]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __web_thread_message {
+	($($value:expr),+ $(,)?) => {{
+		#[allow(unused_imports)]
+		use $crate::web::message::__internal::{
+			InternalMessageSend as _, InternalSend as _, InternalSerializable as _,
+			InternalTransferable as _,
+		};
+
+		($((&mut &mut &mut ::std::option::Option::Some($value)).__web_thread_route(),)+)
+	}};
+}
+
+/// Destructures a message created with [`message!`](macro@crate::web::message)
+/// into the given patterns.
+///
+/// See [`message!`](macro@crate::web::message) for an example.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __web_thread_message_pattern {
+	($($pattern:pat),+ $(,)?) => {
+		($($crate::web::message::__internal::Routed($pattern, _),)+)
+	};
 }
 
 /// We assume that we aren't targeting `wasm64`.
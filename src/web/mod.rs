@@ -4,6 +4,7 @@
 pub mod audio_worklet;
 #[cfg(any(feature = "message", docsrs))]
 pub mod message;
+pub mod sync;
 
 use std::fmt::{self, Debug, Formatter};
 use std::future::{Future, Ready};
@@ -13,7 +14,10 @@ use std::pin::Pin;
 use std::task::{Context, Poll};
 
 #[cfg(any(feature = "message", docsrs))]
-use self::message::MessageSend;
+use self::message::{MessageError, MessageSend};
+#[cfg(any(feature = "message", docsrs))]
+#[doc(inline)]
+pub use crate::{__web_thread_message as message, __web_thread_message_pattern as message_pattern};
 
 #[cfg(not(all(target_family = "wasm", target_os = "unknown")))]
 mod thread {
@@ -143,12 +147,69 @@ pub trait JoinHandleExt<T> {
 	/// # let _ = test();
 	/// ```
 	fn join_async(&mut self) -> JoinHandleFuture<'_, T>;
+
+	/// Posts a [message](MessageSend) to the associated thread, which can be
+	/// received there with [`message::receive()`](self::message::receive).
+	///
+	/// Messages of the same type are received in the order they were posted.
+	/// Messages posted to a thread that finishes before receiving them are
+	/// dropped.
+	///
+	/// # Errors
+	///
+	/// - If the associated thread has already finished.
+	/// - If `message` was unable to be cloned.
+	///
+	/// # Example
+	///
+	/// ```
+	/// # #[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+	/// # wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+	/// # #[cfg_attr(all(target_feature = "atomics", not(unsupported_spawn)), wasm_bindgen_test::wasm_bindgen_test)]
+	/// # async fn test() {
+	/// use js_sys::ArrayBuffer;
+	/// use web_thread::web::message::{self, TransferableWrapper};
+	/// use web_thread::web::{self, JoinHandleExt};
+	///
+	/// let mut handle = web::spawn_async(|| async {
+	/// 	for _ in 0..10 {
+	/// 		let TransferableWrapper(buffer) =
+	/// 			message::receive::<TransferableWrapper<ArrayBuffer>>().await;
+	/// 		// Do work.
+	/// #       let _ = buffer;
+	/// 	}
+	/// });
+	///
+	/// for _ in 0..10 {
+	/// 	handle
+	/// 		.post_message(TransferableWrapper(ArrayBuffer::new(1000)))
+	/// 		.unwrap();
+	/// }
+	///
+	/// handle.join_async().await.unwrap();
+	/// # }
+	/// # #[cfg(not(all(target_feature = "atomics", not(unsupported_spawn))))]
+	/// # let _ = test();
+	/// ```
+	#[cfg(any(feature = "message", docsrs))]
+	fn post_message<M>(&self, message: M) -> Result<(), MessageError<M>>
+	where
+		Self: Sized,
+		M: 'static + MessageSend;
 }
 
 impl<T> JoinHandleExt<T> for JoinHandle<T> {
 	fn join_async(&mut self) -> JoinHandleFuture<'_, T> {
 		JoinHandleFuture(self)
 	}
+
+	#[cfg(any(feature = "message", docsrs))]
+	fn post_message<M>(&self, message: M) -> Result<(), MessageError<M>>
+	where
+		M: 'static + MessageSend,
+	{
+		self.post_message_internal(message)
+	}
 }
 
 /// Waits for the associated thread to finish. See
@@ -504,14 +565,39 @@ pub trait BuilderExt {
 	/// - If the main thread does not support spawning threads, see
 	///   [`has_spawn_support()`].
 	/// - If `message` was unable to be cloned.
+	///
+	/// In both cases `message` is returned in the [`MessageError`].
 	#[cfg(any(feature = "message", docsrs))]
-	fn spawn_with_message<F1, F2, T, M>(self, f: F1, message: M) -> io::Result<JoinHandle<T>>
+	fn spawn_with_message<F1, F2, T, M>(
+		self,
+		f: F1,
+		message: M,
+	) -> Result<JoinHandle<T>, MessageError<M>>
 	where
 		F1: 'static + FnOnce(M) -> F2 + Send,
 		F2: 'static + Future<Output = T>,
 		T: 'static + Send,
 		M: 'static + MessageSend;
 
+	/// [`spawn_async()`] returning a [message](MessageSend).
+	///
+	/// For a more complete documentation see [`spawn_returning_message()`].
+	///
+	/// # Errors
+	///
+	/// If the main thread does not support spawning threads, see
+	/// [`has_spawn_support()`].
+	///
+	/// # Panics
+	///
+	/// If called from an audio worklet.
+	#[cfg(any(feature = "message", docsrs))]
+	fn spawn_returning_message<F1, F2, O>(self, f: F1) -> io::Result<JoinHandle<O>>
+	where
+		F1: 'static + FnOnce() -> F2 + Send,
+		F2: 'static + Future<Output = O>,
+		O: 'static + MessageSend;
+
 	/// Async version of [`Builder::spawn_scoped()`].
 	///
 	/// For a more complete documentation see [`Scope::spawn_async()`].
@@ -539,18 +625,45 @@ pub trait BuilderExt {
 	/// - If the main thread does not support spawning threads, see
 	///   [`has_spawn_support()`].
 	/// - If `message` was unable to be cloned.
+	///
+	/// In both cases `message` is returned in the [`MessageError`].
 	#[cfg(any(feature = "message", docsrs))]
 	fn spawn_scoped_with_message<'scope, #[allow(single_use_lifetimes)] 'env, F1, F2, T, M>(
 		self,
 		scope: &'scope Scope<'scope, 'env>,
 		f: F1,
 		message: M,
-	) -> io::Result<ScopedJoinHandle<'scope, T>>
+	) -> Result<ScopedJoinHandle<'scope, T>, MessageError<M>>
 	where
 		F1: 'scope + FnOnce(M) -> F2 + Send,
 		F2: 'scope + Future<Output = T>,
 		T: 'scope + Send,
 		M: 'scope + MessageSend;
+
+	/// [`BuilderExt::spawn_scoped_async()`] returning a
+	/// [message](MessageSend).
+	///
+	/// For a more complete documentation see
+	/// [`ScopeExt::spawn_returning_message()`].
+	///
+	/// # Errors
+	///
+	/// If the main thread does not support spawning threads, see
+	/// [`has_spawn_support()`].
+	///
+	/// # Panics
+	///
+	/// If called from an audio worklet.
+	#[cfg(any(feature = "message", docsrs))]
+	fn spawn_scoped_returning_message<'scope, #[allow(single_use_lifetimes)] 'env, F1, F2, O>(
+		self,
+		scope: &'scope Scope<'scope, 'env>,
+		f: F1,
+	) -> io::Result<ScopedJoinHandle<'scope, O>>
+	where
+		F1: 'scope + FnOnce() -> F2 + Send,
+		F2: 'scope + Future<Output = O>,
+		O: 'static + MessageSend;
 }
 
 impl BuilderExt for Builder {
@@ -571,7 +684,7 @@ impl BuilderExt for Builder {
 		self,
 		#[allow(clippy::min_ident_chars)] f: F1,
 		message: M,
-	) -> io::Result<JoinHandle<T>>
+	) -> Result<JoinHandle<T>, MessageError<M>>
 	where
 		F1: 'static + FnOnce(M) -> F2 + Send,
 		F2: 'static + Future<Output = T>,
@@ -581,6 +694,19 @@ impl BuilderExt for Builder {
 		self.spawn_with_message_internal(f, message)
 	}
 
+	#[cfg(any(feature = "message", docsrs))]
+	fn spawn_returning_message<F1, F2, O>(
+		self,
+		#[allow(clippy::min_ident_chars)] f: F1,
+	) -> io::Result<JoinHandle<O>>
+	where
+		F1: 'static + FnOnce() -> F2 + Send,
+		F2: 'static + Future<Output = O>,
+		O: 'static + MessageSend,
+	{
+		self.spawn_returning_message_internal(f)
+	}
+
 	fn spawn_scoped_async<'scope, #[allow(single_use_lifetimes)] 'env, F1, F2, T>(
 		self,
 		scope: &'scope Scope<'scope, 'env>,
@@ -600,7 +726,7 @@ impl BuilderExt for Builder {
 		scope: &'scope Scope<'scope, 'env>,
 		#[allow(clippy::min_ident_chars)] f: F1,
 		message: M,
-	) -> io::Result<ScopedJoinHandle<'scope, T>>
+	) -> Result<ScopedJoinHandle<'scope, T>, MessageError<M>>
 	where
 		F1: 'scope + FnOnce(M) -> F2 + Send,
 		F2: 'scope + Future<Output = T>,
@@ -609,6 +735,20 @@ impl BuilderExt for Builder {
 	{
 		self.spawn_scoped_with_message_internal(scope, f, message)
 	}
+
+	#[cfg(any(feature = "message", docsrs))]
+	fn spawn_scoped_returning_message<'scope, #[allow(single_use_lifetimes)] 'env, F1, F2, O>(
+		self,
+		scope: &'scope Scope<'scope, 'env>,
+		#[allow(clippy::min_ident_chars)] f: F1,
+	) -> io::Result<ScopedJoinHandle<'scope, O>>
+	where
+		F1: 'scope + FnOnce() -> F2 + Send,
+		F2: 'scope + Future<Output = O>,
+		O: 'static + MessageSend,
+	{
+		self.spawn_scoped_returning_message_internal(scope, f)
+	}
 }
 
 /// Web-specific extension for [`web_thread::Scope`](crate::Scope).
@@ -687,6 +827,31 @@ pub trait ScopeExt<'scope> {
 		F2: 'scope + Future<Output = T>,
 		T: 'scope + Send,
 		M: 'scope + MessageSend;
+
+	/// [`ScopeExt::spawn_async()`] returning a [message](MessageSend).
+	///
+	/// For a more complete documentation see [`ScopeExt::spawn_async()`] and
+	/// [`spawn_returning_message()`].
+	///
+	/// # Notes
+	///
+	/// The output is received by the event loop of the calling thread.
+	/// Therefor the returned [`ScopedJoinHandle`] can only be joined with
+	/// [`ScopedJoinHandleExt::join_async()`], [`ScopedJoinHandle::join()`]
+	/// would block forever. Leaving the scope only waits for the thread to
+	/// finish and is not affected.
+	///
+	/// # Panics
+	///
+	/// - If the main thread does not support spawning threads, see
+	///   [`has_spawn_support()`].
+	/// - If called from an audio worklet.
+	#[cfg(any(feature = "message", docsrs))]
+	fn spawn_returning_message<F1, F2, O>(&'scope self, f: F1) -> ScopedJoinHandle<'scope, O>
+	where
+		F1: 'scope + FnOnce() -> F2 + Send,
+		F2: 'scope + Future<Output = O>,
+		O: 'static + MessageSend;
 }
 
 impl<'scope> ScopeExt<'scope> for Scope<'scope, '_> {
@@ -716,6 +881,19 @@ impl<'scope> ScopeExt<'scope> for Scope<'scope, '_> {
 	{
 		self.spawn_with_message_internal(f, message)
 	}
+
+	#[cfg(any(feature = "message", docsrs))]
+	fn spawn_returning_message<F1, F2, O>(
+		&'scope self,
+		#[allow(clippy::min_ident_chars)] f: F1,
+	) -> ScopedJoinHandle<'scope, O>
+	where
+		F1: 'scope + FnOnce() -> F2 + Send,
+		F2: 'scope + Future<Output = O>,
+		O: 'static + MessageSend,
+	{
+		self.spawn_returning_message_internal(f)
+	}
 }
 
 /// Async version of [`spawn()`](std::thread::spawn).
@@ -830,6 +1008,74 @@ where
 		.expect("failed to spawn thread")
 }
 
+/// [`spawn_async()`] returning a [message](MessageSend).
+///
+/// Unlike the return value of other threads, the output doesn't have to be
+/// [`Send`]. Instead it is sent back to the calling thread like a message and
+/// reassembled there, e.g. to return an [`ImageBitmap`] or a [`Blob`] produced
+/// by the thread.
+///
+/// For a more complete documentation see [`spawn_async()`].
+///
+/// # Notes
+///
+/// The output is received by the event loop of the calling thread. Therefor
+/// the returned [`JoinHandle`] can only be joined with
+/// [`JoinHandleExt::join_async()`], [`JoinHandle::join()`] would block
+/// forever. The same applies to
+/// [`BuilderExt::spawn_returning_message()`],
+/// [`ScopeExt::spawn_returning_message()`] and
+/// [`BuilderExt::spawn_scoped_returning_message()`].
+///
+/// If the output was unable to be cloned, e.g. because it contained a
+/// detached [`ArrayBuffer`], joining returns an error instead.
+///
+/// # Panics
+///
+/// - If the main thread does not support spawning threads, see
+///   [`has_spawn_support()`].
+/// - If called from an audio worklet.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+/// # wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+/// # #[cfg_attr(all(target_feature = "atomics", not(unsupported_spawn)), wasm_bindgen_test::wasm_bindgen_test)]
+/// # async fn test() {
+/// use web_sys::OffscreenCanvas;
+/// use web_thread::web::{self, JoinHandleExt};
+/// use web_thread::web::message::TransferableWrapper;
+///
+/// let TransferableWrapper(canvas) = web::spawn_returning_message(|| async {
+/// 	let canvas = OffscreenCanvas::new(100, 100).unwrap();
+/// 	// Do work.
+/// 	TransferableWrapper(canvas)
+/// })
+/// .join_async()
+/// .await
+/// .unwrap();
+/// # let _: OffscreenCanvas = canvas;
+/// # }
+/// # #[cfg(not(all(target_feature = "atomics", not(unsupported_spawn))))]
+/// # let _ = test();
+/// ```
+///
+/// [`ArrayBuffer`]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/ArrayBuffer
+/// [`Blob`]: https://developer.mozilla.org/en-US/docs/Web/API/Blob
+/// [`ImageBitmap`]: https://developer.mozilla.org/en-US/docs/Web/API/ImageBitmap
+#[cfg(any(feature = "message", docsrs))]
+pub fn spawn_returning_message<F1, F2, O>(#[allow(clippy::min_ident_chars)] f: F1) -> JoinHandle<O>
+where
+	F1: 'static + FnOnce() -> F2 + Send,
+	F2: 'static + Future<Output = O>,
+	O: 'static + MessageSend,
+{
+	Builder::new()
+		.spawn_returning_message(f)
+		.expect("failed to spawn thread")
+}
+
 /// Async version of [`yield_now()`](std::thread::yield_now). This yields
 /// execution to the [event loop].
 ///
@@ -0,0 +1,211 @@
+//! Platform-specific synchronization primitives for [`web-thread`](crate) on
+//! the Web platform.
+
+use std::cell::UnsafeCell;
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::ops::{Deref, DerefMut};
+
+#[cfg(all(target_family = "wasm", target_os = "unknown"))]
+use crate::thread::sync::RawSpinMutex;
+
+#[cfg(not(all(target_family = "wasm", target_os = "unknown")))]
+struct RawSpinMutex;
+
+/// A mutual exclusion primitive that can be locked on threads not supporting
+/// blocking, e.g. the main thread.
+///
+/// # Notes
+///
+/// On threads supporting blocking, see
+/// [`has_block_support()`](super::has_block_support), [`lock()`] spins shortly
+/// and then blocks until the lock is acquired, similar to
+/// [`std::sync::Mutex`].
+///
+/// On threads not supporting blocking, [`lock()`] spins up to
+/// [`spin_limit()`](Self::spin_limit) times, using [`Atomics.pause()`] if
+/// supported, and returns [`SpinLimitError`] if the lock couldn't be acquired
+/// in time. So this is only suitable for protecting short critical sections.
+///
+/// In debug builds, spinning on the main thread for longer than a millisecond
+/// is reported to the console.
+///
+/// [`lock()`]: Self::lock
+/// [`Atomics.pause()`]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Atomics/pause
+///
+/// # Example
+///
+/// ```
+/// # wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+/// # #[wasm_bindgen_test::wasm_bindgen_test]
+/// # fn test() {
+/// use web_thread::web::sync::SpinMutex;
+///
+/// static COUNTER: SpinMutex<u32> = SpinMutex::new(0);
+///
+/// *COUNTER.lock().unwrap() += 1;
+/// assert_eq!(*COUNTER.lock().unwrap(), 1);
+/// # }
+/// ```
+pub struct SpinMutex<T: ?Sized> {
+	/// Lock state.
+	raw: RawSpinMutex,
+	/// Protected value.
+	value: UnsafeCell<T>,
+}
+
+// SAFETY: Access to the value is synchronized by the lock, see
+// `std::sync::Mutex`.
+unsafe impl<T: ?Sized + Send> Send for SpinMutex<T> {}
+
+// SAFETY: Access to the value is synchronized by the lock, see
+// `std::sync::Mutex`.
+unsafe impl<T: ?Sized + Send> Sync for SpinMutex<T> {}
+
+impl<T> SpinMutex<T> {
+	/// Default value of [`SpinMutex::spin_limit()`].
+	pub const DEFAULT_SPIN_LIMIT: u32 = 10_000;
+
+	/// Creates a new [`SpinMutex`] with [`SpinMutex::DEFAULT_SPIN_LIMIT`].
+	#[must_use]
+	pub const fn new(value: T) -> Self {
+		Self::with_spin_limit(value, Self::DEFAULT_SPIN_LIMIT)
+	}
+
+	/// Creates a new [`SpinMutex`] with the given `spin_limit`. See
+	/// [`SpinMutex::spin_limit()`].
+	#[must_use]
+	pub const fn with_spin_limit(value: T, spin_limit: u32) -> Self {
+		Self {
+			raw: RawSpinMutex::new(spin_limit),
+			value: UnsafeCell::new(value),
+		}
+	}
+
+	/// Consumes this [`SpinMutex`], returning the underlying data.
+	#[must_use]
+	pub fn into_inner(self) -> T {
+		self.value.into_inner()
+	}
+}
+
+impl<T: ?Sized> SpinMutex<T> {
+	/// Acquires the lock.
+	///
+	/// # Errors
+	///
+	/// If the calling thread doesn't support blocking and the lock couldn't be
+	/// acquired after [`SpinMutex::spin_limit()`] spins.
+	///
+	/// # Panics
+	///
+	/// If the lock is already held by the calling thread and it supports
+	/// blocking, this might panic or deadlock.
+	pub fn lock(&self) -> Result<SpinMutexGuard<'_, T>, SpinLimitError> {
+		if self.raw.lock() {
+			Ok(SpinMutexGuard(self))
+		} else {
+			Err(SpinLimitError)
+		}
+	}
+
+	/// Attempts to acquire the lock without spinning. Returns [`None`] if the
+	/// lock is currently held.
+	pub fn try_lock(&self) -> Option<SpinMutexGuard<'_, T>> {
+		self.raw.try_lock().then_some(SpinMutexGuard(self))
+	}
+
+	/// Returns a mutable reference to the underlying data. No locking is
+	/// required because this requires exclusive access.
+	pub fn get_mut(&mut self) -> &mut T {
+		self.value.get_mut()
+	}
+
+	/// Returns the amount of times [`SpinMutex::lock()`] spins on threads not
+	/// supporting blocking before giving up.
+	#[must_use]
+	pub const fn spin_limit(&self) -> u32 {
+		self.raw.spin_limit()
+	}
+}
+
+impl<T: ?Sized + Debug> Debug for SpinMutex<T> {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+		let mut debug = formatter.debug_struct("SpinMutex");
+
+		if let Some(guard) = self.try_lock() {
+			debug.field("data", &&*guard);
+		} else {
+			debug.field("data", &format_args!("<locked>"));
+		}
+
+		debug.field("spin_limit", &self.spin_limit()).finish()
+	}
+}
+
+impl<T: Default> Default for SpinMutex<T> {
+	fn default() -> Self {
+		Self::new(T::default())
+	}
+}
+
+impl<T> From<T> for SpinMutex<T> {
+	fn from(value: T) -> Self {
+		Self::new(value)
+	}
+}
+
+/// Guard returned by [`SpinMutex::lock()`] and [`SpinMutex::try_lock()`].
+/// Releases the lock when dropped.
+#[must_use = "if unused the `SpinMutex` will immediately unlock"]
+pub struct SpinMutexGuard<'mutex, T: ?Sized>(&'mutex SpinMutex<T>);
+
+// SAFETY: Equivalent to `&T`, see `std::sync::MutexGuard`.
+unsafe impl<T: ?Sized + Sync> Sync for SpinMutexGuard<'_, T> {}
+
+impl<T: ?Sized + Debug> Debug for SpinMutexGuard<'_, T> {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+		Debug::fmt(&**self, formatter)
+	}
+}
+
+impl<T: ?Sized + Display> Display for SpinMutexGuard<'_, T> {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+		Display::fmt(&**self, formatter)
+	}
+}
+
+impl<T: ?Sized> Deref for SpinMutexGuard<'_, T> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		// SAFETY: The lock is held as long as the guard exists.
+		unsafe { &*self.0.value.get() }
+	}
+}
+
+impl<T: ?Sized> DerefMut for SpinMutexGuard<'_, T> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		// SAFETY: The lock is held as long as the guard exists.
+		unsafe { &mut *self.0.value.get() }
+	}
+}
+
+impl<T: ?Sized> Drop for SpinMutexGuard<'_, T> {
+	fn drop(&mut self) {
+		self.0.raw.unlock();
+	}
+}
+
+/// Returned by [`SpinMutex::lock()`] if the lock couldn't be acquired after
+/// [`SpinMutex::spin_limit()`] spins.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SpinLimitError;
+
+impl Display for SpinLimitError {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+		formatter.write_str("exceeded spin limit while trying to acquire `SpinMutex`")
+	}
+}
+
+impl Error for SpinLimitError {}
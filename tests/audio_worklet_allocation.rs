@@ -0,0 +1,104 @@
+#![cfg(test)]
+#![cfg(all(
+	target_family = "wasm",
+	feature = "detect-allocations",
+	debug_assertions
+))]
+
+use std::alloc::System;
+use std::hint;
+
+use wasm_bindgen_test::wasm_bindgen_test;
+use web_sys::{AudioWorkletNodeOptions, AudioWorkletProcessor};
+use web_thread::web::audio_worklet::{
+	self, AudioParameters, DetectingAllocator, ExtendAudioWorkletProcessor, ProcessBuffers,
+	ProcessBuffersFn,
+};
+
+#[global_allocator]
+static ALLOCATOR: DetectingAllocator = DetectingAllocator::new_panicking(System);
+
+struct AllocatingProcessor;
+
+impl ExtendAudioWorkletProcessor for AllocatingProcessor {
+	type Data = ();
+
+	const PROCESS_BUFFERS: Option<ProcessBuffersFn<Self>> = Some(ProcessBuffersFn::new());
+
+	fn new(_: AudioWorkletProcessor, _: Option<Self::Data>, _: AudioWorkletNodeOptions) -> Self {
+		Self
+	}
+}
+
+impl ProcessBuffers for AllocatingProcessor {
+	fn process_buffers(
+		&mut self,
+		_: &[&[&[f32]]],
+		outputs: &mut [&mut [&mut [f32]]],
+		_: &AudioParameters<'_>,
+	) -> bool {
+		for channel in outputs.iter_mut().flat_map(|output| output.iter_mut()) {
+			channel.fill(1.);
+		}
+
+		hint::black_box(Vec::<u8>::with_capacity(1));
+
+		true
+	}
+}
+
+#[wasm_bindgen_test]
+async fn allocating() {
+	let channels = audio_worklet::render_offline::<AllocatingProcessor>(8000., 256, 1, ())
+		.await
+		.unwrap();
+
+	// The processor panics after the first render quantum and only outputs silence
+	// afterwards.
+	for channel in channels {
+		assert!(channel
+			.iter()
+			.skip(128)
+			.all(|sample| sample.abs() < f32::EPSILON));
+	}
+}
+
+struct NonAllocatingProcessor;
+
+impl ExtendAudioWorkletProcessor for NonAllocatingProcessor {
+	type Data = ();
+
+	const PROCESS_BUFFERS: Option<ProcessBuffersFn<Self>> = Some(ProcessBuffersFn::new());
+
+	fn new(_: AudioWorkletProcessor, _: Option<Self::Data>, _: AudioWorkletNodeOptions) -> Self {
+		Self
+	}
+}
+
+impl ProcessBuffers for NonAllocatingProcessor {
+	fn process_buffers(
+		&mut self,
+		_: &[&[&[f32]]],
+		outputs: &mut [&mut [&mut [f32]]],
+		_: &AudioParameters<'_>,
+	) -> bool {
+		for channel in outputs.iter_mut().flat_map(|output| output.iter_mut()) {
+			channel.fill(1.);
+		}
+
+		true
+	}
+}
+
+#[wasm_bindgen_test]
+async fn non_allocating() {
+	let channels = audio_worklet::render_offline::<NonAllocatingProcessor>(8000., 256, 1, ())
+		.await
+		.unwrap();
+
+	for channel in channels {
+		assert!(channel
+			.iter()
+			.all(|sample| (*sample - 1.).abs() < f32::EPSILON));
+	}
+}
@@ -1,13 +1,21 @@
 #![cfg(test)]
 #![cfg(all(target_family = "wasm", feature = "message", feature = "audio-worklet"))]
 
-use js_sys::{ArrayBuffer, Uint8Array};
+use std::io::ErrorKind;
+
+use js_sys::{Array, ArrayBuffer, Object, Uint8Array};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
 use wasm_bindgen_test::wasm_bindgen_test;
-use web_sys::OfflineAudioContext;
-use web_thread::web;
-use web_thread::web::audio_worklet::BaseAudioContextExt;
-use web_thread::web::message::TransferableWrapper;
-use web_thread::web::JoinHandleExt;
+use web_sys::{
+	AudioWorkletGlobalScope, AudioWorkletNodeOptions, AudioWorkletProcessor, OfflineAudioContext,
+};
+use web_thread::web::audio_worklet::{
+	AudioWorkletGlobalScopeExt, BaseAudioContextExt, ExtendAudioWorkletProcessor, ReceiveMessage,
+	RustAudioWorkletNode,
+};
+use web_thread::web::message::{SendWrapper, SerializableWrapper, TransferableWrapper};
+use web_thread::web::{self, JoinHandleExt, YieldTime};
 
 use super::util::Flag;
 
@@ -72,3 +80,146 @@ async fn nested() {
 
 	receiver.recv().await.unwrap().join_async().await.unwrap();
 }
+
+struct MessageProcessor(Option<Flag>);
+
+impl ExtendAudioWorkletProcessor for MessageProcessor {
+	type Data = Flag;
+
+	fn new(_: AudioWorkletProcessor, data: Option<Self::Data>, _: AudioWorkletNodeOptions) -> Self {
+		Self(data)
+	}
+
+	fn process(&mut self, _: Array, _: Array, _: Object) -> bool {
+		self.0.is_some()
+	}
+}
+
+impl ReceiveMessage for MessageProcessor {
+	type Message = TransferableWrapper<ArrayBuffer>;
+
+	fn on_message(&mut self, TransferableWrapper(buffer): Self::Message) {
+		let array = Uint8Array::new(&buffer);
+		assert_eq!(array.get_index(0), 42);
+
+		self.0.take().unwrap().signal();
+	}
+}
+
+#[wasm_bindgen_test]
+async fn processor_message() {
+	let context =
+		OfflineAudioContext::new_with_number_of_channels_and_length_and_sample_rate(1, 1, 8000.)
+			.unwrap();
+	let start = Flag::new();
+	let end = Flag::new();
+	context
+		.clone()
+		.register_thread(None, {
+			let start = start.clone();
+			move || {
+				let global: AudioWorkletGlobalScope = js_sys::global().unchecked_into();
+				global
+					.register_processor_ext::<MessageProcessor>("test")
+					.unwrap();
+				start.signal();
+			}
+		})
+		.await
+		.unwrap();
+
+	// Wait until processor is registered.
+	start.await;
+	web::yield_now_async(YieldTime::UserBlocking).await;
+
+	let node =
+		RustAudioWorkletNode::<MessageProcessor>::new(&context, "test", end.clone(), None).unwrap();
+
+	let buffer = ArrayBuffer::new(1);
+	let array = Uint8Array::new(&buffer);
+	array.copy_from(&[42]);
+	node.post(TransferableWrapper(buffer.clone())).unwrap();
+	assert_eq!(buffer.byte_length(), 0);
+
+	JsFuture::from(context.start_rendering().unwrap())
+		.await
+		.unwrap();
+	end.await;
+}
+
+struct DataProcessor;
+
+impl ExtendAudioWorkletProcessor for DataProcessor {
+	type Data = (SerializableWrapper<ArrayBuffer>, SendWrapper<Flag>);
+
+	fn new(_: AudioWorkletProcessor, data: Option<Self::Data>, _: AudioWorkletNodeOptions) -> Self {
+		let (SerializableWrapper(buffer), SendWrapper(flag)) = data.unwrap();
+		let array = Uint8Array::new(&buffer);
+		assert_eq!(array.get_index(0), 42);
+		flag.signal();
+
+		Self
+	}
+}
+
+#[wasm_bindgen_test]
+async fn processor_data_message() {
+	let context =
+		OfflineAudioContext::new_with_number_of_channels_and_length_and_sample_rate(1, 1, 8000.)
+			.unwrap();
+	context.clone().register_thread(None, || ()).await.unwrap();
+	context
+		.register_processor::<DataProcessor>("data")
+		.await
+		.unwrap();
+
+	let buffer = ArrayBuffer::new(1);
+	let array = Uint8Array::new(&buffer);
+	array.copy_from(&[42]);
+	let flag = Flag::new();
+	let _node = context
+		.audio_worklet_node_with_message::<DataProcessor>(
+			"data",
+			(
+				SerializableWrapper(buffer.clone()),
+				SendWrapper(flag.clone()),
+			),
+			None,
+		)
+		.unwrap();
+	assert_eq!(buffer.byte_length(), 1);
+
+	flag.await;
+}
+
+struct TransferProcessor;
+
+impl ExtendAudioWorkletProcessor for TransferProcessor {
+	type Data = TransferableWrapper<ArrayBuffer>;
+
+	fn new(_: AudioWorkletProcessor, _: Option<Self::Data>, _: AudioWorkletNodeOptions) -> Self {
+		unreachable!()
+	}
+}
+
+#[wasm_bindgen_test]
+async fn processor_data_message_transfer() {
+	let context =
+		OfflineAudioContext::new_with_number_of_channels_and_length_and_sample_rate(1, 1, 8000.)
+			.unwrap();
+	context.clone().register_thread(None, || ()).await.unwrap();
+	context
+		.register_processor::<TransferProcessor>("transfer")
+		.await
+		.unwrap();
+
+	let error = context
+		.audio_worklet_node_with_message::<TransferProcessor>(
+			"transfer",
+			TransferableWrapper(ArrayBuffer::new(1)),
+			None,
+		)
+		.unwrap_err();
+	assert_eq!(error.error.kind(), ErrorKind::Unsupported);
+	assert_eq!(error.data.0.byte_length(), 1);
+}
@@ -3,16 +3,21 @@
 
 use std::cell::RefCell;
 use std::future::Future;
+use std::sync::Arc;
 
-use js_sys::{Array, Iterator, JsString, Object, Reflect};
+use js_sys::{Array, Float32Array, Iterator, JsString, Object, Reflect};
 use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::JsFuture;
 use wasm_bindgen_test::wasm_bindgen_test;
 use web_sys::{
-	AudioContext, AudioWorkletGlobalScope, AudioWorkletNode, AudioWorkletNodeOptions,
-	BaseAudioContext, OfflineAudioContext,
+	AudioBuffer, AudioContext, AudioWorkletGlobalScope, AudioWorkletNode, AudioWorkletNodeOptions,
+	AudioWorkletProcessor, BaseAudioContext, OfflineAudioContext,
+};
+use web_thread::web::audio_worklet::{
+	self, AudioParamDescriptor, AudioParameters, AudioWorkletGlobalScopeExt, AutomationRate,
+	BaseAudioContextExt, EventQueue, ExtendAudioWorkletProcessor, ProcessBuffers, ProcessBuffersFn,
+	RingBuffer, RustAudioWorkletNode, Signal,
 };
-use web_thread::web::audio_worklet::{AudioWorkletGlobalScopeExt, BaseAudioContextExt};
 use web_thread::web::{self, JoinHandleExt, YieldTime};
 
 use super::test_processor::{
@@ -96,6 +101,159 @@ async fn test_register_drop(context: BaseAudioContext) {
 
 test_audio!(register_drop);
 
+#[wasm_bindgen_test]
+async fn signal() {
+	let signal = Signal::new();
+	assert!(!signal.is_set());
+
+	signal.notify();
+	signal.notify();
+	assert!(signal.is_set());
+	assert_eq!(signal.count(), 2);
+
+	signal.wait_async().await;
+	assert!(!signal.is_set());
+
+	signal.notify();
+	assert!(signal.reset());
+	assert!(!signal.reset());
+}
+
+async fn test_signal_worklet(context: BaseAudioContext) {
+	let signal = Arc::new(Signal::new());
+
+	context
+		.register_thread(None, {
+			let signal = Arc::clone(&signal);
+			move || signal.notify()
+		})
+		.await
+		.unwrap();
+
+	signal.wait_async().await;
+	assert_eq!(signal.count(), 1);
+	assert!(!signal.is_set());
+}
+
+test_audio!(signal_worklet);
+
+#[wasm_bindgen_test]
+async fn ring_buffer() {
+	let (mut producer, mut consumer) = RingBuffer::new(4).split();
+	assert_eq!(producer.capacity(), 4);
+	assert!(consumer.is_empty());
+
+	assert_eq!(producer.push_slice(&[1, 2, 3]), 3);
+	assert_eq!(consumer.pop(), Some(1));
+	assert_eq!(producer.push_slice(&[4, 5, 6]), 2);
+	assert_eq!(producer.push(7), Err(7));
+	assert_eq!(producer.free_len(), 0);
+	assert_eq!(consumer.len(), 4);
+
+	let mut values = [0; 5];
+	assert_eq!(consumer.pop_slice(&mut values), 4);
+	assert_eq!(values, [2, 3, 4, 5, 0]);
+	assert_eq!(consumer.pop(), None);
+
+	producer.wait_for_space(4).await;
+}
+
+#[wasm_bindgen_test]
+fn ring_buffer_float32_array() {
+	let (mut producer, mut consumer) = RingBuffer::new(4).split();
+	assert_eq!(producer.push_slice(&[1., 2., 3.]), 3);
+
+	let array = Float32Array::new_with_length(2);
+	assert_eq!(consumer.pop_float32_array(&array), 2);
+	assert_eq!(array.to_vec(), [1., 2.]);
+	assert_eq!(consumer.len(), 1);
+
+	// Wraps around the end of the buffer.
+	assert_eq!(producer.push_slice(&[4., 5.]), 2);
+	let array = Float32Array::new_with_length(4);
+	assert_eq!(consumer.pop_float32_array(&array), 3);
+	assert_eq!(array.to_vec(), [3., 4., 5., 0.]);
+	assert!(consumer.is_empty());
+}
+
+async fn test_ring_buffer_worklet(context: BaseAudioContext) {
+	let (mut producer, mut consumer) = RingBuffer::new(128).split();
+	assert_eq!(producer.push_slice(&[1; 128]), 128);
+
+	let flag = Flag::new();
+
+	context
+		.register_thread(None, {
+			let flag = flag.clone();
+			move || {
+				let mut values = [0; 128];
+				assert_eq!(consumer.pop_slice(&mut values), 128);
+				assert_eq!(values, [1; 128]);
+				flag.signal();
+			}
+		})
+		.await
+		.unwrap();
+
+	producer.wait_for_space(128).await;
+	flag.await;
+}
+
+test_audio!(ring_buffer_worklet);
+
+#[wasm_bindgen_test]
+fn event_queue() {
+	let (producer, mut consumer) = EventQueue::new(4).split();
+	assert_eq!(producer.capacity(), 4);
+
+	producer.push(300, 3).unwrap();
+	producer.push(5, 1).unwrap();
+	producer.push(130, 2).unwrap();
+	producer.push(5, 4).unwrap();
+	assert_eq!(producer.push(0, 5), Err(5));
+
+	assert_eq!(consumer.drain(0, 128).collect::<Vec<_>>(), [(5, 1), (5, 4)]);
+	producer.push(200, 5).unwrap();
+	assert_eq!(
+		consumer.drain(128, 128).collect::<Vec<_>>(),
+		[(2, 2), (72, 5)]
+	);
+	assert_eq!(consumer.drain(400, 128).collect::<Vec<_>>(), [(0, 3)]);
+	assert_eq!(consumer.drain(528, 128).next(), None);
+}
+
+async fn test_event_queue_worklet(context: BaseAudioContext) {
+	let (producer, mut consumer) = EventQueue::new(128).split();
+
+	for frame in 0..128 {
+		producer.push(frame, frame).unwrap();
+	}
+
+	let flag = Flag::new();
+
+	context
+		.register_thread(None, {
+			let flag = flag.clone();
+			move || {
+				let events: Vec<_> = consumer.drain(0, 128).collect();
+				assert_eq!(events.len(), 128);
+
+				for (index, (offset, event)) in events.into_iter().enumerate() {
+					assert_eq!(offset, index);
+					assert_eq!(event, u64::try_from(index).unwrap());
+				}
+
+				flag.signal();
+			}
+		})
+		.await
+		.unwrap();
+
+	flag.await;
+}
+
+test_audio!(event_queue_worklet);
+
 async fn test_node(context: BaseAudioContext) {
 	let start = Flag::new();
 	let end = Flag::new();
@@ -671,6 +829,342 @@ async fn test_parameters(context: BaseAudioContext) {
 
 test_audio!(parameters);
 
+struct BufferProcessor(Option<Flag>);
+
+impl ExtendAudioWorkletProcessor for BufferProcessor {
+	type Data = Flag;
+
+	const PARAMETERS: &'static [AudioParamDescriptor] = &[
+		AudioParamDescriptor::new("test"),
+		AudioParamDescriptor::new("factor")
+			.with_default_value(2.)
+			.with_automation_rate(AutomationRate::KRate),
+	];
+	const PROCESS_BUFFERS: Option<ProcessBuffersFn<Self>> = Some(ProcessBuffersFn::new());
+
+	fn new(_: AudioWorkletProcessor, data: Option<Self::Data>, _: AudioWorkletNodeOptions) -> Self {
+		Self(data)
+	}
+
+	fn process(&mut self, _: Array, _: Array, _: Object) -> bool {
+		panic!("called `process()` instead of `process_buffers()`")
+	}
+}
+
+impl ProcessBuffers for BufferProcessor {
+	fn process_buffers(
+		&mut self,
+		inputs: &[&[&[f32]]],
+		outputs: &mut [&mut [&mut [f32]]],
+		parameters: &AudioParameters<'_>,
+	) -> bool {
+		assert_eq!(inputs.len(), 1);
+		assert_eq!(outputs.len(), 1);
+		assert_eq!(parameters.len(), 2);
+		assert_eq!(parameters.get_by_index(1_usize).map(<[f32]>::len), Some(1));
+		let value =
+			parameters.value("test", 0).unwrap() * parameters.value_by_index(1_usize, 0).unwrap();
+
+		for channel in outputs.iter_mut().flat_map(|output| output.iter_mut()) {
+			assert!(channel.iter().all(|sample| *sample == 0.));
+			channel.fill(value);
+		}
+
+		if let Some(end) = self.0.take() {
+			end.signal();
+		}
+
+		true
+	}
+}
+
+#[wasm_bindgen_test]
+async fn offline_process_buffers() {
+	let context =
+		OfflineAudioContext::new_with_number_of_channels_and_length_and_sample_rate(1, 128, 8000.)
+			.unwrap();
+	let start = Flag::new();
+	let end = Flag::new();
+	context
+		.clone()
+		.register_thread(None, {
+			let start = start.clone();
+			move || {
+				let global: AudioWorkletGlobalScope = js_sys::global().unchecked_into();
+				global
+					.register_processor_ext::<BufferProcessor>("test")
+					.unwrap();
+				start.signal();
+			}
+		})
+		.await
+		.unwrap();
+
+	// Wait until processor is registered.
+	start.await;
+	web::yield_now_async(YieldTime::UserBlocking).await;
+
+	let options: AudioWorkletNodeOptionsExt = AudioWorkletNodeOptions::new().unchecked_into();
+	let parameters = Array::new();
+	Reflect::set(&parameters, &"test".into(), &42.0.into()).unwrap();
+	options.set_parameter_data(Some(&parameters));
+	let node = context
+		.audio_worklet_node::<BufferProcessor>("test", end.clone(), Some(&options))
+		.unwrap();
+	node.connect_with_audio_node(&context.destination())
+		.unwrap();
+
+	let buffer = JsFuture::from(context.start_rendering().unwrap())
+		.await
+		.unwrap()
+		.unchecked_into::<AudioBuffer>();
+	end.await;
+
+	let samples = buffer.get_channel_data(0).unwrap();
+	assert!(samples
+		.iter()
+		.all(|sample| (*sample - 84.).abs() < f32::EPSILON));
+}
+
+struct StopProcessor(Option<Flag>);
+
+impl ExtendAudioWorkletProcessor for StopProcessor {
+	type Data = Flag;
+
+	fn new(_: AudioWorkletProcessor, data: Option<Self::Data>, _: AudioWorkletNodeOptions) -> Self {
+		Self(data)
+	}
+
+	fn process(&mut self, _: Array, _: Array, _: Object) -> bool {
+		false
+	}
+
+	fn on_stop(&mut self) {
+		self.0.take().unwrap().signal();
+	}
+}
+
+#[wasm_bindgen_test]
+async fn offline_on_stop() {
+	let context =
+		OfflineAudioContext::new_with_number_of_channels_and_length_and_sample_rate(1, 1, 8000.)
+			.unwrap();
+	let start = Flag::new();
+	let end = Flag::new();
+	context
+		.clone()
+		.register_thread(None, {
+			let start = start.clone();
+			move || {
+				let global: AudioWorkletGlobalScope = js_sys::global().unchecked_into();
+				global
+					.register_processor_ext::<StopProcessor>("test")
+					.unwrap();
+				start.signal();
+			}
+		})
+		.await
+		.unwrap();
+
+	// Wait until processor is registered.
+	start.await;
+	web::yield_now_async(YieldTime::UserBlocking).await;
+
+	context
+		.audio_worklet_node::<StopProcessor>("test", end.clone(), None)
+		.unwrap();
+	JsFuture::from(context.start_rendering().unwrap())
+		.await
+		.unwrap();
+	end.await;
+}
+
+async fn test_register_processor(context: BaseAudioContext) {
+	context.clone().register_thread(None, || ()).await.unwrap();
+	context
+		.register_processor::<ConstantProcessor>("test")
+		.await
+		.unwrap();
+
+	context
+		.audio_worklet_node::<ConstantProcessor>("test", 42., None)
+		.unwrap();
+}
+
+test_audio!(register_processor);
+
+#[wasm_bindgen_test]
+async fn offline_node_from_fn() {
+	let context =
+		OfflineAudioContext::new_with_number_of_channels_and_length_and_sample_rate(1, 128, 8000.)
+			.unwrap();
+	context.clone().register_thread(None, || ()).await.unwrap();
+
+	let mut value = 0.;
+	let node = context
+		.audio_worklet_node_from_fn(None, move |_, outputs, _| {
+			value += 1.;
+
+			for channel in outputs.iter_mut().flat_map(|output| output.iter_mut()) {
+				channel.fill(value);
+			}
+
+			true
+		})
+		.await
+		.unwrap();
+	node.connect_with_audio_node(&context.destination())
+		.unwrap();
+
+	let buffer = JsFuture::from(context.start_rendering().unwrap())
+		.await
+		.unwrap()
+		.unchecked_into::<AudioBuffer>();
+	let samples = buffer.get_channel_data(0).unwrap();
+	assert!(samples
+		.iter()
+		.all(|sample| (*sample - 1.).abs() < f32::EPSILON));
+}
+
+struct ConstantProcessor(f32);
+
+impl ExtendAudioWorkletProcessor for ConstantProcessor {
+	type Data = f32;
+
+	const PROCESS_BUFFERS: Option<ProcessBuffersFn<Self>> = Some(ProcessBuffersFn::new());
+
+	fn new(_: AudioWorkletProcessor, data: Option<Self::Data>, _: AudioWorkletNodeOptions) -> Self {
+		Self(data.unwrap())
+	}
+}
+
+impl ProcessBuffers for ConstantProcessor {
+	fn process_buffers(
+		&mut self,
+		inputs: &[&[&[f32]]],
+		outputs: &mut [&mut [&mut [f32]]],
+		_: &AudioParameters<'_>,
+	) -> bool {
+		assert!(inputs.is_empty());
+
+		for channel in outputs.iter_mut().flat_map(|output| output.iter_mut()) {
+			channel.fill(self.0);
+		}
+
+		true
+	}
+}
+
+#[wasm_bindgen_test]
+async fn render_offline() {
+	let channels = audio_worklet::render_offline::<ConstantProcessor>(8000., 256, 2, 42.)
+		.await
+		.unwrap();
+
+	assert_eq!(channels.len(), 2);
+
+	for channel in channels {
+		assert_eq!(channel.len(), 256);
+		assert!(channel
+			.iter()
+			.all(|sample| (*sample - 42.).abs() < f32::EPSILON));
+	}
+}
+
+struct PanicProcessor;
+
+impl ExtendAudioWorkletProcessor for PanicProcessor {
+	type Data = ();
+
+	const PROCESS_BUFFERS: Option<ProcessBuffersFn<Self>> = Some(ProcessBuffersFn::new());
+
+	fn new(_: AudioWorkletProcessor, _: Option<Self::Data>, _: AudioWorkletNodeOptions) -> Self {
+		Self
+	}
+}
+
+impl ProcessBuffers for PanicProcessor {
+	fn process_buffers(
+		&mut self,
+		_: &[&[&[f32]]],
+		outputs: &mut [&mut [&mut [f32]]],
+		_: &AudioParameters<'_>,
+	) -> bool {
+		for channel in outputs.iter_mut().flat_map(|output| output.iter_mut()) {
+			channel.fill(1.);
+		}
+
+		panic!("test")
+	}
+}
+
+#[wasm_bindgen_test]
+async fn processor_error() {
+	let context =
+		OfflineAudioContext::new_with_number_of_channels_and_length_and_sample_rate(1, 256, 8000.)
+			.unwrap();
+	context.clone().register_thread(None, || ()).await.unwrap();
+	context
+		.register_processor::<PanicProcessor>("panic")
+		.await
+		.unwrap();
+
+	let node = RustAudioWorkletNode::<PanicProcessor>::new(&context, "panic", (), None).unwrap();
+	let error = node.processor_error();
+	node.connect_with_audio_node(&context.destination())
+		.unwrap();
+
+	let buffer = JsFuture::from(context.start_rendering().unwrap())
+		.await
+		.unwrap()
+		.unchecked_into::<AudioBuffer>();
+	let samples = buffer.get_channel_data(0).unwrap();
+	assert!(samples.iter().all(|sample| sample.abs() < f32::EPSILON));
+
+	error.await;
+}
+
+struct DisposeProcessor(Option<Flag>);
+
+impl ExtendAudioWorkletProcessor for DisposeProcessor {
+	type Data = Flag;
+
+	fn new(_: AudioWorkletProcessor, data: Option<Self::Data>, _: AudioWorkletNodeOptions) -> Self {
+		Self(data)
+	}
+}
+
+impl Drop for DisposeProcessor {
+	fn drop(&mut self) {
+		self.0.take().unwrap().signal();
+	}
+}
+
+#[wasm_bindgen_test]
+async fn dispose() {
+	let context =
+		OfflineAudioContext::new_with_number_of_channels_and_length_and_sample_rate(1, 256, 8000.)
+			.unwrap();
+	context.clone().register_thread(None, || ()).await.unwrap();
+	context
+		.register_processor::<DisposeProcessor>("dispose")
+		.await
+		.unwrap();
+
+	let end = Flag::new();
+	let node =
+		RustAudioWorkletNode::<DisposeProcessor>::new(&context, "dispose", end.clone(), None)
+			.unwrap();
+	node.connect_with_audio_node(&context.destination())
+		.unwrap();
+	node.dispose();
+
+	JsFuture::from(context.start_rendering().unwrap())
+		.await
+		.unwrap();
+	end.await;
+}
+
 fn js_string(string: &str) -> JsString {
 	JsString::from_code_point(string.chars().map(u32::from).collect::<Vec<_>>().as_slice())
 		.expect("found invalid Unicode")
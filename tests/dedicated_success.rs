@@ -20,6 +20,8 @@ mod supported_block;
 	not(unsupported_spawn)
 ))]
 mod supported_spawn_success;
+#[cfg(target_family = "wasm")]
+mod sync_success;
 
 #[cfg(target_family = "wasm")]
 wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
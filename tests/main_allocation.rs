@@ -0,0 +1,11 @@
+#[cfg(all(
+	target_family = "wasm",
+	target_feature = "atomics",
+	feature = "detect-allocations",
+	debug_assertions,
+	not(unsupported_spawn)
+))]
+mod audio_worklet_allocation;
+
+#[cfg(target_family = "wasm")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
@@ -35,6 +35,8 @@ mod message_success;
 	)
 ))]
 mod supported_spawn_success;
+#[cfg(target_family = "wasm")]
+mod sync_success;
 mod test_processor;
 mod util;
 
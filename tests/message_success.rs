@@ -1,11 +1,26 @@
 #![cfg(test)]
 #![cfg(all(target_family = "wasm", feature = "message"))]
 
-use js_sys::{ArrayBuffer, Uint8Array};
+use std::collections::HashMap;
+
+use js_sys::WebAssembly::{Memory, Module};
+use js_sys::{ArrayBuffer, SharedArrayBuffer, Uint8Array};
+use wasm_bindgen::{JsCast, JsValue};
 use wasm_bindgen_test::wasm_bindgen_test;
-use web_thread::web;
-use web_thread::web::message::TransferableWrapper;
-use web_thread::web::{JoinHandleExt, ScopeExt};
+use web_sys::{HtmlCanvasElement, MediaStreamTrack};
+#[cfg(web_sys_unstable_apis)]
+use web_sys::{RtcEncodedAudioFrame, RtcEncodedVideoFrame};
+use web_thread::web::message::{
+	self, MessageErrorKind, MessageSend, SendWrapper, SerializableWrapper, TransferableBytes,
+	TransferableWrapper,
+};
+use web_thread::web::{BuilderExt, JoinHandleExt, ScopeExt, ScopedJoinHandleExt, YieldTime};
+use web_thread::{web, Builder};
+#[cfg(feature = "serde")]
+use {
+	serde::{Deserialize, Serialize},
+	web_thread::web::message::SerdeWrapper,
+};
 
 #[wasm_bindgen_test]
 async fn spawn() {
@@ -68,3 +83,900 @@ async fn scope() {
 
 	assert_eq!(buffer.byte_length(), 0);
 }
+
+#[cfg(feature = "derive")]
+#[derive(MessageSend)]
+struct Struct {
+	#[message(send)]
+	value: u8,
+	#[message(serialize)]
+	serialized: ArrayBuffer,
+	#[message(transfer)]
+	transferred: ArrayBuffer,
+	nested: Tuple,
+}
+
+#[cfg(feature = "derive")]
+#[derive(MessageSend)]
+struct Tuple(#[message(send)] u16, #[message(transfer)] ArrayBuffer);
+
+#[cfg(feature = "derive")]
+#[derive(Debug, MessageSend, PartialEq)]
+struct Unit;
+
+#[cfg(feature = "derive")]
+#[derive(MessageSend)]
+enum Enum {
+	Unit,
+	Value(#[message(send)] u8),
+	Transfer {
+		#[message(transfer)]
+		buffer: ArrayBuffer,
+	},
+}
+
+#[cfg(feature = "derive")]
+#[derive(MessageSend)]
+#[allow(clippy::struct_field_names)]
+struct Large {
+	#[message(send)]
+	field_0: u8,
+	#[message(send)]
+	field_1: u8,
+	#[message(send)]
+	field_2: u8,
+	#[message(send)]
+	field_3: u8,
+	#[message(send)]
+	field_4: u8,
+	#[message(send)]
+	field_5: u8,
+	#[message(send)]
+	field_6: u8,
+	#[message(send)]
+	field_7: u8,
+	#[message(send)]
+	field_8: u8,
+	#[message(send)]
+	field_9: u8,
+	#[message(send)]
+	field_10: u8,
+	#[message(send)]
+	field_11: u8,
+	#[message(serialize)]
+	field_12: ArrayBuffer,
+}
+
+fn buffer(value: u8) -> ArrayBuffer {
+	let buffer = ArrayBuffer::new(1);
+	Uint8Array::new(&buffer).copy_from(&[value]);
+	buffer
+}
+
+fn value(buffer: &ArrayBuffer) -> u8 {
+	Uint8Array::new(buffer).get_index(0)
+}
+
+fn round_trip<M: MessageSend>(message: M) -> (M, Vec<JsValue>) {
+	let mut transfer = Vec::new();
+	let raw = message.send(&mut transfer);
+
+	(M::receive(raw.serialize, raw.send), transfer)
+}
+
+#[cfg(feature = "derive")]
+#[wasm_bindgen_test]
+async fn derive() {
+	let transferred = buffer(2);
+	let nested = buffer(4);
+	web::spawn_with_message(
+		|message: Struct| async move {
+			assert_eq!(message.value, 1);
+			assert_eq!(value(&message.serialized), 3);
+			assert_eq!(value(&message.transferred), 2);
+			assert_eq!(message.nested.0, 5);
+			assert_eq!(value(&message.nested.1), 4);
+		},
+		Struct {
+			value: 1,
+			serialized: buffer(3),
+			transferred: transferred.clone(),
+			nested: Tuple(5, nested.clone()),
+		},
+	)
+	.join_async()
+	.await
+	.unwrap();
+
+	assert_eq!(transferred.byte_length(), 0);
+	assert_eq!(nested.byte_length(), 0);
+}
+
+#[cfg(feature = "derive")]
+#[wasm_bindgen_test]
+fn derive_unit() {
+	let (message, transfer) = round_trip(Unit);
+	assert_eq!(message, Unit);
+	assert!(transfer.is_empty());
+}
+
+#[cfg(feature = "derive")]
+#[wasm_bindgen_test]
+fn derive_enum() {
+	let (message, transfer) = round_trip(Enum::Unit);
+	assert!(matches!(message, Enum::Unit));
+	assert!(transfer.is_empty());
+
+	let (message, transfer) = round_trip(Enum::Value(42));
+	assert!(matches!(message, Enum::Value(42)));
+	assert!(transfer.is_empty());
+
+	let (message, transfer) = round_trip(Enum::Transfer { buffer: buffer(42) });
+	let Enum::Transfer { buffer } = message else {
+		panic!("wrong variant")
+	};
+	assert_eq!(value(&buffer), 42);
+	assert_eq!(transfer.len(), 1);
+}
+
+#[cfg(feature = "derive")]
+#[wasm_bindgen_test]
+async fn derive_enum_spawn() {
+	let transferred = buffer(42);
+	web::spawn_with_message(
+		|message| async move {
+			let Enum::Transfer { buffer } = message else {
+				panic!("wrong variant")
+			};
+			assert_eq!(value(&buffer), 42);
+		},
+		Enum::Transfer {
+			buffer: transferred.clone(),
+		},
+	)
+	.join_async()
+	.await
+	.unwrap();
+
+	assert_eq!(transferred.byte_length(), 0);
+}
+
+#[cfg(feature = "derive")]
+#[wasm_bindgen_test]
+fn derive_large() {
+	let (message, _) = round_trip(Large {
+		field_0: 0,
+		field_1: 1,
+		field_2: 2,
+		field_3: 3,
+		field_4: 4,
+		field_5: 5,
+		field_6: 6,
+		field_7: 7,
+		field_8: 8,
+		field_9: 9,
+		field_10: 10,
+		field_11: 11,
+		field_12: buffer(12),
+	});
+
+	assert_eq!(message.field_0, 0);
+	assert_eq!(message.field_11, 11);
+	assert_eq!(value(&message.field_12), 12);
+}
+
+#[cfg(feature = "derive")]
+mod reexport {
+	pub(super) use web_thread as renamed;
+}
+
+#[cfg(feature = "derive")]
+#[derive(MessageSend)]
+#[message(crate = reexport::renamed)]
+struct Renamed(#[message(send)] u8, #[message(transfer)] ArrayBuffer);
+
+#[cfg(feature = "derive")]
+#[wasm_bindgen_test]
+fn derive_crate() {
+	let (Renamed(number, buffer), transfer) = round_trip(Renamed(42, buffer(43)));
+
+	assert_eq!(number, 42);
+	assert_eq!(value(&buffer), 43);
+	assert_eq!(transfer.len(), 1);
+}
+
+#[cfg(feature = "derive")]
+#[wasm_bindgen_test]
+async fn macro_spawn() {
+	let serialized = buffer(1);
+	let transferred = buffer(2);
+	web::spawn_with_message(
+		|web::message_pattern!(serialized, TransferableWrapper(transferred), number, unit)| async move {
+			assert_eq!(value(&serialized), 1);
+			assert_eq!(value(&transferred), 2);
+			assert_eq!(number, 3);
+			assert_eq!(unit, Unit);
+		},
+		web::message!(
+			serialized.clone(),
+			TransferableWrapper(transferred.clone()),
+			3_u8,
+			Unit
+		),
+	)
+	.join_async()
+	.await
+	.unwrap();
+
+	assert_eq!(serialized.byte_length(), 1);
+	assert_eq!(transferred.byte_length(), 0);
+}
+
+#[wasm_bindgen_test]
+fn macro_round_trip() {
+	let (web::message_pattern!(TransferableWrapper(buffer), string), transfer) = round_trip(
+		web::message!(TransferableWrapper(buffer(42)), String::from("test")),
+	);
+
+	assert_eq!(value(&buffer), 42);
+	assert_eq!(string, "test");
+	assert_eq!(transfer.len(), 1);
+}
+
+#[wasm_bindgen_test]
+async fn vec_spawn() {
+	let buffers: Vec<_> = (0..10).map(buffer).collect();
+	web::spawn_with_message(
+		|message: Vec<TransferableWrapper<ArrayBuffer>>| async move {
+			assert_eq!(message.len(), 10);
+
+			for (index, TransferableWrapper(buffer)) in message.iter().enumerate() {
+				assert_eq!(usize::from(value(buffer)), index);
+			}
+		},
+		buffers
+			.iter()
+			.cloned()
+			.map(TransferableWrapper)
+			.collect::<Vec<_>>(),
+	)
+	.join_async()
+	.await
+	.unwrap();
+
+	for buffer in buffers {
+		assert_eq!(buffer.byte_length(), 0);
+	}
+}
+
+#[cfg(feature = "derive")]
+#[wasm_bindgen_test]
+fn vec_mixed() {
+	let (message, transfer) = round_trip(vec![
+		Enum::Unit,
+		Enum::Value(1),
+		Enum::Transfer { buffer: buffer(2) },
+		Enum::Unit,
+		Enum::Value(4),
+	]);
+
+	let [Enum::Unit, Enum::Value(1), Enum::Transfer { buffer }, Enum::Unit, Enum::Value(4)] =
+		message.as_slice()
+	else {
+		panic!("wrong variants")
+	};
+	assert_eq!(value(buffer), 2);
+	assert_eq!(transfer.len(), 1);
+
+	let (message, transfer) = round_trip(vec![Unit, Unit, Unit]);
+	assert_eq!(message, [Unit, Unit, Unit]);
+	assert!(transfer.is_empty());
+
+	let (message, _) = round_trip(Vec::<Unit>::new());
+	assert!(message.is_empty());
+}
+
+#[cfg(feature = "derive")]
+#[wasm_bindgen_test]
+fn option() {
+	let (message, _) = round_trip(Some(Unit));
+	assert_eq!(message, Some(Unit));
+
+	let (message, _) = round_trip(None::<Unit>);
+	assert_eq!(message, None);
+
+	let (message, transfer) = round_trip(vec![None, Some(TransferableWrapper(buffer(1))), None]);
+	let [None, Some(TransferableWrapper(buffer)), None] = message.as_slice() else {
+		panic!("wrong values")
+	};
+	assert_eq!(value(buffer), 1);
+	assert_eq!(transfer.len(), 1);
+}
+
+#[wasm_bindgen_test]
+fn boxed() {
+	let (message, transfer) = round_trip(Box::new(TransferableWrapper(buffer(1))));
+	assert_eq!(value(&message.0), 1);
+	assert_eq!(transfer.len(), 1);
+}
+
+#[wasm_bindgen_test]
+fn result() {
+	let (message, transfer) = round_trip(Ok::<_, SendWrapper<u8>>(TransferableWrapper(buffer(1))));
+	assert_eq!(value(&message.unwrap().0), 1);
+	assert_eq!(transfer.len(), 1);
+
+	let (message, transfer) = round_trip(Err::<TransferableWrapper<ArrayBuffer>, _>(SendWrapper(
+		2_u8,
+	)));
+	assert_eq!(message.unwrap_err().0, 2);
+	assert!(transfer.is_empty());
+}
+
+#[wasm_bindgen_test]
+async fn hash_map_spawn() {
+	let buffers: Vec<_> = (0..5).map(buffer).collect();
+	web::spawn_with_message(
+		|message: HashMap<u8, Option<TransferableWrapper<ArrayBuffer>>>| async move {
+			assert_eq!(message.len(), 6);
+
+			for (key, buffer) in message {
+				if key == 5 {
+					assert!(buffer.is_none());
+				} else {
+					assert_eq!(value(&buffer.unwrap().0), key);
+				}
+			}
+		},
+		buffers
+			.iter()
+			.cloned()
+			.map(|buffer| (value(&buffer), Some(TransferableWrapper(buffer))))
+			.chain([(5, None)])
+			.collect::<HashMap<_, _>>(),
+	)
+	.join_async()
+	.await
+	.unwrap();
+
+	for buffer in buffers {
+		assert_eq!(buffer.byte_length(), 0);
+	}
+}
+
+#[wasm_bindgen_test]
+async fn channel_receiver() {
+	let (sender, receiver) =
+		message::channel::<(TransferableWrapper<ArrayBuffer>, SendWrapper<u8>)>();
+	let mut handle = web::spawn_with_message(
+		|mut receiver| async move {
+			let mut count = 0;
+
+			while let Some((TransferableWrapper(buffer), SendWrapper(number))) =
+				receiver.recv().await
+			{
+				assert_eq!(value(&buffer), number);
+				count += 1;
+			}
+
+			count
+		},
+		receiver,
+	);
+
+	let buffers: Vec<_> = (0..10).map(buffer).collect();
+
+	for (number, buffer) in (0..).zip(&buffers) {
+		sender
+			.post_message((TransferableWrapper(buffer.clone()), SendWrapper(number)))
+			.unwrap();
+	}
+
+	drop(sender);
+	assert_eq!(handle.join_async().await.unwrap(), 10);
+
+	for buffer in buffers {
+		assert_eq!(buffer.byte_length(), 0);
+	}
+}
+
+#[wasm_bindgen_test]
+async fn channel_sender() {
+	let (sender, mut receiver) = message::channel::<TransferableWrapper<ArrayBuffer>>();
+	web::spawn_with_message(
+		|sender| async move {
+			for index in 0..10 {
+				sender
+					.post_message(TransferableWrapper(buffer(index)))
+					.unwrap();
+			}
+		},
+		sender,
+	)
+	.join_async()
+	.await
+	.unwrap();
+
+	for index in 0..10 {
+		let TransferableWrapper(buffer) = receiver.recv().await.unwrap();
+		assert_eq!(value(&buffer), index);
+	}
+
+	assert!(receiver.recv().await.is_none());
+}
+
+#[wasm_bindgen_test]
+async fn channel_forward_receiver() {
+	let (sender, mut receiver) = message::channel::<TransferableWrapper<ArrayBuffer>>();
+	let buffers: Vec<_> = (0..10).map(buffer).collect();
+
+	for buffer in &buffers {
+		sender
+			.post_message(TransferableWrapper(buffer.clone()))
+			.unwrap();
+	}
+
+	drop(sender);
+
+	// Already received messages and the closed channel are forwarded as well.
+	let TransferableWrapper(first) = receiver.recv().await.unwrap();
+	assert_eq!(value(&first), 0);
+	web::yield_now_async(YieldTime::UserBlocking).await;
+
+	let count = web::spawn_with_message(
+		|mut receiver| async move {
+			let mut count = 1;
+
+			while let Some(TransferableWrapper(buffer)) = receiver.recv().await {
+				assert_eq!(value(&buffer), count);
+				count += 1;
+			}
+
+			count
+		},
+		receiver,
+	)
+	.join_async()
+	.await
+	.unwrap();
+	assert_eq!(count, 10);
+
+	for buffer in buffers {
+		assert_eq!(buffer.byte_length(), 0);
+	}
+}
+
+#[wasm_bindgen_test]
+fn channel_disconnected() {
+	let (sender, receiver) = message::channel::<SendWrapper<u8>>();
+	drop(receiver);
+
+	let error = sender.post_message(SendWrapper(42)).unwrap_err();
+	assert_eq!(error.kind, MessageErrorKind::Closed);
+	assert_eq!(error.name, None);
+	assert_eq!(error.message.0, 42);
+}
+
+#[wasm_bindgen_test]
+async fn channel_detached() {
+	let (sender, mut receiver) =
+		message::channel::<(TransferableWrapper<ArrayBuffer>, SendWrapper<u8>)>();
+	let buffer = buffer(42);
+
+	sender
+		.post_message((TransferableWrapper(buffer.clone()), SendWrapper(42)))
+		.unwrap();
+	let error = sender
+		.post_message((TransferableWrapper(buffer), SendWrapper(43)))
+		.unwrap_err();
+	assert_eq!(error.kind, MessageErrorKind::Detached);
+	assert_eq!(error.name.as_deref(), Some("DataCloneError"));
+	assert_eq!(error.message.1 .0, 43);
+
+	let (TransferableWrapper(transferred), SendWrapper(number)) = receiver.recv().await.unwrap();
+	assert_eq!(value(&transferred), 42);
+	assert_eq!(number, 42);
+
+	// The failed message is not received.
+	drop(sender);
+	assert!(receiver.recv().await.is_none());
+}
+
+#[wasm_bindgen_test]
+async fn channel_retry() {
+	let (sender, mut receiver) = message::channel::<[TransferableWrapper<ArrayBuffer>; 2]>();
+	let buffer = buffer(42);
+
+	let error = sender
+		.post_message([
+			TransferableWrapper(buffer.clone()),
+			TransferableWrapper(buffer),
+		])
+		.unwrap_err();
+	assert_eq!(error.kind, MessageErrorKind::DuplicateTransfer);
+	assert_eq!(error.name.as_deref(), Some("DataCloneError"));
+
+	// Nothing was transferred, so the returned message can be sent again.
+	let [buffer, _] = error.message;
+	sender
+		.post_message([buffer, TransferableWrapper(ArrayBuffer::new(1))])
+		.unwrap();
+
+	let [TransferableWrapper(transferred), _] = receiver.recv().await.unwrap();
+	assert_eq!(value(&transferred), 42);
+}
+
+#[wasm_bindgen_test]
+async fn spawn_error() {
+	let buffer = buffer(42);
+
+	let error = Builder::new()
+		.spawn_with_message(
+			|_| async {},
+			[
+				TransferableWrapper(buffer.clone()),
+				TransferableWrapper(buffer),
+			],
+		)
+		.unwrap_err();
+	assert_eq!(error.kind, MessageErrorKind::DuplicateTransfer);
+	assert_eq!(error.name.as_deref(), Some("DataCloneError"));
+
+	// Nothing was transferred, so the returned message can be sent again.
+	let [buffer, _] = error.message;
+	let number = web::spawn_with_message(
+		|TransferableWrapper(buffer)| async move { value(&buffer) },
+		buffer,
+	)
+	.join_async()
+	.await
+	.unwrap();
+	assert_eq!(number, 42);
+}
+
+#[wasm_bindgen_test]
+async fn post_message() {
+	let mut handle = web::spawn_async(|| async {
+		let mut numbers = Vec::new();
+
+		for _ in 0..10 {
+			let (TransferableWrapper(buffer), SendWrapper(number)) =
+				message::receive::<(TransferableWrapper<ArrayBuffer>, SendWrapper<u8>)>().await;
+			assert_eq!(value(&buffer), number);
+			numbers.push(number);
+		}
+
+		numbers
+	});
+
+	let buffers: Vec<_> = (0..10).map(buffer).collect();
+
+	for (number, buffer) in (0..).zip(&buffers) {
+		handle
+			.post_message((TransferableWrapper(buffer.clone()), SendWrapper(number)))
+			.unwrap();
+	}
+
+	assert_eq!(
+		handle.join_async().await.unwrap(),
+		(0..10).collect::<Vec<_>>()
+	);
+
+	for buffer in buffers {
+		assert_eq!(buffer.byte_length(), 0);
+	}
+}
+
+#[wasm_bindgen_test]
+async fn post_message_nested() {
+	web::spawn_async(|| async {
+		let mut handle = web::spawn_async(|| async {
+			let TransferableWrapper(buffer) =
+				message::receive::<TransferableWrapper<ArrayBuffer>>().await;
+			value(&buffer)
+		});
+
+		handle
+			.post_message(TransferableWrapper(buffer(42)))
+			.unwrap();
+		assert_eq!(handle.join_async().await.unwrap(), 42);
+	})
+	.join_async()
+	.await
+	.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn post_message_types() {
+	let mut handle = web::spawn_async(|| async {
+		let SendWrapper(number) = message::receive::<SendWrapper<u8>>().await;
+		let TransferableWrapper(buffer) =
+			message::receive::<TransferableWrapper<ArrayBuffer>>().await;
+		(value(&buffer), number)
+	});
+
+	handle
+		.post_message(TransferableWrapper(buffer(42)))
+		.unwrap();
+	handle.post_message(SendWrapper(43_u8)).unwrap();
+	assert_eq!(handle.join_async().await.unwrap(), (42, 43));
+}
+
+#[wasm_bindgen_test]
+async fn post_message_finished() {
+	let mut handle = web::spawn_async(|| async {});
+	handle.join_async().await.unwrap();
+
+	let error = handle.post_message(SendWrapper(42)).unwrap_err();
+	assert_eq!(error.kind, MessageErrorKind::Closed);
+	assert_eq!(error.message.0, 42);
+}
+
+#[wasm_bindgen_test]
+async fn post_message_error() {
+	let mut handle = web::spawn_async(|| async {
+		let TransferableWrapper(buffer) =
+			message::receive::<TransferableWrapper<ArrayBuffer>>().await;
+		value(&buffer)
+	});
+	let buffer = buffer(42);
+
+	let error = handle
+		.post_message([
+			TransferableWrapper(buffer.clone()),
+			TransferableWrapper(buffer),
+		])
+		.unwrap_err();
+	assert_eq!(error.kind, MessageErrorKind::DuplicateTransfer);
+
+	let [buffer, _] = error.message;
+	handle.post_message(buffer).unwrap();
+	assert_eq!(handle.join_async().await.unwrap(), 42);
+}
+
+#[wasm_bindgen_test]
+async fn returning() {
+	let (TransferableWrapper(buffer), SendWrapper(number)) =
+		web::spawn_returning_message(|| async {
+			(TransferableWrapper(buffer(42)), SendWrapper(42))
+		})
+		.join_async()
+		.await
+		.unwrap();
+
+	assert_eq!(value(&buffer), 42);
+	assert_eq!(number, 42);
+}
+
+#[wasm_bindgen_test]
+async fn returning_nested() {
+	web::spawn_async(|| async {
+		let TransferableWrapper(buffer) =
+			web::spawn_returning_message(|| async { TransferableWrapper(buffer(42)) })
+				.join_async()
+				.await
+				.unwrap();
+
+		assert_eq!(value(&buffer), 42);
+	})
+	.join_async()
+	.await
+	.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn returning_scope() {
+	let number = web::scope_async(|scope| async {
+		let TransferableWrapper(buffer) = scope
+			.spawn_returning_message(|| async { TransferableWrapper(buffer(42)) })
+			.join_async()
+			.await
+			.unwrap();
+
+		value(&buffer)
+	})
+	.await;
+
+	assert_eq!(number, 42);
+}
+
+#[wasm_bindgen_test]
+async fn returning_error() {
+	// Transferring the same buffer twice fails to send the output.
+	web::spawn_returning_message(|| async {
+		let buffer = buffer(42);
+		[
+			TransferableWrapper(buffer.clone()),
+			TransferableWrapper(buffer),
+		]
+	})
+	.join_async()
+	.await
+	.unwrap_err();
+}
+
+#[cfg(feature = "serde")]
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Data {
+	name: String,
+	values: Vec<u32>,
+	map: HashMap<String, Option<f64>>,
+}
+
+#[cfg(feature = "serde")]
+#[wasm_bindgen_test]
+async fn serde() {
+	let data = Data {
+		name: String::from("test"),
+		values: vec![1, 2, 3],
+		map: HashMap::from([(String::from("a"), Some(1.5)), (String::from("b"), None)]),
+	};
+	let (SerdeWrapper(received), transfer) = round_trip(SerdeWrapper(data));
+	assert!(transfer.is_empty());
+	assert_eq!(
+		received,
+		Data {
+			name: String::from("test"),
+			values: vec![1, 2, 3],
+			map: HashMap::from([(String::from("a"), Some(1.5)), (String::from("b"), None)]),
+		}
+	);
+
+	let SerdeWrapper(values) = web::spawn_returning_message(|| async {
+		SerdeWrapper(vec![String::from("a"), String::from("b")])
+	})
+	.join_async()
+	.await
+	.unwrap();
+	assert_eq!(values, [String::from("a"), String::from("b")]);
+}
+
+#[cfg(feature = "serde")]
+#[wasm_bindgen_test]
+async fn serde_undefined() {
+	let SerdeWrapper(received) = SerdeWrapper::<Option<u8>>::receive(None, None);
+	assert_eq!(received, None);
+	let SerdeWrapper(()) = SerdeWrapper::<()>::receive(None, None);
+
+	web::spawn_with_message(
+		|SerdeWrapper(received): SerdeWrapper<Option<u8>>| async move {
+			assert_eq!(received, None);
+		},
+		SerdeWrapper(None),
+	)
+	.join_async()
+	.await
+	.unwrap();
+
+	let SerdeWrapper(()) = web::spawn_returning_message(|| async { SerdeWrapper(()) })
+		.join_async()
+		.await
+		.unwrap();
+}
+
+#[wasm_bindgen_test]
+fn transferable_bytes() {
+	let bytes = TransferableBytes::copy_from_slice(&[1, 2, 3]);
+	assert_eq!(bytes.len(), 3);
+	assert!(!bytes.is_empty());
+	let buffer = bytes.into_array_buffer();
+
+	let (received, transfer) = round_trip(TransferableBytes::from(buffer.clone()));
+	let [transferred] = transfer.as_slice() else {
+		panic!("expected one transferred value")
+	};
+	assert_eq!(transferred, &JsValue::from(buffer));
+	assert_eq!(received.to_vec(), [1, 2, 3]);
+}
+
+#[wasm_bindgen_test]
+async fn transferable_bytes_spawn() {
+	let buffer = TransferableBytes::copy_from_slice(&[42; 100]).into_array_buffer();
+	web::spawn_with_message(
+		|bytes: TransferableBytes| async move {
+			assert_eq!(bytes.into_uint8_array().to_vec(), [42; 100]);
+		},
+		TransferableBytes::from(buffer.clone()),
+	)
+	.join_async()
+	.await
+	.unwrap();
+
+	assert_eq!(buffer.byte_length(), 0);
+
+	let bytes =
+		web::spawn_returning_message(|| async { TransferableBytes::copy_from_slice(&[42; 100]) })
+			.join_async()
+			.await
+			.unwrap();
+	assert_eq!(bytes.to_vec(), [42; 100]);
+}
+
+#[wasm_bindgen_test]
+async fn wasm() {
+	// Smallest valid Wasm module.
+	let module = Module::new(&Uint8Array::from(
+		[0, 0x61, 0x73, 0x6D, 1, 0, 0, 0].as_slice(),
+	))
+	.unwrap();
+	let memory: Memory = wasm_bindgen::memory().unchecked_into();
+	let length = memory
+		.buffer()
+		.unchecked_into::<SharedArrayBuffer>()
+		.byte_length();
+	let buffer = SharedArrayBuffer::new(1);
+	Uint8Array::new(&buffer).copy_from(&[42]);
+
+	web::spawn_with_message(
+		move |(
+			SerializableWrapper(module),
+			SerializableWrapper(memory),
+			SerializableWrapper(buffer),
+		)| async move {
+			assert_eq!(Module::exports(&module).length(), 0);
+			assert!(
+				memory
+					.buffer()
+					.unchecked_into::<SharedArrayBuffer>()
+					.byte_length() >= length
+			);
+
+			let array = Uint8Array::new(&buffer);
+			assert_eq!(array.get_index(0), 42);
+			array.set_index(0, 43);
+		},
+		(
+			SerializableWrapper(module),
+			SerializableWrapper(memory),
+			SerializableWrapper(buffer.clone()),
+		),
+	)
+	.join_async()
+	.await
+	.unwrap();
+
+	assert_eq!(Uint8Array::new(&buffer).get_index(0), 43);
+}
+
+#[wasm_bindgen_test]
+async fn media_stream_track() {
+	// Canvases can only be captured with a `Document`.
+	let Some(window) = web_sys::window() else {
+		return;
+	};
+	let canvas: HtmlCanvasElement = window
+		.document()
+		.unwrap()
+		.create_element("canvas")
+		.unwrap()
+		.unchecked_into();
+	let track: MediaStreamTrack = canvas
+		.capture_stream()
+		.unwrap()
+		.get_video_tracks()
+		.get(0)
+		.unchecked_into();
+
+	let (sender, mut receiver) = message::channel::<TransferableWrapper<MediaStreamTrack>>();
+
+	// Not all browsers support transferring `MediaStreamTrack`s.
+	if let Err(error) = sender.post_message(TransferableWrapper(track.clone())) {
+		assert_eq!(error.kind, MessageErrorKind::DataClone);
+		return;
+	}
+
+	let TransferableWrapper(transferred) = receiver.recv().await.unwrap();
+	assert_ne!(transferred, track);
+	assert_eq!(transferred.kind(), "video");
+}
+
+#[cfg(web_sys_unstable_apis)]
+#[wasm_bindgen_test]
+fn rtc_encoded_frame() {
+	// Encoded frames can only be obtained inside an `RTCRtpScriptTransform` of an
+	// established WebRTC connection, so sending them is not exercised here.
+	fn assert_message<M: MessageSend>() {}
+
+	assert_message::<SerializableWrapper<RtcEncodedAudioFrame>>();
+	assert_message::<SerializableWrapper<RtcEncodedVideoFrame>>();
+}
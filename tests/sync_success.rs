@@ -0,0 +1,57 @@
+#![cfg(test)]
+#![cfg(target_family = "wasm")]
+
+use wasm_bindgen_test::wasm_bindgen_test;
+use web_thread::web;
+use web_thread::web::sync::{SpinLimitError, SpinMutex};
+#[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+use web_thread::web::JoinHandleExt;
+
+#[wasm_bindgen_test]
+fn spin_mutex() {
+	static MUTEX: SpinMutex<u32> = SpinMutex::new(0);
+
+	*MUTEX.lock().unwrap() += 1;
+	*MUTEX.try_lock().unwrap() += 1;
+
+	assert_eq!(*MUTEX.lock().unwrap(), 2);
+	assert_eq!(MUTEX.spin_limit(), SpinMutex::<()>::DEFAULT_SPIN_LIMIT);
+}
+
+#[wasm_bindgen_test]
+fn spin_mutex_locked() {
+	let mutex = SpinMutex::with_spin_limit(0, 10);
+	assert_eq!(mutex.spin_limit(), 10);
+
+	let guard = mutex.lock().unwrap();
+	assert!(mutex.try_lock().is_none());
+
+	if !web::has_block_support() {
+		assert_eq!(mutex.lock().unwrap_err(), SpinLimitError);
+	}
+
+	drop(guard);
+	assert_eq!(mutex.into_inner(), 0);
+}
+
+#[cfg(all(target_feature = "atomics", not(unsupported_spawn)))]
+#[wasm_bindgen_test]
+async fn spin_mutex_contention() {
+	static MUTEX: SpinMutex<u32> = SpinMutex::with_spin_limit(0, u32::MAX);
+
+	let mut handles: Vec<_> = (0..4)
+		.map(|_| {
+			web_thread::spawn(|| {
+				for _ in 0..1000 {
+					*MUTEX.lock().unwrap() += 1;
+				}
+			})
+		})
+		.collect();
+
+	for handle in &mut handles {
+		handle.join_async().await.unwrap();
+	}
+
+	assert_eq!(*MUTEX.lock().unwrap(), 4000);
+}
@@ -35,6 +35,7 @@ const fn basic() {
 
 #[cfg(target_family = "wasm")]
 #[wasm_bindgen_test]
+#[allow(clippy::too_many_lines)]
 const fn web() {
 	use static_assertions::assert_obj_safe;
 	use web_thread::web::{
@@ -66,6 +67,52 @@ const fn web() {
 
 	assert_obj_safe!(JoinHandleExt<()>, ScopedJoinHandleExt<'_, ()>);
 
+	{
+		use std::cell::Cell;
+		use std::error::Error;
+		use std::fmt::Display;
+		use std::rc::Rc;
+
+		use web_thread::web::sync::{SpinLimitError, SpinMutex, SpinMutexGuard};
+
+		assert_impl_all!(SpinMutex<()>: Debug, Default, Send, Sync, Unpin, UnwindSafe);
+		assert_not_impl_any!(SpinMutex<()>: Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, RefUnwindSafe);
+		assert_not_impl_any!(SpinMutex<Rc<()>>: Send, Sync);
+
+		assert_impl_all!(SpinMutexGuard<'_, ()>: Debug, Send, Sync, Unpin);
+		assert_not_impl_any!(SpinMutexGuard<'_, ()>: Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, RefUnwindSafe, UnwindSafe);
+		assert_not_impl_any!(SpinMutexGuard<'_, Cell<()>>: Sync);
+
+		assert_impl_all!(SpinLimitError: Clone, Copy, Debug, Display, Error, Eq, PartialEq, Send, Sync, Unpin, RefUnwindSafe, UnwindSafe);
+		assert_not_impl_any!(SpinLimitError: Hash, Ord, PartialOrd);
+	}
+
+	#[cfg(feature = "message")]
+	{
+		use std::error::Error;
+		use std::fmt::Display;
+
+		use web_thread::web::message::{
+			MessageError, MessageErrorKind, PortReceiver, PortSender, SendWrapper,
+			TransferableBytes,
+		};
+
+		assert_impl_all!(MessageError<()>: Debug, Display, Error, Send, Sync, Unpin);
+		assert_not_impl_any!(MessageError<()>: Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, RefUnwindSafe, UnwindSafe);
+
+		assert_impl_all!(MessageErrorKind: Clone, Copy, Debug, Eq, Hash, PartialEq, Send, Sync, Unpin, RefUnwindSafe, UnwindSafe);
+		assert_not_impl_any!(MessageErrorKind: Ord, PartialOrd);
+
+		assert_impl_all!(PortSender<SendWrapper<()>>: Debug, Unpin, RefUnwindSafe, UnwindSafe);
+		assert_not_impl_any!(PortSender<SendWrapper<()>>: Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, Send, Sync);
+
+		assert_impl_all!(PortReceiver<SendWrapper<()>>: Debug, Unpin);
+		assert_not_impl_any!(PortReceiver<SendWrapper<()>>: Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, Send, Sync, RefUnwindSafe, UnwindSafe);
+
+		assert_impl_all!(TransferableBytes: Debug, Unpin, RefUnwindSafe, UnwindSafe);
+		assert_not_impl_any!(TransferableBytes: Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, Send, Sync);
+	}
+
 	#[cfg(feature = "audio-worklet")]
 	{
 		use std::error::Error;
@@ -73,8 +120,12 @@ const fn web() {
 
 		use web_sys::{AudioWorkletNodeOptions, AudioWorkletProcessor};
 		use web_thread::web::audio_worklet::{
-			AudioWorkletHandle, AudioWorkletNodeError, ExtendAudioWorkletProcessor,
-			RegisterThreadFuture, ReleaseError,
+			AudioParamDescriptor, AudioParameters, AudioParametersIter, AudioWorkletHandle,
+			AudioWorkletNodeError, AudioWorkletNodeFromFnFuture, AutomationRate, EventQueue,
+			EventQueueConsumer, EventQueueDrain, EventQueueProducer, ExtendAudioWorkletProcessor,
+			ProcessBuffersFn, ProcessorErrorFuture, RegisterProcessorFuture, RegisterThreadFuture,
+			ReleaseError, RingBuffer, RingBufferConsumer, RingBufferProducer, RustAudioWorkletNode,
+			Signal, SignalFuture, WaitForSpaceFuture,
 		};
 
 		#[allow(dead_code)]
@@ -95,6 +146,12 @@ const fn web() {
 		assert_impl_all!(RegisterThreadFuture: Debug, Unpin, RefUnwindSafe);
 		assert_not_impl_any!(RegisterThreadFuture: Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, Send, Sync, UnwindSafe);
 
+		assert_impl_all!(RegisterProcessorFuture: Debug, Unpin, RefUnwindSafe);
+		assert_not_impl_any!(RegisterProcessorFuture: Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, Send, Sync, UnwindSafe);
+
+		assert_impl_all!(AudioWorkletNodeFromFnFuture: Debug, Unpin, RefUnwindSafe);
+		assert_not_impl_any!(AudioWorkletNodeFromFnFuture: Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, Send, Sync, UnwindSafe);
+
 		assert_impl_all!(AudioWorkletHandle: Debug, Send, Sync, Unpin, RefUnwindSafe, UnwindSafe);
 		assert_not_impl_any!(AudioWorkletHandle: Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd);
 
@@ -103,5 +160,72 @@ const fn web() {
 
 		assert_impl_all!(ReleaseError: Debug, Display, Error, Send, Sync, Unpin, RefUnwindSafe, UnwindSafe);
 		assert_not_impl_any!(ReleaseError: Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd);
+
+		assert_impl_all!(Signal: Debug, Default, Send, Sync, Unpin, RefUnwindSafe, UnwindSafe);
+		assert_not_impl_any!(Signal: Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd);
+
+		assert_impl_all!(SignalFuture<'_>: Debug, Unpin, RefUnwindSafe, UnwindSafe);
+		assert_not_impl_any!(SignalFuture<'_>: Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, Send, Sync);
+
+		assert_impl_all!(RingBuffer<()>: Debug, Send, Sync, Unpin);
+		assert_not_impl_any!(RingBuffer<()>: Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, RefUnwindSafe, UnwindSafe);
+		assert_not_impl_any!(RingBuffer<*const ()>: Send, Sync);
+
+		assert_impl_all!(RingBufferProducer<()>: Debug, Send, Sync, Unpin);
+		assert_not_impl_any!(RingBufferProducer<()>: Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, RefUnwindSafe, UnwindSafe);
+		assert_not_impl_any!(RingBufferProducer<*const ()>: Send, Sync);
+
+		assert_impl_all!(RingBufferConsumer<()>: Debug, Send, Sync, Unpin);
+		assert_not_impl_any!(RingBufferConsumer<()>: Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, RefUnwindSafe, UnwindSafe);
+		assert_not_impl_any!(RingBufferConsumer<*const ()>: Send, Sync);
+
+		assert_impl_all!(EventQueue<()>: Debug, Send, Sync, Unpin);
+		assert_not_impl_any!(EventQueue<()>: Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, RefUnwindSafe, UnwindSafe);
+		assert_not_impl_any!(EventQueue<*const ()>: Send, Sync);
+
+		assert_impl_all!(EventQueueProducer<()>: Clone, Debug, Send, Sync, Unpin);
+		assert_not_impl_any!(EventQueueProducer<()>: Copy, Hash, Eq, PartialEq, Ord, PartialOrd, RefUnwindSafe, UnwindSafe);
+		assert_not_impl_any!(EventQueueProducer<*const ()>: Send, Sync);
+
+		assert_impl_all!(EventQueueConsumer<()>: Debug, Send, Sync, Unpin);
+		assert_not_impl_any!(EventQueueConsumer<()>: Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, RefUnwindSafe, UnwindSafe);
+		assert_not_impl_any!(EventQueueConsumer<*const ()>: Send, Sync);
+
+		assert_impl_all!(EventQueueDrain<'_, ()>: Debug, Send, Sync, Unpin);
+		assert_not_impl_any!(EventQueueDrain<'_, ()>: Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, UnwindSafe);
+
+		assert_impl_all!(AudioParamDescriptor: Clone, Copy, Debug, PartialEq, Send, Sync, Unpin, RefUnwindSafe, UnwindSafe);
+		assert_not_impl_any!(AudioParamDescriptor: Default, Hash, Eq, Ord, PartialOrd);
+
+		assert_impl_all!(AutomationRate: Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Send, Sync, Unpin, RefUnwindSafe, UnwindSafe);
+		assert_not_impl_any!(AutomationRate: Ord, PartialOrd);
+
+		assert_impl_all!(AudioParameters<'_>: Clone, Copy, Debug, Send, Sync, Unpin, RefUnwindSafe, UnwindSafe);
+		assert_not_impl_any!(AudioParameters<'_>: Hash, Eq, PartialEq, Ord, PartialOrd);
+
+		assert_impl_all!(AudioParametersIter<'_>: Clone, Debug, Send, Sync, Unpin, RefUnwindSafe, UnwindSafe);
+		assert_not_impl_any!(AudioParametersIter<'_>: Copy, Hash, Eq, PartialEq, Ord, PartialOrd);
+
+		assert_impl_all!(ProcessBuffersFn<TestProcessor>: Clone, Copy, Debug, Send, Sync, Unpin, RefUnwindSafe, UnwindSafe);
+		assert_not_impl_any!(ProcessBuffersFn<TestProcessor>: Default, Hash, Eq, PartialEq, Ord, PartialOrd);
+
+		assert_impl_all!(WaitForSpaceFuture<'_, ()>: Debug, Unpin);
+		assert_not_impl_any!(WaitForSpaceFuture<'_, ()>: Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, Send, Sync, RefUnwindSafe, UnwindSafe);
+
+		assert_impl_all!(RustAudioWorkletNode<TestProcessor>: Clone, Debug, Unpin, RefUnwindSafe, UnwindSafe);
+		assert_not_impl_any!(RustAudioWorkletNode<TestProcessor>: Copy, Hash, Eq, PartialEq, Ord, PartialOrd, Send, Sync);
+
+		assert_impl_all!(ProcessorErrorFuture: Debug, Unpin, RefUnwindSafe, UnwindSafe);
+		assert_not_impl_any!(ProcessorErrorFuture: Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, Send, Sync);
+
+		#[cfg(feature = "detect-allocations")]
+		{
+			use std::alloc::System;
+
+			use web_thread::web::audio_worklet::DetectingAllocator;
+
+			assert_impl_all!(DetectingAllocator<System>: Clone, Copy, Debug, Default, Send, Sync, Unpin, RefUnwindSafe, UnwindSafe);
+			assert_not_impl_any!(DetectingAllocator<System>: Hash, Eq, PartialEq, Ord, PartialOrd);
+		}
 	}
 }
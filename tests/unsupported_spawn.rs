@@ -4,6 +4,11 @@
 use wasm_bindgen_test::wasm_bindgen_test;
 use web_thread::web::{BuilderExt, ScopeExt};
 use web_thread::{web, Builder};
+#[cfg(feature = "message")]
+use {
+	std::io::ErrorKind,
+	web_thread::web::message::{MessageErrorKind, SendWrapper},
+};
 
 #[wasm_bindgen_test]
 #[should_panic = "operation not supported on this platform without the atomics target feature and \
@@ -41,6 +46,17 @@ fn builder_async() {
 		.unwrap();
 }
 
+#[cfg(feature = "message")]
+#[wasm_bindgen_test]
+fn builder_with_message() {
+	let error = Builder::new()
+		.spawn_with_message(|_| async {}, SendWrapper(42))
+		.unwrap_err();
+	assert_eq!(error.kind, MessageErrorKind::Other);
+	assert_eq!(error.error.kind(), ErrorKind::Unsupported);
+	assert_eq!(error.message.0, 42);
+}
+
 #[wasm_bindgen_test]
 #[should_panic = "operation not supported on this platform without the atomics target feature and \
                   cross-origin isolation"]